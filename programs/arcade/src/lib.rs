@@ -1,15 +1,81 @@
 use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_lang::solana_program::ed25519_program;
 use anchor_lang::solana_program::entrypoint::ProgramResult;
 use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
 
 use std::mem;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
-// AS OF October 13th 2022 at 3:20 PM the price of solana is $32.06
-// That means that currently $0.25 is 0.00779788 SOL
+// Unless the arcade's authority configures otherwise plays cost 25 US cents, converted to lamports with the arcade's SOL/USD
+// price feed when a player pays.
+const DEFAULT_PLAY_FEE_CENTS: u64 = 25;
 
-const TWENTY_FIVE_CENTS: u64 = (0.0779766 * (LAMPORTS_PER_SOL as f32)) as u64;
+// Prices older than this many seconds, or whose confidence interval is wider than this many basis points of the price, are
+// rejected unless the arcade's authority configures otherwise.
+const DEFAULT_MAX_PRICE_AGE_SECS: u32 = 60;
+const DEFAULT_MAX_PRICE_CONFIDENCE_BPS: u16 = 100;
+
+// Plays paid from prepaid credits get this discount (in basis points of the price), and credits are bought at least this many
+// lamports at a time, unless the arcade's authority configures otherwise.
+const DEFAULT_CREDIT_DISCOUNT_BPS: u16 = 500;
+const DEFAULT_MIN_CREDIT_TOP_UP_LAMPORTS: u64 = 1_000_000_000;
+
+// Winners have this many seconds to cash out a pot before anybody can send it back to its game, unless the arcade's authority
+// configures otherwise.
+const DEFAULT_POT_CLAIM_WINDOW_SECS: u32 = 90 * 24 * 60 * 60;
+
+// The arcade takes no fee (in basis points of each payout) for its treasury unless the arcade's authority configures otherwise.
+const DEFAULT_ARCADE_FEE_BPS: u16 = 0;
+
+// Players get this many seconds at the front of a queue before anybody can skip them, unless the game's owner configures
+// otherwise.
+const DEFAULT_MAX_SESSION_SECS: u32 = 600;
+
+// Skipping an idle player pays the caller this many lamports out of the game, which covers the fee of the skip's transaction.
+const SKIP_CRANK_REWARD_LAMPORTS: u64 = 10_000;
+
+// A payout of a game with a token escrow takes the escrow, the owner's token account, the treasury's token account and the
+// token program after the pots.
+const TOKEN_PAYOUT_ACCOUNTS: usize = 4;
+
+// Layout of a Pyth (v2) price account.
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const PYTH_VERSION: u32 = 2;
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_TIMESTAMP_OFFSET: usize = 96;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_CONF_OFFSET: usize = 216;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+
+// The native ed25519 program's instruction data starts with a 2 byte header (number of signatures + padding) followed by
+// 14 bytes of offsets for each signature.
+const ED25519_HEADER_SIZE: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+// The most players (and so queues) a game can have.  The queue instructions take every queue of a game, so this is bounded by
+// what fits in a transaction.
+const MAX_PLAYERS: u8 = 8;
+
+// The deepest leaderboard a game can ask for.
+const MAX_LEADERBOARD_DEPTH: u8 = 100;
+
+// Payout schedules are written in basis points (1/100th of a percent) and have to add up to 100%.
+const BASIS_POINTS: u16 = 10_000;
+
+// The most ranked places a payout schedule can pay.
+const MAX_PAYOUT_PLACES: usize = 10;
+
+// Unless the arcade's authority configures otherwise a game is hidden once it gets this many strikes.
+const DEFAULT_STRIKE_THRESHOLD: u8 = 3;
+
+// The most collaborators the owner's share of a game's payouts can be split between.
+const MAX_COLLABORATORS: usize = 5;
 
 #[program]
 pub mod arcade {
@@ -27,12 +93,28 @@ pub mod arcade {
         // Get accounts from the context.
         let arcade_account = &mut ctx.accounts.arcade_account;
         let genesis_game_account = &mut ctx.accounts.genesis_game_account;
+        let treasury = &mut ctx.accounts.treasury;
         let authority = &mut ctx.accounts.authority;
 
         // Set up the arcade state.
         arcade_account.authority = authority.key();
         arcade_account.most_recent_game_key = genesis_game_account.key();
-        arcade_account.most_recent_game_pot_key = None;
+        arcade_account.game_count = 1;
+        arcade_account.default_payout_schedule = PayoutSchedule::default();
+        arcade_account.default_price = PricePolicy::UsdCents(DEFAULT_PLAY_FEE_CENTS);
+        arcade_account.price_feed = Pubkey::default();
+        arcade_account.price_feed_kind = PriceFeedKind::Manual;
+        arcade_account.max_price_age_secs = DEFAULT_MAX_PRICE_AGE_SECS;
+        arcade_account.max_price_confidence_bps = DEFAULT_MAX_PRICE_CONFIDENCE_BPS;
+        arcade_account.credit_discount_bps = DEFAULT_CREDIT_DISCOUNT_BPS;
+        arcade_account.min_credit_top_up = DEFAULT_MIN_CREDIT_TOP_UP_LAMPORTS;
+        arcade_account.pot_claim_window_secs = DEFAULT_POT_CLAIM_WINDOW_SECS;
+        arcade_account.arcade_fee_bps = DEFAULT_ARCADE_FEE_BPS;
+        arcade_account.pending_authority = None;
+        arcade_account.strike_threshold = DEFAULT_STRIKE_THRESHOLD;
+        arcade_account.age_attester = Pubkey::default();
+        genesis_game_account.arcade = arcade_account.key();
+        treasury.arcade = arcade_account.key();
 
         // If everything went well return Ok.
         Ok(())
@@ -47,6 +129,11 @@ pub mod arcade {
     /// NOTE: I need to create a webgl build extension/add-on that creates a game wallet for games that 
     /// are to be added to the arcade.  Then I need to pass that wallet address into here to ensure the games
     /// get their money.
+    /// 
+    /// The score authority is the key (usually the game's server) that is allowed to post scores to the leaderboard, the operator
+    /// is the key (usually the cabinet) that moves the game's queues on, and the leaderboard depth is the number of places kept on
+    /// the game's leaderboard.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_game(
         ctx: Context<MakeGame>,
         title: String,
@@ -54,13 +141,31 @@ pub mod arcade {
         game_art_hash: String,
         num_players: u8,
         game_type: u8,
-    ) -> ProgramResult {
+        score_authority: Pubkey,
+        operator: Pubkey,
+        leaderboard_depth: u8,
+        price: Option<PricePolicy>,
+    ) -> Result<()> {
         // Get accounts from the context
         let game_account = &mut ctx.accounts.game_account;
+        let leaderboard_account = &mut ctx.accounts.leaderboard_account;
         let most_recent_game_account = &mut ctx.accounts.most_recent_game_account;
         let arcade_account = &mut ctx.accounts.arcade_account;
         let owner = &mut ctx.accounts.owner;
 
+        // Team games are played by pairs of queues.
+        if game_type > 5 {
+            return Err(Errors::InvalidGameType.into());
+        }
+
+        if num_players == 0 || num_players > MAX_PLAYERS || ((game_type == 2 || game_type == 5) && num_players % 2 == 1) {
+            return Err(Errors::InvalidNumPlayers.into());
+        }
+
+        if leaderboard_depth == 0 || leaderboard_depth > MAX_LEADERBOARD_DEPTH {
+            return Err(Errors::InvalidLeaderboardDepth.into());
+        }
+
         // Initialize game_account
         game_account.title = title;
         game_account.web_gl_hash = web_gl_hash;
@@ -72,18 +177,30 @@ pub mod arcade {
         game_account.younger_game_key = game_account.key();
         game_account.max_players = num_players;
         game_account.game_type = game_type;
+        game_account.score_authority = score_authority;
+        game_account.operator = operator;
+        game_account.arcade = arcade_account.key();
+        game_account.payout_schedule = None;
+        game_account.price = price;
+        game_account.escrow = None;
+        game_account.escrow_reserved = 0;
+        game_account.queued_fees = 0;
+        game_account.queued_tokens = 0;
+        game_account.max_session_secs = DEFAULT_MAX_SESSION_SECS;
+        game_account.collaborators = Vec::new();
+        game_account.pending_owner = None;
+        game_account.hidden = false;
+        game_account.appeal_pending = false;
+        game_account.first_player_sequence = 0;
 
         // Initialize leaderboard
-        let first_place = Place {name: String::from("AAA"), wallet_key: owner.key(), score: 100};
-        let second_place = Place {name: String::from("BBB"), wallet_key: owner.key(), score: 50};
-        let third_place = Place {name: String::from("CCC"), wallet_key: owner.key(), score: 25};
-
-        let leaderboard = Leaderboard {first_place, second_place, third_place};
-
-        game_account.leaderboard = leaderboard;
+        leaderboard_account.game = game_account.key();
+        leaderboard_account.depth = leaderboard_depth;
+        leaderboard_account.places = Vec::with_capacity(leaderboard_depth as usize);
 
         // Store most recent game key as current game key in arcade account.
         arcade_account.most_recent_game_key = game_account.key();
+        arcade_account.game_count += 1;
 
         // TODO: connect past most_recent_game_key to the new game
         most_recent_game_account.younger_game_key = game_account.key();
@@ -99,1549 +216,970 @@ pub mod arcade {
         Ok(())
     }
 
-    /// This function deletes a game, while making sure that the person deleting the machine/game is
-    /// the person who owns it.  I would like anyone to upload whatever they want onto the arcade which
-    /// may come back to bite me, but I think this is the best way to promote an open space.
-    pub fn delete_game(ctx: Context<DeleteGame>) -> Result<()> {
+    /// This function replaces the score authority of a game.
+    /// 
+    /// If a build's server key ever leaks the owner can rotate the key so the leaked key can no longer post scores.
+    pub fn set_score_authority(ctx: Context<SetScoreAuthority>, score_authority: Pubkey) -> Result<()> {
         let game_account = &mut ctx.accounts.game_account;
-        let younger_game = &mut ctx.accounts.younger_game;
-        let older_game = &mut ctx.accounts.older_game;
 
-        younger_game.older_game_key = older_game.key();
-        older_game.younger_game_key = younger_game.key();
+        let old_score_authority = game_account.score_authority;
+        game_account.score_authority = score_authority;
 
-        emit!(GameEvent {
-            label: "DELETE".to_string(),
+        // emit an event to make known the score authority has been rotated
+        emit!(ScoreAuthorityEvent {
             game_id: game_account.key(),
-            more_recent_game_id: Some(younger_game.key()),
-            less_recent_game_id: Some(older_game.key()),
+            old_score_authority,
+            new_score_authority: score_authority,
         });
 
         Ok(())
     }
 
-    /// This game deletes the most recent game.
-    /// 
-    /// I'm going to be completely honest, I think I'm going to delete this and instead make the arcade a 
-    /// circular linked list, but this is here for now.
-    pub fn delete_most_recent_game(ctx: Context<DeleteMostRecentGame>) -> Result<()> {
+    /// This function replaces the operator of a game, the key that moves the game's queues on.
+    pub fn set_operator(ctx: Context<SetOperator>, operator: Pubkey) -> Result<()> {
         let game_account = &mut ctx.accounts.game_account;
-        let older_game = &mut ctx.accounts.older_game;
-        let arcade_state = &mut ctx.accounts.arcade_state;
-
-        older_game.younger_game_key = older_game.key();
 
-        arcade_state.most_recent_game_key = older_game.key();
+        let old_operator = game_account.operator;
+        game_account.operator = operator;
 
-        emit!(GameEvent {
-            label: "DELETE".to_string(),
+        // emit an event to make known the operator has been replaced
+        emit!(OperatorEvent {
             game_id: game_account.key(),
-            more_recent_game_id: None,
-            less_recent_game_id: Some(older_game.key()),
+            old_operator,
+            new_operator: operator,
         });
 
         Ok(())
     }
 
-    /// This function initializes the game queues of a one player normal game
+    /// This function sets the payout schedule of a game.
     /// 
-    /// This function will require the user to pay for the created accounts as well as an additional $0.25
-    /// to fund the game account.
-    pub fn init_one_player_game_queue(ctx: Context<InitOnePlayerGameQueue>) -> ProgramResult {
-        let game_queue_account = &mut ctx.accounts.game_queue_account;
-        let player_account = &mut ctx.accounts.player_account;
+    /// Passing None makes the game fall back to the arcade's default schedule.
+    pub fn set_payout_schedule(ctx: Context<SetPayoutSchedule>, payout_schedule: Option<PayoutSchedule>) -> Result<()> {
         let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
-
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
-        );
-
-        anchor_lang::solana_program::program::invoke(
-            &ix,
-            &[
-                payer.to_account_info(),
-                game_account.to_account_info(),
-            ],
-        )?;
-
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
-
-        game_queue_account.game = game_account.key();
-        game_queue_account.current_player = player_account.key();
-        game_queue_account.last_player = player_account.key();
-        game_queue_account.num_players_in_queue = 1;
 
-        if game_account.game_queues.len() == 0 {
-            game_account.game_queues.push(game_queue_account.key());
-        } else {
-            game_account.game_queues[0] = game_queue_account.key();
+        if let Some(schedule) = &payout_schedule {
+            schedule.validate()?;
         }
+        game_account.payout_schedule = payout_schedule.clone();
 
-        // emit an event to make known that a queue is being created
-        emit!(NewQueueEvent {
-            game_id: game_account.key(),
-            queue_ids: vec![game_queue_account.key()],
-            queue_nums: vec![1],
-            new_player_ids: vec![Some(player_account.key())],
-            num_players: vec![1],
+        emit!(PayoutScheduleEvent {
+            account_id: game_account.key(),
+            payout_schedule,
         });
 
         Ok(())
     }
 
-    /// This function allows a user to join the queue of a 1 player game
-    pub fn join_one_player_game_queue(ctx: Context<JoinOnePlayerGameQueue>) -> ProgramResult {
-        let player_account = &mut ctx.accounts.player_account;
-        let last_player = &mut ctx.accounts.last_player;
-        let game_queue_account = &mut ctx.accounts.game_queue_account;
-        let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
+    /// This function proposes a new authority for the arcade, who takes over once they accept.  Passing None withdraws the
+    /// proposal.
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+        let arcade_account = &mut ctx.accounts.arcade_account;
 
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
-        );
+        arcade_account.pending_authority = new_authority;
 
-        anchor_lang::solana_program::program::invoke(
-            &ix,
-            &[
-                payer.to_account_info(),
-                game_account.to_account_info(),
-            ],
-        )?;
+        // emit an event to make known the arcade's authority may change
+        emit!(AuthorityEvent {
+            label: "PROPOSE".to_string(),
+            arcade_id: arcade_account.key(),
+            old_authority: arcade_account.authority,
+            new_authority,
+        });
 
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
+        Ok(())
+    }
 
-        last_player.next_player = Some(player_account.key());
+    /// This function makes the proposed authority of the arcade its authority.  Roles granted by the old authority stay granted.
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let arcade_account = &mut ctx.accounts.arcade_account;
+        let new_authority = &ctx.accounts.new_authority;
 
-        game_queue_account.last_player = player_account.key();
-        game_queue_account.num_players_in_queue += 1;
+        let old_authority = arcade_account.authority;
+        arcade_account.authority = new_authority.key();
+        arcade_account.pending_authority = None;
 
-        // Emit an event to make known the queue has been updated
-        emit!(JoinQueueEvent {
-            game_id: game_account.key(),
-            queue_id: game_queue_account.key(),
-            queue_num: 1,
-            last_player: player_account.key(),
-            num_players: game_queue_account.num_players_in_queue,
+        // emit an event to make known the arcade's authority changed
+        emit!(AuthorityEvent {
+            label: "ACCEPT".to_string(),
+            arcade_id: arcade_account.key(),
+            old_authority,
+            new_authority: Some(new_authority.key()),
         });
 
         Ok(())
     }
 
-    /// This function advances a one player game (i.e. makes the next player the current player etc.)
-    pub fn advance_one_player_game_queue(ctx: Context<AdvanceOnePlayerGameQueue>) -> Result<()> {
-        let game_account = & ctx.accounts.game_account;
-        let current_player = &mut ctx.accounts.current_player;
-        let game_queue_account = &mut ctx.accounts.game_queue_account;
+    /// This function grants a wallet one of the arcade's admin roles, letting it run that role's admin instructions without
+    /// holding the arcade's authority key.
+    pub fn grant_role(ctx: Context<GrantRole>, wallet: Pubkey, role: Role) -> Result<()> {
+        let arcade_account = &ctx.accounts.arcade_account;
+        let role_grant = &mut ctx.accounts.role_grant;
 
-        (game_queue_account.current_player, game_queue_account.num_players_in_queue) = match current_player.next_player {
-            Some(player) => (player, game_queue_account.num_players_in_queue - 1),
-            None => return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into()),
-        };
+        role_grant.arcade = arcade_account.key();
+        role_grant.wallet = wallet;
+        role_grant.role = role;
 
-        // emit an event to make known that the queue has been advanced
-        emit!(AdvanceQueueEvent {
-            game_id: game_account.key(),
-            queue_ids: vec![game_queue_account.key()],
-            queue_nums: vec![1],
-            finished_players: vec![Some(current_player.key())],
-            new_players: vec![current_player.next_player],
-            num_players: vec![game_queue_account.num_players_in_queue],
+        // emit an event to make known a role has been granted
+        emit!(RoleEvent {
+            label: "GRANT".to_string(),
+            arcade_id: arcade_account.key(),
+            wallet,
+            role,
         });
 
         Ok(())
     }
-    
-    /// This function destroys the queues associated with the one player game to save space and resources.
-    /// 
-    /// The credits associated with these queues is then credited to the game account.
-    pub fn finish_one_player_game_queue(ctx: Context<FinishOnePlayerGameQueue>) -> ProgramResult {
-        let game_account = &mut ctx.accounts.game_account;
 
-        game_account.game_queues[0] = game_account.key();
+    /// This function takes an admin role away from a wallet.
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        let role_grant = &ctx.accounts.role_grant;
 
-        emit!(FinishQueueEvent {
-            game_id: game_account.key()
+        // emit an event to make known a role has been revoked
+        emit!(RoleEvent {
+            label: "REVOKE".to_string(),
+            arcade_id: role_grant.arcade,
+            wallet: role_grant.wallet,
+            role: role_grant.role,
         });
 
         Ok(())
     }
 
-    /// This function initializes the game queues for a two player game.
-    /// 
-    /// The person initializing the queues will pay $0.25 + the amount it costs to create the queues.
-    pub fn init_two_player_game_queue(ctx: Context<InitTwoPlayerGameQueue>) -> ProgramResult {
-        let player_account = &mut ctx.accounts.player_account;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-        let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
-
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
-        );
+    /// This function sets the payout schedule used by every game in the arcade that does not have its own.
+    pub fn set_default_payout_schedule(ctx: Context<SetDefaultPayoutSchedule>, payout_schedule: PayoutSchedule) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Curator, ctx.remaining_accounts, ctx.program_id)?;
 
-        anchor_lang::solana_program::program::invoke(
-            &ix,
-            &[
-                payer.to_account_info(),
-                game_account.to_account_info(),
-            ],
-        )?;
+        let arcade_account = &mut ctx.accounts.arcade_account;
 
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
-        
-        game_queue_account_one.game = game_account.key();
-        game_queue_account_one.current_player = player_account.key();
-        game_queue_account_one.last_player = player_account.key();
-        game_queue_account_one.num_players_in_queue = 1;
-
-        game_queue_account_two.game = game_account.key();
-        game_queue_account_two.current_player = game_account.key();
-        game_queue_account_two.last_player = game_account.key();
-        game_queue_account_two.num_players_in_queue = 0;
-
-        if game_account.game_queues.len() == 0 {
-            game_account.game_queues.push(game_queue_account_one.key());
-            game_account.game_queues.push(game_queue_account_two.key());
-        } else {
-            game_account.game_queues[0] = game_queue_account_one.key();
-            game_account.game_queues[1] = game_queue_account_two.key();
-        }
+        payout_schedule.validate()?;
+        arcade_account.default_payout_schedule = payout_schedule.clone();
 
-        // emit an event to make known that a new queue has been created
-        emit!(NewQueueEvent {
-            game_id: game_account.key(),
-            queue_ids: vec![game_queue_account_one.key(), game_queue_account_two.key()],
-            queue_nums: vec![1, 2],
-            new_player_ids: vec![Some(player_account.key()), None],
-            num_players: vec![1, 0],
+        emit!(PayoutScheduleEvent {
+            account_id: arcade_account.key(),
+            payout_schedule: Some(payout_schedule),
         });
 
         Ok(())
     }
 
-    /// This function allows a user to join a two player game queue
-    pub fn join_two_player_game_queue(ctx: Context<JoinTwoPlayerGameQueue>) -> Result<()> {
-        let player_account = &mut ctx.accounts.player_account;
-        let q1_last_player = &mut ctx.accounts.q1_last_player;
-        let q2_last_player = &mut ctx.accounts.q2_last_player;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
+    /// This function sets the price of a game.
+    /// 
+    /// Passing None makes the game fall back to the arcade's default price.
+    pub fn set_game_price(ctx: Context<SetGamePrice>, price: Option<PricePolicy>) -> Result<()> {
         let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
 
-        if game_queue_account_two.current_player != game_account.key() &&
-           game_queue_account_two.last_player != q2_last_player.key() {
-            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-        }
-
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
-        );
+        game_account.price = price;
 
-        anchor_lang::solana_program::program::invoke(
-            &ix,
-            &[
-                payer.to_account_info(),
-                game_account.to_account_info(),
-            ],
-        )?;
+        emit!(PriceEvent {
+            account_id: game_account.key(),
+            price,
+        });
 
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
+        Ok(())
+    }
 
-        if q1_last_player.key() == q2_last_player.key() {
-            // Add new player as player 2
-            game_queue_account_two.current_player = player_account.key();
-            game_queue_account_two.last_player = player_account.key();
-            game_queue_account_two.num_players_in_queue = 1;
+    /// This function proposes a new owner for a game, who takes over deleting it, paying it out and its settings once they
+    /// accept.  Passing None withdraws the proposal.
+    pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Option<Pubkey>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
 
-            // emit an event to make known a player has joined the queue
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_two.key(),
-                queue_num: 2,
-                last_player: player_account.key(),
-                num_players: 1,
-            });
-        } else if game_queue_account_one.num_players_in_queue == game_queue_account_two.num_players_in_queue {
-            // Add new player behind current player 1
-            q1_last_player.next_player = Some(player_account.key());
-            game_queue_account_one.last_player = player_account.key();
-            game_queue_account_one.num_players_in_queue += 1;
-
-            // emit an event to make known a player has joined the queue
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_one.key(),
-                queue_num: 1,
-                last_player: player_account.key(),
-                num_players: game_queue_account_one.num_players_in_queue,
-            });
-        } else {
-            // Add new player behind current player 2
-            q2_last_player.next_player = Some(player_account.key());
-            game_queue_account_two.last_player = player_account.key();
-            game_queue_account_two.num_players_in_queue += 1;
+        game_account.pending_owner = new_owner;
 
-            // emit an event to make known a player has joined the queue
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_two.key(),
-                queue_num: 2,
-                last_player: player_account.key(),
-                num_players: game_queue_account_two.num_players_in_queue,
-            });
-        }
+        // emit an event to make known the game's owner may change
+        emit!(OwnerEvent {
+            label: "PROPOSE".to_string(),
+            game_id: game_account.key(),
+            old_owner: game_account.owner_wallet,
+            new_owner,
+        });
 
         Ok(())
     }
 
-    /// Advances a 2 player game queue.
-    /// 
-    /// E F -> 
-    /// C D -> E F -> 
-    /// A B -> C D -> E F
-    pub fn advance_two_player_game_queue(ctx: Context<AdvanceTwoPlayerGameQueue>) -> Result<()> {
-        let player_one = &mut ctx.accounts.player_one;
-        let player_two = &mut ctx.accounts.player_two;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-        let game_account = & ctx.accounts.game_account;
-
-        (game_queue_account_one.current_player, game_queue_account_one.last_player, game_queue_account_one.num_players_in_queue) = match player_one.next_player {
-            Some(player) => (player, game_queue_account_one.last_player, game_queue_account_one.num_players_in_queue - 1),
-            None => return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into()),
-        };
+    /// This function makes the proposed owner of a game its owner.  The game's collaborators (if any) keep their shares.
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
+        let new_owner = &ctx.accounts.new_owner;
 
-        (game_queue_account_two.current_player, game_queue_account_two.last_player, game_queue_account_two.num_players_in_queue) = match player_two.next_player {
-            Some(player) => (player, game_queue_account_two.last_player, game_queue_account_two.num_players_in_queue - 1),
-            None => (game_account.key(), game_account.key(), 0),
-        };
+        let old_owner = game_account.owner_wallet;
+        game_account.owner_wallet = new_owner.key();
+        game_account.pending_owner = None;
 
-        emit!(AdvanceQueueEvent {
+        // emit an event to make known the game's owner changed
+        emit!(OwnerEvent {
+            label: "ACCEPT".to_string(),
             game_id: game_account.key(),
-            queue_ids: vec![game_queue_account_one.key(), game_queue_account_two.key()],
-            queue_nums: vec![1, 2],
-            finished_players: if player_one.key() == player_two.key() { vec![Some(player_one.key()), None] }
-                              else { vec![Some(player_one.key()), Some(player_two.key())] },
-            new_players: if game_queue_account_two.current_player == game_account.key() { vec![Some(game_queue_account_one.current_player), None] }
-                         else { vec![Some(game_queue_account_one.current_player), Some(game_queue_account_two.current_player)] },
-            num_players: vec![game_queue_account_one.num_players_in_queue, game_queue_account_two.num_players_in_queue],
+            old_owner,
+            new_owner: Some(new_owner.key()),
         });
 
         Ok(())
     }
 
-    /// Advances a two player king of the hill game queue
-    /// 
-    /// E F -> E   -> E   ->     ->     -> 
-    /// C D -> C F -> C   ->   E ->     -> 
-    /// A B -> A D -> A F -> A C -> A E -> A
-    pub fn advance_two_player_king_of_hill_queue(ctx: Context<AdvanceTwoPlayerKingOfHillQueue>) -> Result<()> {
-        let game_account = &mut ctx.accounts.game_account;
-        let winning_player = &mut ctx.accounts.winning_player;
-        let losing_player = &mut ctx.accounts.losing_player;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-
-        if winning_player.key() != game_queue_account_one.current_player && winning_player.key() != game_queue_account_two.current_player {
-            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-        } else if losing_player.key() != game_queue_account_one.current_player && losing_player.key() != game_queue_account_two.current_player {
-            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-        }
+    /// This function gives a game a strike for the reason given by `reason` (a code from the arcade's moderation policy).  The
+    /// game is hidden, and takes no new plays, once its strikes reach the arcade's strike threshold.
+    pub fn report_game(ctx: Context<ModerateGame>, reason: u8) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.moderator.key(), Role::Moderator, ctx.remaining_accounts, ctx.program_id)?;
 
-        if losing_player.key() == game_queue_account_one.current_player {
-            (game_queue_account_one.current_player, game_queue_account_one.last_player, game_queue_account_one.num_players_in_queue) = match losing_player.next_player {
-                Some(player) => (player, game_queue_account_one.last_player, game_queue_account_one.num_players_in_queue - 1),
-                None => match winning_player.next_player {
-                    Some(player) => {
-                        let last_player_two = game_queue_account_two.last_player;
-                        let old_queue_two_num_players = game_queue_account_two.num_players_in_queue - 1;
-                        game_queue_account_two.last_player = winning_player.key();
-                        game_queue_account_two.num_players_in_queue = 1;
-                        winning_player.next_player = None;
-
-                        (player, last_player_two, old_queue_two_num_players)
-                    },
-                    None => (game_account.key(), game_account.key(), 0),
-                },
-            };
+        let game_account = &mut ctx.accounts.game_account;
+        let moderator = &ctx.accounts.moderator;
 
-            // emit an event to make known that the queues have been advanced
-            emit!(AdvanceQueueEvent {
-                game_id: game_account.key(),
-                queue_ids: vec![game_queue_account_one.key()],
-                queue_nums: vec![1],
-                finished_players: vec![Some(losing_player.key())],
-                new_players: vec![losing_player.next_player],
-                num_players: vec![game_queue_account_one.num_players_in_queue, game_queue_account_two.num_players_in_queue],
-            });
-        } else {
-            (game_queue_account_two.current_player, game_queue_account_two.last_player, game_queue_account_two.num_players_in_queue) = match losing_player.next_player {
-                Some(player) => (player, game_queue_account_two.last_player, game_queue_account_two.num_players_in_queue - 1),
-                None => match winning_player.next_player {
-                    Some(player) => {
-                        let last_player_one = game_queue_account_one.last_player;
-                        let old_queue_one_num_players = game_queue_account_one.num_players_in_queue - 1;
-                        game_queue_account_one.last_player = winning_player.key();
-                        game_queue_account_one.num_players_in_queue = 1;
-                        winning_player.next_player = None;
-                        
-                        (player, last_player_one, old_queue_one_num_players)
-                    },
-                    None => (game_account.key(), game_account.key(), 0),
-                },
-            };
+        let delisted = game_account.add_strike(ctx.accounts.arcade_account.strike_threshold);
 
-            // emit an event to make known that the queues have been advanced
-            emit!(AdvanceQueueEvent {
+        // emit an event to make known the game got a strike
+        emit!(ModerationEvent {
+            label: "STRIKE".to_string(),
+            game_id: game_account.key(),
+            wallet: moderator.key(),
+            reason,
+            strikes: game_account.strikes,
+            hidden: game_account.hidden,
+        });
+        if delisted {
+            // emit an event to make known the game has been hidden
+            emit!(ModerationEvent {
+                label: "DELIST".to_string(),
                 game_id: game_account.key(),
-                queue_ids: vec![game_queue_account_two.key()],
-                queue_nums: vec![2],
-                finished_players: vec![Some(losing_player.key())],
-                new_players: vec![losing_player.next_player],
-                num_players: vec![game_queue_account_one.num_players_in_queue, game_queue_account_two.num_players_in_queue],
+                wallet: moderator.key(),
+                reason,
+                strikes: game_account.strikes,
+                hidden: true,
             });
         }
 
-        return Ok(());
+        Ok(())
     }
 
-    /// Finishes a 2 player game by resetting the game queue pointers.
-    pub fn finish_two_player_game_queue(ctx: Context<FinishTwoPlayerGameQueue>) -> ProgramResult {
+    /// This function lets the owner of a game with strikes ask the arcade's moderators to look at them again.
+    pub fn appeal_game(ctx: Context<AppealGame>) -> Result<()> {
         let game_account = &mut ctx.accounts.game_account;
 
-        for i in 0..2 {
-            game_account.game_queues[i] = game_account.key();
+        if game_account.strikes == 0 {
+            return Err(Errors::NothingToAppeal.into());
         }
+        game_account.appeal_pending = true;
 
-        // emit an event to make known that the queues have been deleted
-        emit!(FinishQueueEvent {
+        // emit an event to make known the game's strikes have been appealed
+        emit!(ModerationEvent {
+            label: "APPEAL".to_string(),
             game_id: game_account.key(),
+            wallet: ctx.accounts.owner.key(),
+            reason: 0,
+            strikes: game_account.strikes,
+            hidden: game_account.hidden,
         });
 
         Ok(())
     }
 
-    /// Finishes a 2 player king of the hill game by resetting the game queue pointers.
-    pub fn finish_two_player_king_of_hill_queue(ctx: Context<FinishTwoPlayerKingOfHillQueue>) -> ProgramResult {
+    /// This function turns down a game's appeal, leaving its strikes as they are.
+    pub fn dismiss_appeal(ctx: Context<ModerateGame>) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.moderator.key(), Role::Moderator, ctx.remaining_accounts, ctx.program_id)?;
+
         let game_account = &mut ctx.accounts.game_account;
 
-        for i in 0..2 {
-            game_account.game_queues[i] = game_account.key();
+        if !game_account.appeal_pending {
+            return Err(Errors::NoAppealPending.into());
         }
+        game_account.appeal_pending = false;
 
-        // emit an event to make known that the queues have been deleted
-        emit!(FinishQueueEvent {
+        // emit an event to make known the appeal has been turned down
+        emit!(ModerationEvent {
+            label: "DISMISS".to_string(),
             game_id: game_account.key(),
+            wallet: ctx.accounts.moderator.key(),
+            reason: 0,
+            strikes: game_account.strikes,
+            hidden: game_account.hidden,
         });
 
         Ok(())
     }
 
-    /// Initializes the game queues of a 3 player game.
-    pub fn init_three_player_game_queue(ctx: Context<InitThreePlayerGameQueue>) -> ProgramResult {
-        let player_account = &mut ctx.accounts.player_account;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-        let game_queue_account_three = &mut ctx.accounts.game_queue_account_three;
-        let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
-
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
-        );
-
-        anchor_lang::solana_program::program::invoke(
-            &ix,
-            &[
-                payer.to_account_info(),
-                game_account.to_account_info(),
-            ],
-        )?;
+    /// This function clears a game's strikes (upholding its appeal, if any) and lists it again.
+    pub fn clear_strikes(ctx: Context<ModerateGame>) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.moderator.key(), Role::Moderator, ctx.remaining_accounts, ctx.program_id)?;
 
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
+        let game_account = &mut ctx.accounts.game_account;
 
-        game_queue_account_one.game = game_account.key();
-        game_queue_account_one.current_player = player_account.key();
-        game_queue_account_one.last_player = player_account.key();
-        game_queue_account_one.num_players_in_queue = 1;
-
-        game_queue_account_two.game = game_account.key();
-        game_queue_account_two.current_player = game_account.key();
-        game_queue_account_two.last_player = game_account.key();
-        game_queue_account_two.num_players_in_queue = 0;
-
-        game_queue_account_three.game = game_account.key();
-        game_queue_account_three.current_player = game_account.key();
-        game_queue_account_three.last_player = game_account.key();
-        game_queue_account_three.num_players_in_queue = 0;
-
-        if game_account.game_queues.len() == 0 {
-            game_account.game_queues.push(game_queue_account_one.key());
-            game_account.game_queues.push(game_queue_account_two.key());
-            game_account.game_queues.push(game_queue_account_three.key());
-        } else {
-            game_account.game_queues[0] = game_queue_account_one.key();
-            game_account.game_queues[1] = game_queue_account_two.key();
-            game_account.game_queues[2] = game_queue_account_three.key();
-        }
+        game_account.strikes = 0;
+        game_account.hidden = false;
+        game_account.appeal_pending = false;
 
-        // emit event to make known that a new game queue has started
-        emit!(NewQueueEvent {
+        // emit an event to make known the game's strikes have been cleared
+        emit!(ModerationEvent {
+            label: "CLEAR".to_string(),
             game_id: game_account.key(),
-            queue_ids: vec![
-                game_queue_account_one.key(),
-                game_queue_account_two.key(),
-                game_queue_account_three.key(),
-            ],
-            queue_nums: vec![1, 2, 3],
-            new_player_ids: vec![Some(player_account.key()), None, None],
-            num_players: vec![1, 0, 0],
+            wallet: ctx.accounts.moderator.key(),
+            reason: 0,
+            strikes: 0,
+            hidden: false,
         });
 
         Ok(())
     }
 
-    /// Joins a player into a normal 3 player game queue
-    pub fn join_three_player_game_queue(ctx: Context<JoinThreePlayerGameQueue>) -> Result<()> {
-        let player_account = &mut ctx.accounts.player_account;
-        let q1_last_player = &mut ctx.accounts.q1_last_player;
-        let q2_last_player = &mut ctx.accounts.q2_last_player;
-        let q3_last_player = &mut ctx.accounts.q3_last_player;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-        let game_queue_account_three = &mut ctx.accounts.game_queue_account_three;
-        let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
+    /// This function sets how many strikes hide a game, 0 never hides games.  Games are only hidden when they get a strike.
+    pub fn set_strike_threshold(ctx: Context<SetStrikeThreshold>, strike_threshold: u8) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Curator, ctx.remaining_accounts, ctx.program_id)?;
 
-        if game_queue_account_two.current_player != game_account.key() &&
-           game_queue_account_two.last_player != q2_last_player.key() {
-            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-        } else if game_queue_account_three.current_player != game_account.key() &&
-                  game_queue_account_three.last_player != q3_last_player.key() {
-            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-        }
+        let arcade_account = &mut ctx.accounts.arcade_account;
 
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
-        );
+        arcade_account.strike_threshold = strike_threshold;
 
-        anchor_lang::solana_program::program::invoke(
-            &ix,
-            &[
-                payer.to_account_info(),
-                game_account.to_account_info(),
-            ],
-        )?;
+        emit!(StrikeThresholdEvent {
+            arcade_id: arcade_account.key(),
+            strike_threshold,
+        });
 
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
+        Ok(())
+    }
 
-        if game_queue_account_two.current_player == game_account.key() {
-            // Add a new player as player 2
-            game_queue_account_two.current_player = player_account.key();
-            game_queue_account_two.last_player = player_account.key();
-            game_queue_account_two.num_players_in_queue = 1;
+    /// This function sets the key trusted to attest the age of the wallets playing 18+ games, the default key trusts nobody.
+    /// 
+    /// Attestations issued by a previous attester stop counting as soon as it is replaced.
+    pub fn set_age_attester(ctx: Context<SetAgeAttester>, age_attester: Pubkey) -> Result<()> {
+        let arcade_account = &mut ctx.accounts.arcade_account;
 
-            // emit event to make known that a player has joined the game
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_two.key(),
-                queue_num: 2,
-                last_player: player_account.key(),
-                num_players: 1,
-            });
+        let old_age_attester = arcade_account.age_attester;
+        arcade_account.age_attester = age_attester;
 
-            return Ok(());
-        }
+        // emit an event to make known who attests ages on the arcade
+        emit!(AgeAttesterEvent {
+            arcade_id: arcade_account.key(),
+            old_age_attester,
+            new_age_attester: age_attester,
+        });
 
-        if game_queue_account_three.current_player == game_account.key() {
-            // Add a new player as player 3
-            game_queue_account_three.current_player = player_account.key();
-            game_queue_account_three.last_player = player_account.key();
-            game_queue_account_three.num_players_in_queue = 1;
+        Ok(())
+    }
 
-            // emit an event to make known that a player has joined the game
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_three.key(),
-                queue_num: 3,
-                last_player: player_account.key(),
-                num_players: 1,
-            });
+    /// This function attests that a wallet is old enough to play the arcade's 18+ games until `expires_at`, renewing the
+    /// wallet's attestation if it already has one.  Only the arcade's age attester can do this and it pays for the attestation.
+    pub fn attest_age(ctx: Context<AttestAge>, wallet: Pubkey, expires_at: i64) -> Result<()> {
+        let arcade_account = &ctx.accounts.arcade_account;
+        let age_attestation = &ctx.accounts.age_attestation;
+        let attester = &ctx.accounts.attester;
 
-            return Ok(());
+        if expires_at <= Clock::get()?.unix_timestamp {
+            return Err(Errors::AgeAttestationExpired.into());
         }
 
-        if game_queue_account_one.num_players_in_queue == game_queue_account_two.num_players_in_queue &&
-           game_queue_account_two.num_players_in_queue == game_queue_account_three.num_players_in_queue {
-            // Add a new player behind player 1.
-            q1_last_player.next_player = Some(player_account.key());
-            game_queue_account_one.last_player = player_account.key();
-            game_queue_account_one.num_players_in_queue += 1;
-
-            // emit an event to make known a player has been added to the end of queue 1
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_one.key(),
-                queue_num: 1,
-                last_player: player_account.key(),
-                num_players: game_queue_account_one.num_players_in_queue,
-            });
-        } else if game_queue_account_two.num_players_in_queue == game_queue_account_three.num_players_in_queue {
-            // Add a new player behind player 2.
-            q2_last_player.next_player = Some(player_account.key());
-            game_queue_account_two.last_player = player_account.key();
-            game_queue_account_two.num_players_in_queue += 1;
-
-            // emit an event to make known a player has been added to the end of queue 2
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_two.key(),
-                queue_num: 2,
-                last_player: player_account.key(),
-                num_players: game_queue_account_two.num_players_in_queue,
-            });
+        let arcade_key = arcade_account.key();
+        if age_attestation.owner == ctx.program_id {
+            let (address, _) = Pubkey::find_program_address(&[b"age", arcade_key.as_ref(), wallet.as_ref()], ctx.program_id);
+            if age_attestation.key() != address {
+                return Err(Errors::WrongAccountAddress.into());
+            }
         } else {
-            // Add a new player behind player 3.
-            q3_last_player.next_player = Some(player_account.key());
-            game_queue_account_three.last_player = player_account.key();
-            game_queue_account_three.num_players_in_queue += 1;
-
-            // emit an event to make known a player has been added to the end of queue 3
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_three.key(),
-                queue_num: 3,
-                last_player: player_account.key(),
-                num_players: game_queue_account_three.num_players_in_queue,
-            });
+            create_pda_account(
+                &attester.to_account_info(),
+                age_attestation,
+                8 + AgeAttestation::MAX_SIZE,
+                &[b"age", arcade_key.as_ref(), wallet.as_ref()],
+                &ctx.accounts.system_program.to_account_info(),
+                ctx.program_id,
+            )?;
         }
 
+        let attestation = AgeAttestation {
+            arcade: arcade_key,
+            wallet,
+            attester: attester.key(),
+            expires_at,
+        };
+        attestation.try_serialize(&mut &mut age_attestation.try_borrow_mut_data()?[..])?;
+
+        // emit an event to make known a wallet's age has been attested
+        emit!(AgeAttestationEvent {
+            label: "ATTEST".to_string(),
+            arcade_id: arcade_key,
+            wallet,
+            attester: attester.key(),
+            expires_at,
+        });
+
         Ok(())
     }
 
-    /// Advances the game queue for a normal 3 player game
-    /// 
-    /// G H I ->       -> 
-    /// D E F -> G H I ->
-    /// A B C -> D E F -> G H I
-    pub fn advance_three_player_game_queue(ctx: Context<AdvanceThreePlayerGameQueue>) -> Result<()> {
-        let player_one = &mut ctx.accounts.player_one;
-        let player_two = &mut ctx.accounts.player_two;
-        let player_three = &mut ctx.accounts.player_three;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-        let game_queue_account_three = &mut ctx.accounts.game_queue_account_three;
-        let game_account = & ctx.accounts.game_account;
-
-        (game_queue_account_one.current_player, game_queue_account_two.last_player, game_queue_account_one.num_players_in_queue) = match player_one.next_player {
-            Some(player) => (player, game_queue_account_two.last_player, game_queue_account_one.num_players_in_queue - 1),
-            None => return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into()),
-        };
+    /// This function revokes the age attestation of a wallet, which can not play 18+ games until it is attested again.
+    pub fn revoke_age_attestation(ctx: Context<RevokeAgeAttestation>) -> Result<()> {
+        let age_attestation = &ctx.accounts.age_attestation;
+
+        // emit an event to make known a wallet's age attestation has been revoked
+        emit!(AgeAttestationEvent {
+            label: "REVOKE".to_string(),
+            arcade_id: age_attestation.arcade,
+            wallet: age_attestation.wallet,
+            attester: ctx.accounts.attester.key(),
+            expires_at: age_attestation.expires_at,
+        });
 
-        (game_queue_account_two.current_player, game_queue_account_two.last_player, game_queue_account_two.num_players_in_queue) = match player_two.next_player {
-            Some(player) => (player, game_queue_account_two.last_player, game_queue_account_two.num_players_in_queue - 1),
-            None => (game_account.key(), game_account.key(), 0),
-        };
+        Ok(())
+    }
 
-        (game_queue_account_three.current_player, game_queue_account_three.last_player, game_queue_account_three.num_players_in_queue) = match player_three.next_player {
-            Some(player) => (player, game_queue_account_three.last_player, game_queue_account_three.num_players_in_queue - 1),
-            None => (game_account.key(), game_account.key(), 0),
-        };
+    /// This function sets how long a player can stay at the front of one of the game's queues before anybody can skip them.
+    /// 
+    /// Passing 0 means players are never skipped.
+    pub fn set_max_session(ctx: Context<SetMaxSession>, max_session_secs: u32) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
 
-        // emit an event to make known that the game queue has been advanced
-        emit!(AdvanceQueueEvent {
+        game_account.max_session_secs = max_session_secs;
+
+        emit!(MaxSessionEvent {
             game_id: game_account.key(),
-            queue_ids: vec![
-                game_queue_account_one.key(),
-                game_queue_account_two.key(),
-                game_queue_account_three.key(),
-            ],
-            queue_nums: vec![1, 2, 3],
-            finished_players: vec![
-                Some(player_one.key()),
-                Some(player_two.key()),
-                Some(player_three.key()),
-            ],
-            new_players: vec![
-                player_one.next_player,
-                player_two.next_player,
-                player_three.next_player,
-            ],
-            num_players: vec![
-                game_queue_account_one.num_players_in_queue,
-                game_queue_account_two.num_players_in_queue,
-                game_queue_account_three.num_players_in_queue,
-            ],
+            max_session_secs,
         });
 
         Ok(())
     }
 
-    /// Advances the game queues for a 3 player king of the hill game.
-    /// 
-    /// 7 8 9 ->     9  |  5     ->      
-    /// 4 5 6 -> 7 8 6  |  4     -> 5    
-    /// 1 2 3 -> 4 5 3  |  1 2 3 -> 4 _ 3
-    /// x x o           |  x x o
-    pub fn advance_three_player_king_of_hill_queue(ctx: Context<AdvanceThreePlayerKingOfHillQueue>) -> Result<()> {
-        let winning_player = &mut ctx.accounts.winning_player;
-        let losing_player_one = &mut ctx.accounts.losing_player_one;
-        let losing_player_two = &mut ctx.accounts.losing_player_two;
-        let queues = [&mut ctx.accounts.game_queue_account_one, &mut ctx.accounts.game_queue_account_two, &mut ctx.accounts.game_queue_account_three];
-        let game_account = & ctx.accounts.game_account;
-        let mut player_mappings = [0, 0, 0];
-        let mut winning_queue_num = 0;
-
-        for i in 0..3 {
-            if queues[i].current_player == winning_player.key() {
-                player_mappings[i] = 1;
-                winning_queue_num = i;
-            } else if queues[i].current_player == losing_player_one.key() {
-                player_mappings[i] = 2;
-            } else if queues[i].current_player == losing_player_two.key() {
-                player_mappings[i] = 3;
-            } else if queues[i].current_player == game_account.key() {
-                player_mappings[i] = 5;
-            } else {
-                return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-            }
-        }
+    /// This function sets the price players pay to play the games in the arcade that do not have their own price.
+    pub fn set_default_price(ctx: Context<SetDefaultPrice>, price: PricePolicy) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Curator, ctx.remaining_accounts, ctx.program_id)?;
 
-        let mut queue_ids = Vec::with_capacity(2);
-        let mut queue_nums = Vec::with_capacity(2);
-        let mut finished_players = Vec::with_capacity(2);
-        let mut new_players = Vec::with_capacity(2);
-        let mut num_players = Vec::with_capacity(2);
-
-        for i in 0..3 {
-            if player_mappings[i] == 2 {
-                (queues[i].current_player, queues[i].last_player, queues[i].num_players_in_queue) = match losing_player_one.next_player {
-                    Some(player) => (player, queues[i].last_player, queues[i].num_players_in_queue - 1),
-                    None => match winning_player.next_player {
-                        Some(player) => {
-                            let old_winning_queue_last_player = queues[winning_queue_num].last_player;
-                            let old_num_players = queues[winning_queue_num].num_players_in_queue - 1;
-
-                            winning_player.next_player = None;
-                            queues[winning_queue_num].last_player = winning_player.key();
-                            queues[winning_queue_num].num_players_in_queue = 1;
-
-                            (player, old_winning_queue_last_player, old_num_players)
-                        },
-                        None => (game_account.key(), game_account.key(), 0),
-                    },
-                };
-
-                queue_ids.push(queues[i].key());
-                queue_nums.push(i as u8);
-                finished_players.push(Some(losing_player_one.key()));
-                new_players.push(if queues[i].current_player != game_account.key() { Some(queues[i].current_player) } else { None });
-                num_players.push(queues[i].num_players_in_queue);
-            } else if player_mappings[i] == 3 {
-                (queues[i].current_player, queues[i].last_player, queues[i].num_players_in_queue) = match losing_player_two.next_player {
-                    Some(player) => (player, queues[i].last_player, queues[i].num_players_in_queue - 1),
-                    None => match winning_player.next_player {
-                        Some(player) => {
-                            let old_winning_queue_last_player = queues[winning_queue_num].last_player;
-                            let old_num_players = queues[winning_queue_num].num_players_in_queue - 1;
-
-                            winning_player.next_player = None;
-                            queues[winning_queue_num].last_player = winning_player.key();
-                            queues[winning_queue_num].num_players_in_queue = 1;
-
-                            (player, old_winning_queue_last_player, old_num_players)
-                        },
-                        None => {
-                            (game_account.key(), game_account.key(), 0)
-                        },
-                    },
-                };
-
-                queue_ids.push(queues[i].key());
-                queue_nums.push(i as u8);
-                finished_players.push(Some(losing_player_two.key()));
-                new_players.push(if queues[i].current_player != game_account.key() { Some(queues[i].current_player) } else { None });
-                num_players.push(queues[i].num_players_in_queue);
-            }
-        }
+        let arcade_account = &mut ctx.accounts.arcade_account;
 
-        // emit an event to make known that the game queue has advanced
-        emit!(AdvanceQueueEvent {
-            game_id: game_account.key(),
-            queue_ids: queue_ids,
-            queue_nums: queue_nums,
-            finished_players: finished_players,
-            new_players: new_players,
-            num_players: num_players,
+        arcade_account.default_price = price;
+
+        emit!(PriceEvent {
+            account_id: arcade_account.key(),
+            price: Some(price),
         });
 
         Ok(())
     }
 
-    /// Finishes the game queues for a 3 player game by resetting their pointers.
-    pub fn finish_three_player_game_queue(ctx: Context<FinishThreePlayerGameQueue>) -> ProgramResult {
-        let game_account = &mut ctx.accounts.game_account;
+    /// This function sets the discount plays paid from prepaid credits get and the smallest amount of credits that can be
+    /// bought at once.
+    pub fn set_credit_terms(ctx: Context<SetCreditTerms>, credit_discount_bps: u16, min_credit_top_up: u64) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Treasurer, ctx.remaining_accounts, ctx.program_id)?;
+
+        let arcade_account = &mut ctx.accounts.arcade_account;
 
-        for i in 0..3 {
-            game_account.game_queues[i] = game_account.key();
+        if credit_discount_bps > BASIS_POINTS {
+            return Err(Errors::InvalidCreditTerms.into());
         }
 
-        // emit an event to make known that the game queue has ended
-        emit!(FinishQueueEvent {
-            game_id: game_account.key(),
+        arcade_account.credit_discount_bps = credit_discount_bps;
+        arcade_account.min_credit_top_up = min_credit_top_up;
+
+        // emit an event to make known the credit terms have changed
+        emit!(CreditTermsEvent {
+            arcade_id: arcade_account.key(),
+            credit_discount_bps,
+            min_credit_top_up,
         });
 
         Ok(())
     }
 
-    /// Finishes the game queues for a 3 player king of the hill game by resetting their pointers.
-    pub fn finish_three_player_king_of_hill_queue(ctx: Context<FinishThreePlayerKingOfHillQueue>) -> ProgramResult {
-        let game_account = &mut ctx.accounts.game_account;
+    /// This function sets how long winners have to cash out their pots, 0 lets pots wait for their winners forever.  Only pots
+    /// created afterwards get the new deadline.
+    pub fn set_pot_claim_window(ctx: Context<SetPotClaimWindow>, pot_claim_window_secs: u32) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Curator, ctx.remaining_accounts, ctx.program_id)?;
 
-        for i in 0..3 {
-            game_account.game_queues[i] = game_account.key();
-        }
+        let arcade_account = &mut ctx.accounts.arcade_account;
 
-        // emit and event to make known that the game queue has ended
-        emit!(FinishQueueEvent {
-            game_id: game_account.key(),
+        arcade_account.pot_claim_window_secs = pot_claim_window_secs;
+
+        // emit an event to make known the pot claim window has changed
+        emit!(PotClaimWindowEvent {
+            arcade_id: arcade_account.key(),
+            pot_claim_window_secs,
         });
-    
+
         Ok(())
     }
 
-    /// Initializes the game queues for a 4 player game.
-    pub fn init_four_player_game_queue(ctx: Context<InitFourPlayerGameQueue>) -> ProgramResult {
-        let player_account = &mut ctx.accounts.player_account;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-        let game_queue_account_three = &mut ctx.accounts.game_queue_account_three;
-        let game_queue_account_four = &mut ctx.accounts.game_queue_account_four;
-        let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
+    /// This function sets the arcade's fee, the share (in basis points) of every payout that is taken off the top and collected
+    /// in the arcade's treasury before the game's payout schedule splits the rest.
+    pub fn set_arcade_fee(ctx: Context<SetArcadeFee>, arcade_fee_bps: u16) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Treasurer, ctx.remaining_accounts, ctx.program_id)?;
 
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
-        );
+        if arcade_fee_bps > BASIS_POINTS {
+            return Err(Errors::InvalidArcadeFee.into());
+        }
 
-        anchor_lang::solana_program::program::invoke(
-            &ix,
-            &[
-                payer.to_account_info(),
-                game_account.to_account_info(),
-            ],
-        )?;
+        let arcade_account = &mut ctx.accounts.arcade_account;
 
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
+        arcade_account.arcade_fee_bps = arcade_fee_bps;
 
-        game_queue_account_one.game = game_account.key();
-        game_queue_account_one.current_player = player_account.key();
-        game_queue_account_one.last_player = player_account.key();
-        game_queue_account_one.num_players_in_queue = 1;
-
-        game_queue_account_two.game = game_account.key();
-        game_queue_account_two.current_player = game_account.key();
-        game_queue_account_two.last_player = game_account.key();
-        game_queue_account_two.num_players_in_queue = 0;
-
-        game_queue_account_three.game = game_account.key();
-        game_queue_account_three.current_player = game_account.key();
-        game_queue_account_three.last_player = game_account.key();
-        game_queue_account_three.num_players_in_queue = 0;
-
-        game_queue_account_four.game = game_account.key();
-        game_queue_account_four.current_player = game_account.key();
-        game_queue_account_four.last_player = game_account.key();
-        game_queue_account_four.num_players_in_queue = 0;
-
-        if game_account.game_queues.len() == 0 {
-            game_account.game_queues.push(game_queue_account_one.key());
-            game_account.game_queues.push(game_queue_account_two.key());
-            game_account.game_queues.push(game_queue_account_three.key());
-            game_account.game_queues.push(game_queue_account_four.key());
-        } else {
-            game_account.game_queues[0] = game_queue_account_one.key();
-            game_account.game_queues[1] = game_queue_account_two.key();
-            game_account.game_queues[2] = game_queue_account_three.key();
-            game_account.game_queues[3] = game_queue_account_four.key();
+        // emit an event to make known the arcade fee has changed
+        emit!(ArcadeFeeEvent {
+            arcade_id: arcade_account.key(),
+            arcade_fee_bps,
+        });
+
+        Ok(())
+    }
+
+    /// This function moves lamports out of the arcade's treasury, always leaving its rent behind.
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, lamports: u64) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Treasurer, ctx.remaining_accounts, ctx.program_id)?;
+
+        let treasury = &ctx.accounts.treasury;
+        let destination = &ctx.accounts.destination;
+
+        if lamports > treasury_balance(&treasury.to_account_info())? {
+            return Err(Errors::InsufficientTreasuryFunds.into());
         }
 
-        // emit an event to make known the queues were initialized
-        emit!(NewQueueEvent {
-            game_id: game_account.key(),
-            queue_ids: vec![
-                game_queue_account_one.key(),
-                game_queue_account_two.key(),
-                game_queue_account_three.key(),
-                game_queue_account_four.key(),
-            ],
-            queue_nums: vec![1, 2, 3, 4],
-            new_player_ids: vec![
-                Some(player_account.key()),
-                None,
-                None,
-                None,
-            ],
-            num_players: vec![1, 0, 0, 0],
+        let treasury_info = treasury.to_account_info();
+        **treasury_info.lamports.borrow_mut() = treasury_info.lamports() - lamports;
+        credit_lamports(&destination.to_account_info(), lamports)?;
+
+        // emit an event to make known funds have left the treasury
+        emit!(TreasuryEvent {
+            label: "WITHDRAW".to_string(),
+            arcade_id: treasury.arcade,
+            treasury_id: treasury.key(),
+            lamports,
+            tokens: 0,
+            mint: None,
         });
-        
+
         Ok(())
     }
 
-    /// Joins a player in to a game queue for a normal 4 player game.
-    /// 
-    /// Players are added from left to right to keep order.
-    pub fn join_four_player_game_queue(ctx: Context<JoinFourPlayerGameQueue>) -> Result<()> {
-        let player_account = &mut ctx.accounts.player_account;
-        let q1_last_player = &mut ctx.accounts.q1_last_player;
-        let q2_last_player = &mut ctx.accounts.q2_last_player;
-        let q3_last_player = &mut ctx.accounts.q3_last_player;
-        let q4_last_player = &mut ctx.accounts.q4_last_player;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-        let game_queue_account_three = &mut ctx.accounts.game_queue_account_three;
-        let game_queue_account_four = &mut ctx.accounts.game_queue_account_four;
-        let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
+    /// This function moves tokens out of one of the treasury's token accounts (a token account whose owner is the treasury).
+    pub fn withdraw_treasury_tokens(ctx: Context<WithdrawTreasuryTokens>, amount: u64) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Treasurer, ctx.remaining_accounts, ctx.program_id)?;
 
-        if game_queue_account_two.current_player != game_account.key() &&
-           game_queue_account_two.last_player != q2_last_player.key() {
-            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-        } else if game_queue_account_three.current_player != game_account.key() &&
-                  game_queue_account_three.last_player != q3_last_player.key() {
-            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-        } else if game_queue_account_four.current_player != game_account.key() &&
-                  game_queue_account_four.last_player != q4_last_player.key() {
-            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
+        let treasury = &ctx.accounts.treasury;
+        let treasury_token_account = &ctx.accounts.treasury_token_account;
+
+        let token_account = unpack_token_account(treasury_token_account)?;
+        if token_account.owner != treasury.key() {
+            return Err(Errors::WrongTreasuryTokenAccount.into());
         }
 
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
-        );
+        let (_, bump) = Pubkey::find_program_address(&[b"treasury", treasury.arcade.as_ref()], ctx.program_id);
+        transfer_tokens(
+            &ctx.accounts.token_program,
+            treasury_token_account,
+            &ctx.accounts.destination_token_account,
+            &treasury.to_account_info(),
+            amount,
+            &[&[b"treasury", treasury.arcade.as_ref(), &[bump]]],
+        )?;
+
+        // emit an event to make known tokens have left the treasury
+        emit!(TreasuryEvent {
+            label: "WITHDRAW".to_string(),
+            arcade_id: treasury.arcade,
+            treasury_id: treasury.key(),
+            lamports: 0,
+            tokens: amount,
+            mint: Some(token_account.mint),
+        });
+
+        Ok(())
+    }
+
+    /// This function creates the escrow token account of a game, which holds the tokens paid to play a game priced in tokens
+    /// until they are paid out.
+    /// 
+    /// The escrow's address is derived from the game and it is its own token authority, so only this program can move the
+    /// tokens out of it.  A game has a single escrow, so its token price has to stay in the escrow's mint.
+    pub fn init_token_escrow(ctx: Context<InitTokenEscrow>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
+        let escrow_account = &ctx.accounts.escrow_account;
+        let mint = &ctx.accounts.mint;
+        let owner = &ctx.accounts.owner;
+        let system_program = &ctx.accounts.system_program;
+
+        let game_key = game_account.key();
+        let bump = *ctx.bumps.get("escrow_account").unwrap();
+        create_account(
+            &owner.to_account_info(),
+            &escrow_account.to_account_info(),
+            spl_token::state::Account::LEN,
+            &spl_token::ID,
+            &system_program.to_account_info(),
+            &[&[b"escrow", game_key.as_ref(), &[bump]]],
+        )?;
 
+        let ix = spl_token::instruction::initialize_account3(&spl_token::ID, escrow_account.key, mint.key, escrow_account.key)?;
         anchor_lang::solana_program::program::invoke(
             &ix,
             &[
-                payer.to_account_info(),
-                game_account.to_account_info(),
+                escrow_account.to_account_info(),
+                mint.to_account_info(),
             ],
         )?;
 
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
+        game_account.escrow = Some(escrow_account.key());
 
-        if game_queue_account_two.current_player == game_account.key() {
-            // Add a new player as player 2
-            game_queue_account_two.current_player = player_account.key();
-            game_queue_account_two.last_player = player_account.key();
-            game_queue_account_two.num_players_in_queue = 1;
+        Ok(())
+    }
 
-            // emit an event to make known a player has joined game queue 2
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_two.key(),
-                queue_num: 2,
-                last_player: player_account.key(),
-                num_players: 1,
-            });
-        } else if game_queue_account_three.current_player == game_account.key() {
-            // Add a new player as player 3
-            game_queue_account_three.current_player = player_account.key();
-            game_queue_account_three.last_player = player_account.key();
-            game_queue_account_three.num_players_in_queue = 1;
-
-            // emit an event to make known a player has joined game queue 3
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_three.key(),
-                queue_num: 3,
-                last_player: player_account.key(),
-                num_players: 1,
-            });
-        } else if game_queue_account_four.current_player == game_account.key() {
-            // Add a new player as player 4
-            game_queue_account_four.current_player = player_account.key();
-            game_queue_account_four.last_player = player_account.key();
-            game_queue_account_four.num_players_in_queue = 1;
-
-            // emit an event to make known a player has joined game queue 4
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_four.key(),
-                queue_num: 4,
-                last_player: player_account.key(),
-                num_players: 1,
-            });
-        } else if game_queue_account_one.num_players_in_queue == game_queue_account_two.num_players_in_queue &&
-                  game_queue_account_two.num_players_in_queue == game_queue_account_three.num_players_in_queue &&
-                  game_queue_account_three.num_players_in_queue == game_queue_account_four.num_players_in_queue {
-            // Add a new player behind player 1.
-            q1_last_player.next_player = Some(player_account.key());
-            game_queue_account_one.last_player = player_account.key();
-            game_queue_account_one.num_players_in_queue += 1;
-
-            // emit an event to make known a player has joined game queue 1
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_one.key(),
-                queue_num: 1,
-                last_player: player_account.key(),
-                num_players: game_queue_account_one.num_players_in_queue,
-            });
-        } else if game_queue_account_two.num_players_in_queue == game_queue_account_three.num_players_in_queue &&
-                  game_queue_account_three.num_players_in_queue == game_queue_account_four.num_players_in_queue {
-            // Add a new player behind player 2
-            q2_last_player.next_player = Some(player_account.key());
-            game_queue_account_two.last_player = player_account.key();
-            game_queue_account_two.num_players_in_queue += 1;
-
-            // emit an event to make known a player has joined game queue 2
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_two.key(),
-                queue_num: 2,
-                last_player: player_account.key(),
-                num_players: game_queue_account_two.num_players_in_queue,
-            });
-        } else if game_queue_account_three.num_players_in_queue == game_queue_account_four.num_players_in_queue {
-            // Add a new player behind player 3
-            q3_last_player.next_player = Some(player_account.key());
-            game_queue_account_three.last_player = player_account.key();
-            game_queue_account_three.num_players_in_queue += 1;
-
-            // emit an event to make known a player has joined game queue 3
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_three.key(),
-                queue_num: 3,
-                last_player: player_account.key(),
-                num_players: game_queue_account_three.num_players_in_queue,
-            });
-        } else {
-            q4_last_player.next_player = Some(player_account.key());
-            game_queue_account_four.last_player = player_account.key();
-            game_queue_account_four.num_players_in_queue += 1;
+    /// This function sets the SOL/USD price feed used to convert the play fee to lamports and how fresh and certain its
+    /// prices have to be.
+    /// 
+    /// The feed has to hold a usable price when it is set, so a feed of the wrong kind can never be configured.
+    pub fn set_price_feed(
+        ctx: Context<SetPriceFeed>,
+        price_feed_kind: PriceFeedKind,
+        max_price_age_secs: u32,
+        max_price_confidence_bps: u16,
+    ) -> Result<()> {
+        authorize_admin(&ctx.accounts.arcade_account, &ctx.accounts.authority.key(), Role::Curator, ctx.remaining_accounts, ctx.program_id)?;
 
-            // emit an event to make known a player has joined game queue 4
-            emit!(JoinQueueEvent {
-                game_id: game_account.key(),
-                queue_id: game_queue_account_four.key(),
-                queue_num: 4,
-                last_player: player_account.key(),
-                num_players: game_queue_account_four.num_players_in_queue,
-            });
-        }
+        let arcade_account = &mut ctx.accounts.arcade_account;
+        let price_feed = &ctx.accounts.price_feed;
+
+        let sol_price = SolPrice::load(price_feed_kind, price_feed)?;
+        sol_price.check(Clock::get()?.unix_timestamp, max_price_age_secs, max_price_confidence_bps)?;
+
+        arcade_account.price_feed = price_feed.key();
+        arcade_account.price_feed_kind = price_feed_kind;
+        arcade_account.max_price_age_secs = max_price_age_secs;
+        arcade_account.max_price_confidence_bps = max_price_confidence_bps;
 
         Ok(())
     }
 
-    /// Advances a normal 4 player game queue
+    /// This function creates a price feed whose price is written by hand.
     /// 
-    /// I J K L ->         -> 
-    /// E F G H -> I J K L -> 
-    /// A B C D -> E F G H -> I J K L 
-    pub fn advance_four_player_game_queue(ctx: Context<AdvanceFourPlayerGameQueue>) -> Result<()> {
-        let player_one = &mut ctx.accounts.player_one;
-        let player_two = &mut ctx.accounts.player_two;
-        let player_three = &mut ctx.accounts.player_three;
-        let player_four = &mut ctx.accounts.player_four;
-        let game_queue_account_one = &mut ctx.accounts.game_queue_account_one;
-        let game_queue_account_two = &mut ctx.accounts.game_queue_account_two;
-        let game_queue_account_three = &mut ctx.accounts.game_queue_account_three;
-        let game_queue_account_four = &mut ctx.accounts.game_queue_account_four;
-        let game_account = & ctx.accounts.game_account;
-
-        (game_queue_account_one.current_player, game_queue_account_one.last_player, game_queue_account_one.num_players_in_queue) = match player_one.next_player {
-            Some(player) => (player, game_queue_account_one.last_player, game_queue_account_one.num_players_in_queue - 1),
-            None => return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into()),
-        };
+    /// Useful for local testing and for clusters without a Pyth feed.
+    pub fn init_manual_price_feed(ctx: Context<InitManualPriceFeed>, price: i64, conf: u64, expo: i32) -> Result<()> {
+        let price_feed = &mut ctx.accounts.price_feed;
+        let authority = &mut ctx.accounts.authority;
 
-        (game_queue_account_two.current_player, game_queue_account_two.last_player, game_queue_account_two.num_players_in_queue) = match player_two.next_player {
-            Some(player) => (player, game_queue_account_two.last_player, game_queue_account_two.num_players_in_queue - 1),
-            None => (game_account.key(), game_account.key(), 0),
-        };
+        price_feed.authority = authority.key();
+        price_feed.price = price;
+        price_feed.conf = conf;
+        price_feed.expo = expo;
+        price_feed.publish_time = Clock::get()?.unix_timestamp;
 
-        (game_queue_account_three.current_player, game_queue_account_three.last_player, game_queue_account_three.num_players_in_queue) = match player_three.next_player {
-            Some(player) => (player, game_queue_account_three.last_player, game_queue_account_three.num_players_in_queue - 1),
-            None => (game_account.key(), game_account.key(), 0),
-        };
+        Ok(())
+    }
 
-        (game_queue_account_four.current_player, game_queue_account_four.last_player, game_queue_account_four.num_players_in_queue) = match player_four.next_player {
-            Some(player) => (player, game_queue_account_four.last_player, game_queue_account_four.num_players_in_queue - 1),
-            None => (game_account.key(), game_account.key(), 0),
-        };
+    /// This function publishes a new price to a manual price feed.
+    pub fn update_manual_price_feed(ctx: Context<UpdateManualPriceFeed>, price: i64, conf: u64, expo: i32) -> Result<()> {
+        let price_feed = &mut ctx.accounts.price_feed;
 
-        // emit an event to make known that the game queue has been advanced
-        emit!(AdvanceQueueEvent {
+        price_feed.price = price;
+        price_feed.conf = conf;
+        price_feed.expo = expo;
+        price_feed.publish_time = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+
+    /// This function deletes a game, while making sure that the person deleting the machine/game is
+    /// the person who owns it.  I would like anyone to upload whatever they want onto the arcade which
+    /// may come back to bite me, but I think this is the best way to promote an open space.
+    pub fn delete_game(ctx: Context<DeleteGame>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
+        let younger_game = &mut ctx.accounts.younger_game;
+        let older_game = &mut ctx.accounts.older_game;
+
+        younger_game.older_game_key = older_game.key();
+        older_game.younger_game_key = younger_game.key();
+
+        emit!(GameEvent {
+            label: "DELETE".to_string(),
             game_id: game_account.key(),
-            queue_ids: vec![
-                game_queue_account_one.key(),
-                game_queue_account_two.key(),
-                game_queue_account_three.key(),
-                game_queue_account_four.key(),
-            ],
-            queue_nums: vec![1, 2, 3, 4],
-            finished_players: vec![
-                Some(player_one.key()),
-                Some(player_two.key()),
-                Some(player_three.key()),
-                Some(player_four.key()),
-            ],
-            new_players: vec![
-                player_one.next_player,
-                player_two.next_player,
-                player_three.next_player,
-                player_four.next_player,
-            ],
-            num_players: vec![
-                game_queue_account_one.num_players_in_queue,
-                game_queue_account_two.num_players_in_queue,
-                game_queue_account_three.num_players_in_queue,
-                game_queue_account_four.num_players_in_queue,
-            ],
+            more_recent_game_id: Some(younger_game.key()),
+            less_recent_game_id: Some(older_game.key()),
         });
 
         Ok(())
     }
 
-    /// Advances a 4 player king of the hill game.
+    /// This game deletes the most recent game.
     /// 
-    /// I J K L -> I       -> I       ->         ->         ->
-    /// E F G H -> E J K L -> E       ->   I     ->         -> 
-    /// A B C D -> A F G H -> A J K L -> A E _ _ -> A I _ _ -> A
-    pub fn advance_four_player_king_of_hill_queue(ctx: Context<AdvanceFourPlayerKingOfHillQueue>) -> Result<()> {
-        let winning_player = &mut ctx.accounts.winning_player;
-        let losing_player_one = &mut ctx.accounts.losing_player_one;
-        let losing_player_two = &mut ctx.accounts.losing_player_two;
-        let losing_player_three = &mut ctx.accounts.losing_player_three;
-        let queues = [
-            &mut ctx.accounts.game_queue_account_one,
-            &mut ctx.accounts.game_queue_account_two,
-            &mut ctx.accounts.game_queue_account_three,
-            &mut ctx.accounts.game_queue_account_four
-        ];
-        let game_account = & ctx.accounts.game_account;
-        let mut player_mappings = [0, 0, 0, 0];
-        let mut winning_queue_num = 0;
-
-        for i in 0..4 {
-            if queues[i].current_player == winning_player.key() {
-                player_mappings[i] = 1;
-                winning_queue_num = i;
-            } else if queues[i].current_player == losing_player_one.key() {
-                player_mappings[i] = 2;
-            } else if queues[i].current_player == losing_player_two.key() {
-                player_mappings[i] = 3;
-            } else if queues[i].current_player == losing_player_three.key() {
-                player_mappings[i] = 4;
-            } else if queues[i].current_player == game_account.key() {
-                player_mappings[i] = 5;
-            } else {
-                return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-            }
-        }
+    /// I'm going to be completely honest, I think I'm going to delete this and instead make the arcade a 
+    /// circular linked list, but this is here for now.
+    pub fn delete_most_recent_game(ctx: Context<DeleteMostRecentGame>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
+        let older_game = &mut ctx.accounts.older_game;
+        let arcade_state = &mut ctx.accounts.arcade_state;
 
-        let mut queue_ids = Vec::with_capacity(3);
-        let mut queue_nums = Vec::with_capacity(3);
-        let mut finished_players = Vec::with_capacity(3);
-        let mut new_players = Vec::with_capacity(3);
-        let mut num_players = Vec::with_capacity(3);
-
-        for i in 0..4 {
-            match player_mappings[i] {
-                2 => {
-                    (queues[i].current_player, queues[i].last_player, queues[i].num_players_in_queue) = match losing_player_one.next_player {
-                        Some(player) => (player, queues[i].last_player, queues[i].num_players_in_queue - 1),
-                        None => match winning_player.next_player {
-                            Some(player) => {
-                                let old_last_player = queues[winning_queue_num].last_player;
-                                let old_num_players = queues[winning_queue_num].num_players_in_queue - 1;
-
-                                winning_player.next_player = None;
-                                queues[winning_queue_num].last_player = winning_player.key();
-                                queues[winning_queue_num].num_players_in_queue = 1;
-
-                                (player, old_last_player, old_num_players)
-                            },
-                            None => (game_account.key(), game_account.key(), 0),
-                        },
-                    };
-
-                    queue_ids.push(queues[i].key());
-                    queue_nums.push(i as u8);
-                    finished_players.push(Some(losing_player_one.key()));
-                    new_players.push(if queues[i].current_player != game_account.key() { Some(queues[i].current_player) } else { None });
-                    num_players.push(queues[i].num_players_in_queue);
-                },
-                3 => {
-                    (queues[i].current_player, queues[i].last_player, queues[i].num_players_in_queue) = match losing_player_two.next_player {
-                        Some(player) => (player, queues[i].last_player, queues[i].num_players_in_queue - 1),
-                        None => match winning_player.next_player {
-                            Some(player) => {
-                                let old_last_player = queues[winning_queue_num].last_player;
-                                let old_num_players = queues[winning_queue_num].num_players_in_queue - 1;
-
-                                winning_player.next_player = None;
-                                queues[winning_queue_num].last_player = winning_player.key();
-                                queues[winning_queue_num].num_players_in_queue = 1;
-
-                                (player, old_last_player, old_num_players)
-                            },
-                            None => (game_account.key(), game_account.key(), 0),
-                        },
-                    };
-
-                    queue_ids.push(queues[i].key());
-                    queue_nums.push(i as u8);
-                    finished_players.push(Some(losing_player_two.key()));
-                    new_players.push(if queues[i].current_player != game_account.key() { Some(queues[i].current_player) } else { None });
-                    num_players.push(queues[i].num_players_in_queue);
-                },
-                4 => {
-                    (queues[i].current_player, queues[i].last_player, queues[i].num_players_in_queue) = match losing_player_three.next_player {
-                        Some(player) => (player, queues[i].last_player, queues[i].num_players_in_queue - 1),
-                        None => match winning_player.next_player {
-                            Some(player) => {
-                                let old_last_player = queues[winning_queue_num].last_player;
-                                let old_num_players = queues[winning_queue_num].num_players_in_queue - 1;
-
-                                winning_player.next_player = None;
-                                queues[winning_queue_num].last_player = winning_player.key();
-                                queues[winning_queue_num].num_players_in_queue = 1;
-
-                                (player, old_last_player, old_num_players)
-                            },
-                            None => (game_account.key(), game_account.key(), 0),
-                        },
-                    };
-
-                    queue_ids.push(queues[i].key());
-                    queue_nums.push(i as u8);
-                    finished_players.push(Some(losing_player_three.key()));
-                    new_players.push(if queues[i].current_player != game_account.key() { Some(queues[i].current_player) } else { None });
-                    num_players.push(queues[i].num_players_in_queue);
-                },
-                _ => continue,
-            }
-        }
+        older_game.younger_game_key = older_game.key();
 
-        // emit an event to make known that the game queue has advanced
-        emit!(AdvanceQueueEvent {
+        arcade_state.most_recent_game_key = older_game.key();
+
+        emit!(GameEvent {
+            label: "DELETE".to_string(),
             game_id: game_account.key(),
-            queue_ids: queue_ids,
-            queue_nums: queue_nums,
-            finished_players: finished_players,
-            new_players: new_players,
-            num_players: num_players,
+            more_recent_game_id: None,
+            less_recent_game_id: Some(older_game.key()),
         });
 
         Ok(())
     }
 
-    /// We have given up on making the queue flow smoothly, basically you will register in queue for a specific team and wait for the people in front of
-    /// you to fail.
+    /// This function initializes the game queues of a game with the paying player at the front of the first queue.
     /// 
-    /// TODO: Maybe we can fix the queue advancing but it honestly looks quite difficult.
-    pub fn advance_team_king_of_hill_queue(ctx: Context<AdvanceTeamKingOfHillQueue>) -> Result<()> {
-        let winning_player_one = &mut ctx.accounts.winning_player_one;
-        let winning_player_two = &mut ctx.accounts.winning_player_two;
-        let losing_player_one = &mut ctx.accounts.losing_player_one;
-        let losing_player_two = &mut ctx.accounts.losing_player_two;
-        let queues = [
-            &mut ctx.accounts.game_queue_account_one,
-            &mut ctx.accounts.game_queue_account_two,
-            &mut ctx.accounts.game_queue_account_three,
-            &mut ctx.accounts.game_queue_account_four
-        ];
-        let game_account = & ctx.accounts.game_account;
-        let mut player_mappings = [0, 0, 0, 0];
-        let mut winning_queue_nums = [0, 0];
-
-        for i in 0..4 {
-            if queues[i].current_player == winning_player_one.key() {
-                player_mappings[i] = 1;
-                winning_queue_nums[0] = i;
-            } else if queues[i].current_player == winning_player_two.key() {
-                player_mappings[i] = 2;
-                winning_queue_nums[1] = i;
-            } else if queues[i].current_player == losing_player_one.key() {
-                player_mappings[i] = 3;
-            } else if queues[i].current_player == losing_player_two.key() {
-                player_mappings[i] = 4;
-            } else if queues[i].current_player == game_account.key() {
-                player_mappings[i] = 5;
-            } else {
-                return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
-            }
+    /// The new queue accounts (one for every player of the game, in queue order) are passed as remaining accounts.  The person
+    /// initializing the queues will pay the play fee as well as for the created accounts.
+    pub fn init_game_queue<'info>(ctx: Context<'_, '_, '_, 'info, InitGameQueue<'info>>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let fee = charge_play_fee(&accounts.game_account, &accounts.arcade_account, &accounts.price_feed, &accounts.payer)?;
+        let wallet = accounts.payer.key();
+        accounts.init_queues(ctx.remaining_accounts, ctx.program_id, fee, wallet)
+    }
+
+    /// Same as init_game_queue, but the play is paid in the game's token.
+    pub fn init_game_queue_with_tokens<'info>(ctx: Context<'_, '_, '_, 'info, InitGameQueueWithTokens<'info>>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let fee = accounts.payment.charge(&accounts.queue.game_account, &accounts.queue.arcade_account, &accounts.queue.payer)?;
+        let wallet = accounts.queue.payer.key();
+        accounts.queue.init_queues(ctx.remaining_accounts, ctx.program_id, fee, wallet)
+    }
+
+    /// Same as init_game_queue, but the play is paid from the prepaid credits of the wallet that will be playing, which can be
+    /// spent by that wallet or by its kiosk.  The payer only pays for the created accounts.
+    pub fn init_game_queue_with_credits<'info>(ctx: Context<'_, '_, '_, 'info, InitGameQueueWithCredits<'info>>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let fee = accounts.credits.charge(&accounts.queue.game_account, &accounts.queue.arcade_account, &accounts.queue.price_feed, &accounts.queue.payer)?;
+        let wallet = accounts.credits.player_credits.wallet;
+        accounts.queue.init_queues(ctx.remaining_accounts, ctx.program_id, fee, wallet)
+    }
+
+    /// This function allows a user to join the queues of a normal game.
+    /// 
+    /// The game's queues are passed (in queue order) as remaining accounts, followed by the last player of the queue the user
+    /// will join unless that queue is empty.  Players fill the empty queues first and then line up behind the shortest queue.
+    pub fn join_game_queue<'info>(ctx: Context<'_, '_, '_, 'info, JoinGameQueue<'info>>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let fee = charge_play_fee(&accounts.game_account, &accounts.arcade_account, &accounts.price_feed, &accounts.payer)?;
+        let wallet = accounts.payer.key();
+        accounts.join_queue(ctx.remaining_accounts, ctx.program_id, fee, wallet)
+    }
+
+    /// Same as join_game_queue, but the play is paid in the game's token.
+    pub fn join_game_queue_with_tokens<'info>(ctx: Context<'_, '_, '_, 'info, JoinGameQueueWithTokens<'info>>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let fee = accounts.payment.charge(&accounts.queue.game_account, &accounts.queue.arcade_account, &accounts.queue.payer)?;
+        let wallet = accounts.queue.payer.key();
+        accounts.queue.join_queue(ctx.remaining_accounts, ctx.program_id, fee, wallet)
+    }
+
+    /// Same as join_game_queue, but the play is paid from the prepaid credits of the wallet that will be playing.
+    pub fn join_game_queue_with_credits<'info>(ctx: Context<'_, '_, '_, 'info, JoinGameQueueWithCredits<'info>>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let fee = accounts.credits.charge(&accounts.queue.game_account, &accounts.queue.arcade_account, &accounts.queue.price_feed, &accounts.queue.payer)?;
+        let wallet = accounts.credits.player_credits.wallet;
+        accounts.queue.join_queue(ctx.remaining_accounts, ctx.program_id, fee, wallet)
+    }
+
+    /// Advances a normal game queue, every current player is done and the players behind them are up next.
+    /// 
+    /// E F -> 
+    /// C D -> E F -> 
+    /// A B -> C D -> E F
+    /// 
+    /// The game's queues are passed (in queue order) as remaining accounts, followed by the current player of every queue that
+    /// is not empty and then the session receipt of every one of those players.  A game where nobody is waiting anymore is
+    /// finished with finish_game_queue instead.
+    pub fn advance_game_queue<'info>(ctx: Context<'_, '_, '_, 'info, AdvanceGameQueue<'info>>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
+        let mut queues = load_game_queues(game_account, ctx.remaining_accounts)?;
+        let players = load_current_players(&queues, &ctx.remaining_accounts[queues.len()..])?;
+        let mut receipt_accounts = ctx.remaining_accounts[(queues.len() + players.iter().flatten().count())..].iter();
+
+        if !players.iter().flatten().any(|player| player.next_player.is_some()) {
+            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
         }
 
-        let mut queue_ids = Vec::with_capacity(2);
-        let mut queue_nums = Vec::with_capacity(2);
-        let mut finished_players = Vec::with_capacity(2);
-        let mut new_players = Vec::with_capacity(2);
-        let mut num_players = Vec::with_capacity(2);
-
-        for i in [0, 2] {
-            if player_mappings[i] == 3 {
-                (
-                    queues[i].current_player, queues[i].last_player, queues[i].num_players_in_queue,
-                    queues[i+1].current_player, queues[i+1].last_player, queues[i+1].num_players_in_queue
-                ) = match (losing_player_one.next_player, losing_player_two.next_player) {
-                    (Some(player_one), Some(player_two)) => (
-                        player_one, queues[i].last_player, queues[i].num_players_in_queue - 1,
-                        player_two, queues[i+1].last_player, queues[i+1].num_players_in_queue - 1
-                    ),
-                    (Some(player_one), None) => (
-                        player_one, queues[i].last_player, queues[i].num_players_in_queue - 1,
-                        game_account.key(), game_account.key(), 0
-                    ),
-                    (None, Some(player_two)) => (
-                        game_account.key(), game_account.key(), 0,
-                        player_two, queues[i+1].last_player, queues[i+1].num_players_in_queue - 1,
-                    ),
-                    (None, None) => {
-                        match (winning_player_one.next_player, winning_player_two.next_player) {
-                            (Some(player_one), Some(player_two)) => {
-                                if player_one != player_two {
-                                    let old_last_player_one = queues[winning_queue_nums[0]].last_player;
-                                    let old_last_player_two = queues[winning_queue_nums[1]].last_player;
-                                    let old_num_players_one = queues[winning_queue_nums[0]].num_players_in_queue - 1;
-                                    let old_num_players_two = queues[winning_queue_nums[1]].num_players_in_queue - 1;
-
-                                    winning_player_one.next_player = None;
-                                    winning_player_two.next_player = None;
-                                    queues[winning_queue_nums[0]].num_players_in_queue = 1;
-                                    queues[winning_queue_nums[1]].num_players_in_queue = 1;
-                                    queues[winning_queue_nums[0]].last_player = winning_player_one.key();
-                                    queues[winning_queue_nums[1]].last_player = winning_player_two.key();
-
-                                    (
-                                        player_one, old_last_player_one, old_num_players_one,
-                                        player_two, old_last_player_two, old_num_players_two
-                                    )
-                                } else {
-                                    (
-                                        game_account.key(), game_account.key(), 0,
-                                        game_account.key(), game_account.key(), 0
-                                    )
-                                }
-                            },
-                            (Some(player_one), None) => {
-                                let old_last_player_one = queues[winning_queue_nums[0]].last_player;
-                                let old_num_players_one = queues[winning_queue_nums[0]].num_players_in_queue - 1;
-
-                                winning_player_one.next_player = None;
-                                queues[winning_queue_nums[0]].num_players_in_queue = 1;
-                                queues[winning_queue_nums[0]].last_player = winning_player_one.key();
-
-                                (
-                                    player_one, old_last_player_one, old_num_players_one,
-                                    game_account.key(), game_account.key(), 0
-                                )
-                            },
-                            (None, Some(player_two)) => {
-                                let old_last_player_two = queues[winning_queue_nums[1]].last_player;
-                                let old_num_players_two = queues[winning_queue_nums[1]].num_players_in_queue - 1;
-
-                                winning_player_two.next_player = None;
-                                queues[winning_queue_nums[1]].num_players_in_queue = 1;
-                                queues[winning_queue_nums[1]].last_player = winning_player_two.key();
-
-                                (
-                                    game_account.key(), game_account.key(), 0,
-                                    player_two, old_last_player_two, old_num_players_two
-                                )
-                            },
-                            (None, None) => (
-                                game_account.key(), game_account.key(), 0,
-                                game_account.key(), game_account.key(), 0
-                            ),
-                        }
-                    },
-                };
-
-                queue_ids.push(queues[i].key());
-                queue_ids.push(queues[i+1].key());
-                queue_nums.push((i+1) as u8);
-                queue_nums.push((i+2) as u8);
-                finished_players.push(Some(losing_player_one.key()));
-                finished_players.push(Some(losing_player_two.key()));
-                new_players.push(if queues[i].current_player != game_account.key() { Some(queues[i].current_player) } else { None });
-                new_players.push(if queues[i+1].current_player != game_account.key() { Some(queues[i+1].current_player) } else { None });
-                num_players.push(queues[i].num_players_in_queue);
-                num_players.push(queues[i+1].num_players_in_queue);
-            } else if player_mappings[i] == 4 {
-                (
-                    queues[i].current_player, queues[i].last_player, queues[i].num_players_in_queue,
-                    queues[i+1].current_player, queues[i+1].last_player, queues[i+1].num_players_in_queue
-                ) = match (losing_player_two.next_player, losing_player_one.next_player) {
-                    (Some(player_one), Some(player_two)) => (
-                        player_one, queues[i].last_player, queues[i].num_players_in_queue - 1,
-                        player_two, queues[i+1].last_player, queues[i+1].num_players_in_queue - 1
-                    ),
-                    (Some(player_one), None) => (
-                        player_one, queues[i].last_player, queues[i].num_players_in_queue - 1,
-                        game_account.key(), game_account.key(), 0
-                    ),
-                    (None, Some(player_two)) => (
-                        game_account.key(), game_account.key(), 0,
-                        player_two, queues[i+1].last_player, queues[i+1].num_players_in_queue - 1,
-                    ),
-                    (None, None) => {
-                        match (winning_player_one.next_player, winning_player_two.next_player) {
-                            (Some(player_one), Some(player_two)) => {
-                                if player_one != player_two {
-                                    let old_last_player_one = queues[winning_queue_nums[0]].last_player;
-                                    let old_last_player_two = queues[winning_queue_nums[1]].last_player;
-                                    let old_num_players_one = queues[winning_queue_nums[0]].num_players_in_queue - 1;
-                                    let old_num_players_two = queues[winning_queue_nums[1]].num_players_in_queue - 1;
-
-                                    winning_player_one.next_player = None;
-                                    winning_player_two.next_player = None;
-                                    queues[winning_queue_nums[0]].num_players_in_queue = 1;
-                                    queues[winning_queue_nums[1]].num_players_in_queue = 1;
-                                    queues[winning_queue_nums[0]].last_player = winning_player_one.key();
-                                    queues[winning_queue_nums[1]].last_player = winning_player_two.key();
-
-                                    (
-                                        player_one, old_last_player_one, old_num_players_one,
-                                        player_two, old_last_player_two, old_num_players_two
-                                    )
-                                } else {
-                                    (
-                                        game_account.key(), game_account.key(), 0,
-                                        game_account.key(), game_account.key(), 0
-                                    )
-                                }
-                            },
-                            (Some(player_one), None) => {
-                                let old_last_player_one = queues[winning_queue_nums[0]].last_player;
-                                let old_num_players_one = queues[winning_queue_nums[0]].num_players_in_queue - 1;
-
-                                winning_player_one.next_player = None;
-                                queues[winning_queue_nums[0]].num_players_in_queue = 1;
-                                queues[winning_queue_nums[0]].last_player = winning_player_one.key();
-
-                                (
-                                    player_one, old_last_player_one, old_num_players_one,
-                                    game_account.key(), game_account.key(), 0
-                                )
-                            },
-                            (None, Some(player_two)) => {
-                                let old_last_player_two = queues[winning_queue_nums[1]].last_player;
-                                let old_num_players_two = queues[winning_queue_nums[1]].num_players_in_queue - 1;
-
-                                winning_player_two.next_player = None;
-                                queues[winning_queue_nums[1]].num_players_in_queue = 1;
-                                queues[winning_queue_nums[1]].last_player = winning_player_two.key();
-
-                                (
-                                    game_account.key(), game_account.key(), 0,
-                                    player_two, old_last_player_two, old_num_players_two
-                                )
-                            },
-                            (None, None) => (
-                                game_account.key(), game_account.key(), 0,
-                                game_account.key(), game_account.key(), 0
-                            ),
-                        }
-                    },
-                };
-
-                queue_ids.push(queues[i].key());
-                queue_ids.push(queues[i+1].key());
-                queue_nums.push((i+1) as u8);
-                queue_nums.push((i+2) as u8);
-                finished_players.push(Some(losing_player_one.key()));
-                finished_players.push(Some(losing_player_two.key()));
-                new_players.push(if queues[i].current_player != game_account.key() { Some(queues[i].current_player) } else { None });
-                new_players.push(if queues[i+1].current_player != game_account.key() { Some(queues[i+1].current_player) } else { None });
-                num_players.push(queues[i].num_players_in_queue);
-                num_players.push(queues[i+1].num_players_in_queue);
+        let now = Clock::get()?.unix_timestamp;
+        let finishing_wallets: Vec<Pubkey> = players.iter().flatten().map(|player| player.wallet_key).collect();
+        check_queue_authority(game_account, &ctx.accounts.authority.key(), &queues, &finishing_wallets, now)?;
+
+        let mut queue_ids = Vec::with_capacity(queues.len());
+        let mut queue_nums = Vec::with_capacity(queues.len());
+        let mut finished_players = Vec::with_capacity(queues.len());
+        let mut new_players = Vec::with_capacity(queues.len());
+        let mut num_players = Vec::with_capacity(queues.len());
+
+        for (i, (queue, player)) in queues.iter_mut().zip(players.iter()).enumerate() {
+            if let Some(player) = player {
+                let started_at = queue.current_started_at;
+                queue.advance(player);
+                queue.current_started_at = now;
+
+                queue_ids.push(queue.key());
+                queue_nums.push((i + 1) as u8);
+                finished_players.push(Some(player.key()));
+                new_players.push(player.next_player);
+                num_players.push(queue.num_players_in_queue);
+
+                game_account.release_fee(player);
+                let receipt_account = receipt_accounts.next().ok_or(Errors::MissingSessionReceipt)?;
+                issue_session_receipt(&game_account.key(), player, receipt_account, started_at, now, &ctx.accounts.system_program, ctx.program_id)?;
             }
+            queue.exit(ctx.program_id)?;
         }
 
-        // emit an event to make known the queue has advanced
+        // emit an event to make known that the game queue has been advanced
         emit!(AdvanceQueueEvent {
             game_id: game_account.key(),
-            queue_ids: queue_ids,
-            queue_nums: queue_nums,
-            finished_players: finished_players,
-            new_players: new_players,
-            num_players: num_players,
+            queue_ids,
+            queue_nums,
+            finished_players,
+            new_players,
+            num_players,
         });
 
         Ok(())
     }
 
-    /// Finishes a normal 4 player game queue by resetting the queue pointers.
-    pub fn finish_four_player_game_queue(ctx: Context<FinishFourPlayerGameQueue>) -> ProgramResult {
+    /// Advances a king of the hill game queue.  The current player of `winning_queue` stays on and everyone else playing is
+    /// replaced by the player behind them.
+    /// 
+    /// E F -> E   -> E   ->     ->     -> 
+    /// C D -> C F -> C   ->   E ->     -> 
+    /// A B -> A D -> A F -> A C -> A E -> A
+    /// 
+    /// In team king of the hill games neighbouring queues (1 and 2, 3 and 4, ...) play as a team and `winning_queue` can be
+    /// either queue of the winning team.  Once a losing queue (or for teams, a whole losing team) runs out of players the players
+    /// waiting behind the winners move over to it.
+    /// 
+    /// The game's queues are passed (in queue order) as remaining accounts, followed by the current player of every queue that
+    /// is not empty and then the session receipt of every losing player.
+    pub fn advance_king_of_hill_queue<'info>(ctx: Context<'_, '_, '_, 'info, AdvanceKingOfHillQueue<'info>>, winning_queue: u8) -> Result<()> {
         let game_account = &mut ctx.accounts.game_account;
+        let mut queues = load_game_queues(game_account, ctx.remaining_accounts)?;
+        let mut players = load_current_players(&queues, &ctx.remaining_accounts[queues.len()..])?;
+        let mut receipt_accounts = ctx.remaining_accounts[(queues.len() + players.iter().flatten().count())..].iter();
+
+        let team_size = if game_account.base_game_type() == 2 { 2 } else { 1 };
+        let winning_team = (winning_queue as usize) / team_size * team_size;
 
-        for i in 0..4 {
-            game_account.game_queues[i] = game_account.key();
+        if !matches!(players.get(winning_queue as usize), Some(Some(_))) {
+            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
         }
 
-        // emit an event to make known the queues have been destroyed
-        emit!(FinishQueueEvent {
-            game_id: game_account.key(),
-        });
+        // The winners can not crown themselves, only the losers can give up the round.  A session running out does not let
+        // anybody else pick the winner either, idle players are skipped with skip_idle_player instead.
+        let now = Clock::get()?.unix_timestamp;
+        let authority = ctx.accounts.authority.key();
+        let losing_player_wallet = players
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i / team_size * team_size != winning_team)
+            .filter_map(|(_, player)| player.as_ref())
+            .any(|player| player.wallet_key == authority);
+        if authority != game_account.operator && !losing_player_wallet {
+            return Err(Errors::NotQueueAuthority.into());
+        }
 
-        Ok(())
-    }
+        let mut queue_ids = Vec::with_capacity(queues.len());
+        let mut queue_nums = Vec::with_capacity(queues.len());
+        let mut finished_players = Vec::with_capacity(queues.len());
+        let mut new_players = Vec::with_capacity(queues.len());
+        let mut num_players = Vec::with_capacity(queues.len());
 
-    /// Finishes a 4 player king of the hill queue by resetting the queue pointers.
-    pub fn finish_four_player_king_of_hill_queue(ctx: Context<FinishFourPlayerKingOfHillQueue>) -> ProgramResult {
-        let game_account = &mut ctx.accounts.game_account;
+        for team in (0..queues.len()).step_by(team_size) {
+            if team == winning_team || players[team..(team + team_size)].iter().all(Option::is_none) {
+                continue;
+            }
+
+            // The losers only make way for the winners' line when nobody is waiting behind any of them.
+            let out_of_players = players[team..(team + team_size)].iter().flatten().all(|player| player.next_player.is_none());
+
+            for offset in 0..team_size {
+                let losing_player = players[team + offset].take();
+                let started_at = queues[team + offset].current_started_at;
+
+                if out_of_players {
+                    queues[team + offset].clear();
+
+                    let (queue, winners_queue) = pair_mut(&mut queues, team + offset, winning_team + offset);
+                    if let Some(winner) = players[winning_team + offset].as_mut() {
+                        queue.take_waiting(winners_queue, winner);
+                    }
+                } else if let Some(losing_player) = &losing_player {
+                    queues[team + offset].advance(losing_player);
+                }
+
+                if let Some(losing_player) = losing_player {
+                    let queue = &queues[team + offset];
+                    queue_ids.push(queue.key());
+                    queue_nums.push((team + offset + 1) as u8);
+                    finished_players.push(Some(losing_player.key()));
+                    new_players.push(if queue.is_empty() { None } else { Some(queue.current_player) });
+                    num_players.push(queue.num_players_in_queue);
+
+                    game_account.release_fee(&losing_player);
+                    let receipt_account = receipt_accounts.next().ok_or(Errors::MissingSessionReceipt)?;
+                    issue_session_receipt(&game_account.key(), &losing_player, receipt_account, started_at, now, &ctx.accounts.system_program, ctx.program_id)?;
+                }
+            }
+        }
 
-        for i in 0..4 {
-            game_account.game_queues[i] = game_account.key();
+        if finished_players.is_empty() {
+            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
         }
 
-        // emit an event to make known the queues have been destroyed
-        emit!(FinishQueueEvent {
+        // Everybody still playing starts a new round.
+        for queue in queues.iter_mut() {
+            if !queue.is_empty() {
+                queue.current_started_at = now;
+            }
+            queue.exit(ctx.program_id)?;
+        }
+        for winner in players.iter().flatten() {
+            winner.exit(ctx.program_id)?;
+        }
+
+        // emit an event to make known that the game queue has advanced
+        emit!(AdvanceQueueEvent {
             game_id: game_account.key(),
+            queue_ids,
+            queue_nums,
+            finished_players,
+            new_players,
+            num_players,
         });
 
         Ok(())
     }
 
-    /// Finishes a team king of the hill queue by resetting the queue pointers.
-    pub fn finish_team_king_of_hill_queue(ctx: Context<FinishTeamKingOfHillQueue>) -> ProgramResult {
+    /// Finishes a game once nobody is waiting anymore by closing its queues and current players and resetting the game queue
+    /// pointers.
+    /// 
+    /// The game's queues are passed (in queue order) as remaining accounts, followed by the current player of every queue that
+    /// is not empty and then the session receipt of every one of those players.  The rent of the closed queues is credited to
+    /// the game account.
+    pub fn finish_game_queue<'info>(ctx: Context<'_, '_, '_, 'info, FinishGameQueue<'info>>) -> Result<()> {
         let game_account = &mut ctx.accounts.game_account;
+        let queues = load_game_queues(game_account, ctx.remaining_accounts)?;
+        let players = load_current_players(&queues, &ctx.remaining_accounts[queues.len()..])?;
+        let mut receipt_accounts = ctx.remaining_accounts[(queues.len() + players.iter().flatten().count())..].iter();
+
+        if players.iter().flatten().any(|player| player.next_player.is_some()) {
+            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let finishing_wallets: Vec<Pubkey> = players.iter().flatten().map(|player| player.wallet_key).collect();
+        check_queue_authority(game_account, &ctx.accounts.authority.key(), &queues, &finishing_wallets, now)?;
+
+        for (queue, player) in queues.iter().zip(players.iter()) {
+            if let Some(player) = player {
+                game_account.release_fee(player);
+                let receipt_account = receipt_accounts.next().ok_or(Errors::MissingSessionReceipt)?;
+                issue_session_receipt(&game_account.key(), player, receipt_account, queue.current_started_at, now, &ctx.accounts.system_program, ctx.program_id)?;
+            }
+        }
+        // Recreated queues carry on from the highest sequence number so far, as an unspent session receipt of an earlier player
+        // would otherwise block the player that gets their address.
+        for queue in queues.iter() {
+            game_account.first_player_sequence = game_account.first_player_sequence.max(queue.next_sequence);
+            queue.close(game_account.to_account_info())?;
+        }
 
-        for i in 0..4 {
-            game_account.game_queues[i] = game_account.key();
+        let game_key = game_account.key();
+        for game_queue in game_account.game_queues.iter_mut() {
+            *game_queue = game_key;
         }
 
-        // emit an event to make known the queues have been destroyed
+        // emit an event to make known that the queues have been deleted
         emit!(FinishQueueEvent {
             game_id: game_account.key(),
         });
@@ -1651,61 +1189,179 @@ pub mod arcade {
 
     /// Joins a specific queue for a king of the hill game.
     pub fn join_king_of_hill_game_queue(ctx: Context<JoinKingOfHillGameQueue>) -> Result<()> {
-        let player_account = &mut ctx.accounts.player_account;
-        let last_player = &mut ctx.accounts.last_player;
-        let game_queue_account = &mut ctx.accounts.game_queue_account;
-        let game_account = &mut ctx.accounts.game_account;
-        let payer = &mut ctx.accounts.payer;
+        let accounts = &mut *ctx.accounts;
+        let fee = charge_play_fee(&accounts.game_account, &accounts.arcade_account, &accounts.price_feed, &accounts.payer)?;
+        let wallet = accounts.payer.key();
+        accounts.join_queue(ctx.program_id, fee, wallet)
+    }
 
-        let mut correct_game_queue = false;
-        let mut queue_num: u8 = 0;
+    /// Same as join_king_of_hill_game_queue, but the play is paid in the game's token.
+    pub fn join_king_of_hill_game_queue_with_tokens(ctx: Context<JoinKingOfHillGameQueueWithTokens>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let fee = accounts.payment.charge(&accounts.queue.game_account, &accounts.queue.arcade_account, &accounts.queue.payer)?;
+        let wallet = accounts.queue.payer.key();
+        accounts.queue.join_queue(ctx.program_id, fee, wallet)
+    }
 
-        for i in 0..(game_account.max_players as usize) {
-            if (*game_account.game_queues.get(i).unwrap()) == game_queue_account.key() {
-                correct_game_queue = true;
-                queue_num = i as u8;
-                break;
-            }
-        }
+    /// Same as join_king_of_hill_game_queue, but the play is paid from the prepaid credits of the wallet that will be playing.
+    pub fn join_king_of_hill_game_queue_with_credits(ctx: Context<JoinKingOfHillGameQueueWithCredits>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let fee = accounts.credits.charge(&accounts.queue.game_account, &accounts.queue.arcade_account, &accounts.queue.price_feed, &accounts.queue.payer)?;
+        let wallet = accounts.credits.player_credits.wallet;
+        accounts.queue.join_queue(ctx.program_id, fee, wallet)
+    }
 
-        if !correct_game_queue {
-            return Err(Errors::CannotAdvanceGameQueueWrongGameQueue.into());
+    /// This function opens the prepaid play credits account of a wallet on an arcade.
+    pub fn open_player_credits(ctx: Context<OpenPlayerCredits>) -> Result<()> {
+        let player_credits = &mut ctx.accounts.player_credits;
+
+        player_credits.arcade = ctx.accounts.arcade_account.key();
+        player_credits.wallet = ctx.accounts.wallet.key();
+        player_credits.kiosk = None;
+        player_credits.discounts_received = 0;
+
+        Ok(())
+    }
+
+    /// This function buys play credits in bulk.  Anybody can top up a wallet's credits, but only with at least the arcade's
+    /// minimum top up.
+    pub fn top_up_credits(ctx: Context<TopUpCredits>, lamports: u64) -> Result<()> {
+        let player_credits = &ctx.accounts.player_credits;
+        let payer = &ctx.accounts.payer;
+
+        if lamports < ctx.accounts.arcade_account.min_credit_top_up {
+            return Err(Errors::CreditTopUpTooSmall.into());
         }
 
         let ix = anchor_lang::solana_program::system_instruction::transfer(
             &payer.key(),
-            &game_account.key(),
-            TWENTY_FIVE_CENTS,
+            &player_credits.key(),
+            lamports,
         );
 
         anchor_lang::solana_program::program::invoke(
             &ix,
             &[
                 payer.to_account_info(),
-                game_account.to_account_info(),
+                player_credits.to_account_info(),
             ],
         )?;
 
-        player_account.wallet_key = payer.key();
-        player_account.next_player = None;
+        emit!(CreditsEvent {
+            label: "TOP_UP".to_string(),
+            credits_id: player_credits.key(),
+            wallet: player_credits.wallet,
+            lamports,
+            balance: credits_balance(&player_credits.to_account_info())?,
+        });
 
-        if game_queue_account.current_player == game_account.key() {
-            game_queue_account.current_player = player_account.key();
+        Ok(())
+    }
+
+    /// This function lets a kiosk (a key the wallet hands to a frontend) spend the wallet's credits on plays without the wallet
+    /// having to sign every play.  Passing None takes the kiosk's access away.
+    pub fn set_credits_kiosk(ctx: Context<SetCreditsKiosk>, kiosk: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.player_credits.kiosk = kiosk;
+
+        Ok(())
+    }
+
+    /// This function pays unused credits back to the wallet.
+    /// 
+    /// Credits are sold at a discount because they are bought in bulk, so the discounts received on plays since the last
+    /// withdrawal are paid back (into the arcade's treasury) out of the credits when withdrawing.
+    pub fn withdraw_credits(ctx: Context<WithdrawCredits>, lamports: u64) -> Result<()> {
+        let player_credits = &mut ctx.accounts.player_credits;
+        let treasury = &ctx.accounts.treasury;
+        let wallet = &ctx.accounts.wallet;
+
+        let repaid = player_credits.discounts_received;
+        let debit = lamports.checked_add(repaid).ok_or(Errors::PayoutOverflow)?;
+        if debit > credits_balance(&player_credits.to_account_info())? {
+            return Err(Errors::InsufficientCredits.into());
         }
 
-        if game_queue_account.last_player == last_player.key() {
-            last_player.next_player = Some(player_account.key());
+        let credits_info = player_credits.to_account_info();
+        **credits_info.lamports.borrow_mut() = credits_info.lamports() - debit;
+        credit_lamports(&wallet.to_account_info(), lamports)?;
+        credit_lamports(&treasury.to_account_info(), repaid)?;
+        player_credits.discounts_received = 0;
+
+        if repaid > 0 {
+            // emit an event to make known the repaid discounts went into the treasury
+            emit!(TreasuryEvent {
+                label: "CREDIT_DISCOUNTS".to_string(),
+                arcade_id: treasury.arcade,
+                treasury_id: treasury.key(),
+                lamports: repaid,
+                tokens: 0,
+                mint: None,
+            });
         }
-        game_queue_account.last_player = player_account.key();
-        game_queue_account.num_players_in_queue += 1;
 
-        // emit an event to make known the queue has been joined
-        emit!(JoinQueueEvent {
+        emit!(CreditsEvent {
+            label: "WITHDRAW".to_string(),
+            credits_id: player_credits.key(),
+            wallet: player_credits.wallet,
+            lamports,
+            balance: credits_balance(&player_credits.to_account_info())?,
+        });
+
+        Ok(())
+    }
+
+    /// Lets a player waiting in any game queue leave it before their turn, refunding their play fee and the player account's
+    /// rent to their wallet.  Play fees paid from prepaid credits are refunded into those credits.  The current player of a queue
+    /// is already playing and can not leave.
+    /// 
+    /// The players waiting behind the leaving player are passed (in queue order) as remaining accounts, followed by the game's
+    /// escrow, the token account to refund to and the token program when the play was paid in tokens, or by the player's credits
+    /// when it was paid from credits.
+    pub fn leave_queue<'info>(ctx: Context<'_, '_, '_, 'info, LeaveQueue<'info>>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let refund_accounts = accounts.unlink(ctx.remaining_accounts)?;
+        accounts.refund(refund_accounts, ctx.program_id)
+    }
+
+    /// Skips the current player of a queue once they have been at the front for longer than the game's maximum session, so an
+    /// idle player can not hold up everyone behind them.
+    /// 
+    /// Anybody can skip an idle player, and is paid a small reward out of the game's funds (never out of its rent or the fees of
+    /// waiting players) for doing so.
+    pub fn skip_idle_player(ctx: Context<SkipIdlePlayer>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
+        let game_queue_account = &mut ctx.accounts.game_queue_account;
+        let player_account = &ctx.accounts.player_account;
+        let caller = &ctx.accounts.caller;
+
+        let now = Clock::get()?.unix_timestamp;
+        if !game_account.session_expired(game_queue_account, now) {
+            return Err(Errors::SessionNotExpired.into());
+        }
+
+        let started_at = game_queue_account.current_started_at;
+        game_queue_account.advance(player_account);
+        game_queue_account.current_started_at = now;
+        game_account.release_fee(player_account);
+        issue_session_receipt(&game_account.key(), player_account, &ctx.accounts.session_receipt, started_at, now, &ctx.accounts.system_program, ctx.program_id)?;
+
+        let game_info = game_account.to_account_info();
+        let minimum_game_rent = Rent::get()?.minimum_balance(game_info.data_len());
+        let reserved_lamports = game_account.reserved_lamports(minimum_game_rent).ok_or(Errors::PayoutOverflow)?;
+        let reward = SKIP_CRANK_REWARD_LAMPORTS.min(game_info.lamports().saturating_sub(reserved_lamports));
+        **game_info.lamports.borrow_mut() = game_info.lamports() - reward;
+        credit_lamports(&caller.to_account_info(), reward)?;
+
+        // emit an event to make known an idle player has been skipped
+        emit!(SkipPlayerEvent {
             game_id: game_account.key(),
             queue_id: game_queue_account.key(),
-            queue_num: queue_num,
-            last_player: player_account.key(),
+            skipped_player: player_account.key(),
+            skipped_wallet: player_account.wallet_key,
+            new_player: if game_queue_account.is_empty() { None } else { Some(game_queue_account.current_player) },
             num_players: game_queue_account.num_players_in_queue,
+            caller: caller.key(),
+            reward_lamports: reward,
         });
 
         Ok(())
@@ -1713,136 +1369,491 @@ pub mod arcade {
 
     /// Whenever a game is played the game should make a call to the update leaderboard function to see if the leaderboard
     /// should be updated.
-    pub fn update_leaderboard(ctx: Context<GameEnd>, player_name: String, score: u128, wallet_key: Pubkey) -> Result<()> {
-        let game_account = &mut ctx.accounts.game_account;
+    /// 
+    /// The game's server has to sign (game, player wallet, score, nonce) with the game's score authority key and that signature
+    /// has to be verified by an ed25519 program instruction right before this one.  Every nonce can only be used once per game.
+    /// 
+    /// Each wallet only keeps its best score on the leaderboard, and every score uses up one of the wallet's session receipts so
+    /// only wallets that paid to play can post scores.  The score is shown with the initials registered in the wallet's profile.
+    pub fn update_leaderboard(ctx: Context<GameEnd>, score: u128, wallet_key: Pubkey, nonce: u64) -> Result<()> {
+        let game_account = &ctx.accounts.game_account;
+        let leaderboard_account = &mut ctx.accounts.leaderboard_account;
+        let score_nonce_account = &mut ctx.accounts.score_nonce_account;
+        let player_profile = &ctx.accounts.player_profile;
+        let instructions = &ctx.accounts.instructions;
 
-        let name = match player_name.chars().count() {
-            1 => player_name + "  ",
-            2 => player_name + " ",
-            3 => player_name,
-            _ => return Err(Errors::IllegalName.into()),
-        };
+        let message = score_attestation_message(&game_account.key(), &wallet_key, score, nonce);
+        verify_ed25519_attestation(&instructions.to_account_info(), &game_account.score_authority, &message)?;
 
-        let first = game_account.leaderboard.first_place.score;
-        let second = game_account.leaderboard.second_place.score;
-        let third = game_account.leaderboard.third_place.score;
-
-        if score > third {
-            let place = Place {name: name.clone(), wallet_key, score};
-
-            if score > first {
-                game_account.leaderboard.third_place = game_account.leaderboard.second_place.clone();
-                game_account.leaderboard.second_place = game_account.leaderboard.first_place.clone();
-                game_account.leaderboard.first_place = place.clone();
-            } else if score > second {
-                game_account.leaderboard.third_place = game_account.leaderboard.second_place.clone();
-                game_account.leaderboard.second_place = place.clone();
-            } else {
-                game_account.leaderboard.third_place = place.clone();
-            }
-        }
+        score_nonce_account.game = game_account.key();
+        score_nonce_account.nonce = nonce;
+
+        let name = player_profile.initials.clone();
+
+        let rank = leaderboard_account.insert(Place {name: name.clone(), wallet_key, score});
 
         // emit an event to make known the leaderboard has been updated
         emit!(LeaderboardEvent {
             game_id: game_account.key(),
+            leaderboard_id: leaderboard_account.key(),
             player_name: name,
-            first_place_name: game_account.leaderboard.first_place.name.to_string(),
-            second_place_name: game_account.leaderboard.second_place.name.to_string(),
-            third_place_name: game_account.leaderboard.third_place.name.to_string(),
+            score,
+            rank: rank.map(|rank| rank as u8),
         });
 
         Ok(())
     }
 
     /// Whenever the owner of a game wants to payout the funds they will call this function
-    pub fn payback_funds(ctx: Context<PaybackGameFunds>) -> Result<()> {
-        let player_one_pot = &mut ctx.accounts.player_one_pot;
-        let player_two_pot = &mut ctx.accounts.player_two_pot;
-        let player_three_pot = &mut ctx.accounts.player_three_pot;
-        let arcade_account = &mut ctx.accounts.arcade_account;
+    pub fn payback_funds<'info>(ctx: Context<'_, '_, '_, 'info, PaybackGameFunds<'info>>) -> Result<()> {
+        let arcade_account = &ctx.accounts.arcade_account;
         let game_account = &mut ctx.accounts.game_account;
+        let leaderboard_account = &mut ctx.accounts.leaderboard_account;
+        let treasury = &ctx.accounts.treasury;
         let owner_account = &mut ctx.accounts.owner;
+        let system_program = &ctx.accounts.system_program;
+
+        // A game's own schedule takes precedence over the arcade's default one.
+        let schedule = match &game_account.payout_schedule {
+            Some(schedule) => schedule.clone(),
+            None => arcade_account.default_payout_schedule.clone(),
+        };
+
+        // Every paid place that somebody holds gets a pot (passed in rank order through the remaining accounts, followed by the
+        // claim indexes of their winners), places nobody has claimed yet are paid to the owner.  Games with a token escrow also
+        // pass the token accounts right after the claim indexes.
+        let num_pots = schedule.place_bps.len().min(leaderboard_account.places.len());
+        let num_token_accounts = if game_account.escrow.is_some() { TOKEN_PAYOUT_ACCOUNTS } else { 0 };
+        if ctx.remaining_accounts.len() != 2 * num_pots + num_token_accounts {
+            return Err(Errors::WrongNumberOfPots.into());
+        }
+        let (pot_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(num_pots);
+        let (claim_index_accounts, token_accounts) = remaining_accounts.split_at(num_pots);
 
-        // Calculate amount SOL to give to game owner, top player, second player, and third player
+        // Calculate amount SOL to give to game owner, the arcade's treasury and each winner.
         let game_lamports = game_account.to_account_info().lamports();
-        let rent_calculator = Rent::default();
-        let minimum_game_rent = rent_calculator.minimum_balance(Game::MAX_SIZE + 8);
-        let minimum_pot_rent = rent_calculator.minimum_balance(GamePot::MAX_SIZE + 8);
-        let distribution_lamports = game_lamports - minimum_game_rent;
-        let owner_lamports = (distribution_lamports / 2) as u64;
-        let player_one_lamports= (owner_lamports * 4 / 7) as u64;
-        let player_two_lamports = (owner_lamports * 2 / 7) as u64;
-        let player_three_lamports = (owner_lamports / 7) as u64;
-        
-        let paying_lamports = owner_lamports + player_one_lamports + player_three_lamports;
-        if minimum_game_rent > game_lamports - paying_lamports {
-            return Err(Errors::InsufficientFundsForPayout.into());
-        } else if minimum_pot_rent > player_one_lamports {
-            return Err(Errors::InsufficientFundsPotOne.into());
-        } else if minimum_pot_rent > player_two_lamports {
-            return Err(Errors::InsufficientFundsPotTwo.into());
-        } else if minimum_pot_rent > player_three_lamports {
-            return Err(Errors::InsufficientFundsPotThree.into());
-        }
-
-        **owner_account.to_account_info().lamports.borrow_mut() += owner_lamports;
-        **player_one_pot.to_account_info().lamports.borrow_mut() += player_one_lamports;
-        **player_two_pot.to_account_info().lamports.borrow_mut() += player_two_lamports;
-        **player_three_pot.to_account_info().lamports.borrow_mut() += player_three_lamports;
-        **game_account.to_account_info().lamports.borrow_mut() -= owner_lamports + player_one_lamports + player_two_lamports + player_three_lamports;
-
-        player_one_pot.game = game_account.key();
-        player_one_pot.winner_wallet = game_account.leaderboard.first_place.wallet_key;
-        player_one_pot.next_game_pot = Some(player_two_pot.key());
-
-        player_two_pot.game = game_account.key();
-        player_two_pot.winner_wallet = game_account.leaderboard.second_place.wallet_key;
-        player_two_pot.next_game_pot = Some(player_three_pot.key());
-
-        player_three_pot.game = game_account.key();
-        player_three_pot.winner_wallet = game_account.leaderboard.third_place.wallet_key;
-        player_three_pot.next_game_pot = arcade_account.most_recent_game_pot_key;
-
-        arcade_account.most_recent_game_pot_key = Some(player_one_pot.key());
+        let minimum_game_rent = Rent::get()?.minimum_balance(game_account.to_account_info().data_len());
+        let reserved_lamports = game_account.reserved_lamports(minimum_game_rent).ok_or(Errors::PayoutOverflow)?;
+        let payout = PayoutAmounts::calculate_with_arcade_fee(game_lamports, reserved_lamports, &schedule, num_pots, arcade_account.arcade_fee_bps)?;
+        let mut paying_lamports = payout.total().ok_or(Errors::PayoutOverflow)?;
+
+        // The tokens in the escrow are split the same way, leaving the tokens of pots that have not been cashed out yet.
+        let token_payout = match token_accounts {
+            [escrow_account, _, treasury_token_account, token_program] => {
+                if Some(escrow_account.key()) != game_account.escrow {
+                    return Err(Errors::WrongEscrow.into());
+                }
+                if token_program.key() != spl_token::ID {
+                    return Err(Errors::WrongTokenProgram.into());
+                }
+                if unpack_token_account(treasury_token_account)?.owner != treasury.key() {
+                    return Err(Errors::WrongTreasuryTokenAccount.into());
+                }
+                let escrow = unpack_token_account(escrow_account)?;
+                let reserved_tokens = game_account.reserved_tokens().ok_or(Errors::PayoutOverflow)?;
+                Some(PayoutAmounts::calculate_with_arcade_fee(escrow.amount, reserved_tokens, &schedule, num_pots, arcade_account.arcade_fee_bps)?)
+            }
+            _ => None,
+        };
+
+        // Create all of the pots before moving any lamports around.  Each pot is filed under the next index of its winner's
+        // claim index, so wallets can find their pots without scanning the arcade.
+        let pot_keys: Vec<Pubkey> = pot_accounts.iter().map(|pot| pot.key()).collect();
+        let mut winner_names = Vec::with_capacity(num_pots);
+        let game_key = game_account.key();
+        let now = Clock::get()?.unix_timestamp;
+        let claim_deadline = match arcade_account.pot_claim_window_secs {
+            0 => 0,
+            window => now + window as i64,
+        };
+        for (rank, (pot_account, claim_index_account)) in pot_accounts.iter().zip(claim_index_accounts.iter()).enumerate() {
+            let place = &leaderboard_account.places[rank];
+            let mut claim_index = load_claim_index(&owner_account.to_account_info(), claim_index_account, &place.wallet_key, system_program, ctx.program_id)?;
+            let index = claim_index.next_index;
+
+            create_pda_account(
+                &owner_account.to_account_info(),
+                pot_account,
+                8 + GamePot::MAX_SIZE,
+                &[b"game_pot", place.wallet_key.as_ref(), &index.to_le_bytes()],
+                &system_program.to_account_info(),
+                ctx.program_id,
+            )?;
+
+            let game_pot = GamePot {
+                game: game_account.key(),
+                arcade: arcade_account.key(),
+                winner_wallet: place.wallet_key,
+                index,
+                token_amount: token_payout.as_ref().map_or(0, |token_payout| token_payout.pot_amounts[rank]),
+                created_at: now,
+                claim_deadline,
+            };
+            game_pot.try_serialize(&mut &mut pot_account.try_borrow_mut_data()?[..])?;
+
+            claim_index.next_index += 1;
+            claim_index.unclaimed_pots += 1;
+            claim_index.try_serialize(&mut &mut claim_index_account.try_borrow_mut_data()?[..])?;
+            winner_names.push(place.name.clone());
+        }
+
+        let mut fee_mint = None;
+        if let (Some(token_payout), [escrow_account, owner_token_account, treasury_token_account, token_program]) = (&token_payout, token_accounts) {
+            if game_account.collaborators.is_empty() {
+                transfer_from_escrow(token_program, escrow_account, &game_key, owner_token_account, token_payout.owner_amount, ctx.program_id)?;
+            }
+            transfer_from_escrow(token_program, escrow_account, &game_key, treasury_token_account, token_payout.arcade_fee_amount, ctx.program_id)?;
+            fee_mint = Some(unpack_token_account(escrow_account)?.mint);
+
+            let pots_total = token_payout.pots_total().ok_or(Errors::PayoutOverflow)?;
+            game_account.escrow_reserved = game_account.escrow_reserved.checked_add(pots_total).ok_or(Errors::PayoutOverflow)?;
+        }
+
+        // A game with collaborators keeps the owner's share (lamports in the game, tokens in the escrow) until each collaborator
+        // claims their part of it.
+        if game_account.collaborators.is_empty() {
+            credit_lamports(&owner_account.to_account_info(), payout.owner_amount)?;
+        } else {
+            let owner_tokens = token_payout.as_ref().map_or(0, |token_payout| token_payout.owner_amount);
+            game_account.split_owner_share(payout.owner_amount, owner_tokens).ok_or(Errors::PayoutOverflow)?;
+            paying_lamports -= payout.owner_amount;
+        }
+
+        let game_info = game_account.to_account_info();
+        let remaining_game_lamports = game_info.lamports().checked_sub(paying_lamports).ok_or(Errors::InsufficientFundsForPayout)?;
+        **game_info.lamports.borrow_mut() = remaining_game_lamports;
+        for (pot_account, lamports) in pot_accounts.iter().zip(payout.pot_amounts.iter()) {
+            credit_lamports(pot_account, *lamports)?;
+        }
+        credit_lamports(&treasury.to_account_info(), payout.arcade_fee_amount)?;
 
         emit!(PayoutEvent {
             game_name: game_account.title.clone(),
             arcade_id: arcade_account.key(),
-            player_one_pot_key: player_one_pot.key(),
-            player_two_pot_key: player_two_pot.key(),
-            player_three_pot_key: player_three_pot.key(),
-            player_one_name: game_account.leaderboard.first_place.name.clone(),
-            player_two_name: game_account.leaderboard.second_place.name.clone(),
-            player_three_name: game_account.leaderboard.third_place.name.clone(),
+            pot_keys,
+            winner_names,
+            owner_lamports: payout.owner_amount,
+            arcade_fee_lamports: payout.arcade_fee_amount,
+            owner_tokens: token_payout.as_ref().map_or(0, |token_payout| token_payout.owner_amount),
+            arcade_fee_tokens: token_payout.as_ref().map_or(0, |token_payout| token_payout.arcade_fee_amount),
         });
 
-        let first_place = Place {name: String::from("AAA"), wallet_key: owner_account.key(), score: 100};
-        let second_place = Place {name: String::from("BBB"), wallet_key: owner_account.key(), score: 50};
-        let third_place = Place {name: String::from("CCC"), wallet_key: owner_account.key(), score: 25};
-
-        let leaderboard = Leaderboard {first_place, second_place, third_place};
+        // emit an event to make known the arcade's fee went into the treasury
+        emit!(TreasuryEvent {
+            label: "PAYOUT_FEE".to_string(),
+            arcade_id: arcade_account.key(),
+            treasury_id: treasury.key(),
+            lamports: payout.arcade_fee_amount,
+            tokens: token_payout.as_ref().map_or(0, |token_payout| token_payout.arcade_fee_amount),
+            mint: fee_mint,
+        });
 
-        game_account.leaderboard = leaderboard;
+        // Start a new ladder for the next payout.
+        leaderboard_account.places.clear();
 
         Ok(())
     }
 
-    /// Whenever a user wants to cash out their pots they can call this function to do so.
+    /// Whenever a user wants to cash out their pots they can call this function to do so.  The wallet's pots are found at the
+    /// addresses derived from the wallet and the indexes below its claim index's next index.
     pub fn cash_out_pot(ctx: Context<CashOutPot>) -> Result<()> {
-        let game_pot_account = &mut ctx.accounts.game_pot_account;
-        let previous_pot_account = &mut ctx.accounts.previous_pot_account;
+        if ctx.accounts.game_pot_account.token_amount > 0 {
+            return Err(Errors::TokenPot.into());
+        }
+        if ctx.accounts.game_pot_account.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(Errors::PotExpired.into());
+        }
+
+        ctx.accounts.settle()
+    }
+
+    /// Same as cash_out_pot, but also pays out the pot's tokens from the game's escrow.
+    pub fn cash_out_token_pot(ctx: Context<CashOutTokenPot>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+
+        if accounts.cash_out.game_pot_account.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(Errors::PotExpired.into());
+        }
+
+        accounts.payout.pay(&accounts.cash_out.game_pot_account, ctx.program_id)?;
+        accounts.cash_out.settle()
+    }
+
+    /// This function cashes out every pot passed in the remaining accounts in one go.  All of the pots have to be won by the
+    /// signer on the arcade of the profile given, and hold no tokens (those are cashed out with cash_out_token_pot).
+    pub fn claim_all<'info>(ctx: Context<'_, '_, '_, 'info, ClaimAll<'info>>) -> Result<()> {
+        let claim_index = &mut ctx.accounts.claim_index;
+        let player_profile = &mut ctx.accounts.player_profile;
+        let winner = &ctx.accounts.winner;
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut pot_keys = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut lamports: u64 = 0;
+        for pot_account in ctx.remaining_accounts {
+            let game_pot: Account<GamePot> = Account::try_from(pot_account)?;
+            if game_pot.winner_wallet != winner.key() {
+                return Err(Errors::NotPotWinner.into());
+            }
+            if game_pot.arcade != player_profile.arcade {
+                return Err(Errors::WrongArcade.into());
+            }
+            if game_pot.token_amount > 0 {
+                return Err(Errors::TokenPot.into());
+            }
+            if game_pot.is_expired(now) {
+                return Err(Errors::PotExpired.into());
+            }
 
-        previous_pot_account.next_game_pot = game_pot_account.next_game_pot;
+            record_win(player_profile, pot_account)?;
+            lamports = lamports.checked_add(pot_account.lamports()).ok_or(Errors::PayoutOverflow)?;
+            pot_keys.push(pot_account.key());
+
+            game_pot.close(winner.to_account_info())?;
+            claim_index.unclaimed_pots = claim_index.unclaimed_pots.saturating_sub(1);
+        }
+
+        // emit an event to make known the pots have been claimed
+        emit!(ClaimEvent {
+            wallet: winner.key(),
+            arcade_id: player_profile.arcade,
+            pot_keys,
+            lamports,
+        });
 
         Ok(())
     }
 
-    /// Whenever a user wants to cash out their pots they can call this function to do so if that pot is the first one.
-    pub fn cash_out_most_recent_pot(ctx: Context<CashOutMostRecentPot>) -> Result<()> {
-        let game_pot_account = &mut ctx.accounts.game_pot_account;
-        let arcade_state = &mut ctx.accounts.arcade_state;
+    /// This function sends a pot whose winner did not cash it out before its deadline back into its game's prize pool, freeing
+    /// up the pot's tokens in the escrow too.  The pot's lamports go to the arcade's treasury instead when the game has been
+    /// deleted.  Anybody can call this.
+    pub fn reclaim_expired_pot(ctx: Context<ReclaimExpiredPot>) -> Result<()> {
+        let game_pot_account = &ctx.accounts.game_pot_account;
+        let game_info = ctx.accounts.game_account.to_account_info();
+        let claim_index = &mut ctx.accounts.claim_index;
+
+        if !game_pot_account.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(Errors::PotNotExpired.into());
+        }
+
+        let lamports = game_pot_account.to_account_info().lamports();
+        let recipient = if game_info.owner == ctx.program_id {
+            let mut game_account: Account<Game> = Account::try_from(&game_info)?;
+            game_account.escrow_reserved = game_account.escrow_reserved.saturating_sub(game_pot_account.token_amount);
+            game_account.exit(ctx.program_id)?;
+            game_info
+        } else {
+            let treasury = &ctx.accounts.treasury;
+
+            // emit an event to make known the pot's lamports went into the treasury
+            emit!(TreasuryEvent {
+                label: "EXPIRED_POT".to_string(),
+                arcade_id: treasury.arcade,
+                treasury_id: treasury.key(),
+                lamports,
+                tokens: 0,
+                mint: None,
+            });
+
+            treasury.to_account_info()
+        };
+
+        game_pot_account.close(recipient.clone())?;
+        claim_index.unclaimed_pots = claim_index.unclaimed_pots.saturating_sub(1);
+
+        // emit an event to make known the pot has been reclaimed
+        emit!(PotReclaimedEvent {
+            pot_id: game_pot_account.key(),
+            game_id: game_pot_account.game,
+            winner_wallet: game_pot_account.winner_wallet,
+            recipient: recipient.key(),
+            lamports,
+            tokens: game_pot_account.token_amount,
+        });
+
+        Ok(())
+    }
+
+    /// This function proposes a new list of collaborators for a game, who split the owner's share of its payouts in basis points
+    /// (which have to add up to 100%, or be an empty list to pay the owner directly again).
+    /// 
+    /// The list only changes once every collaborator involved (added, removed or whose share changes) has approved it, the
+    /// owner's proposal counting as the owner's approval.
+    pub fn propose_collaborators(ctx: Context<ProposeCollaborators>, shares: Vec<CollaboratorShare>) -> Result<()> {
+        let game_account = &ctx.accounts.game_account;
+        let proposal = &mut ctx.accounts.proposal;
+        let owner = &ctx.accounts.owner;
+
+        validate_collaborator_shares(&shares)?;
+        let involved = game_account.involved_collaborators(&shares);
+        if involved.is_empty() {
+            return Err(Errors::InvalidCollaborators.into());
+        }
+
+        proposal.game = game_account.key();
+        proposal.shares = shares;
+        proposal.approvals = involved.into_iter().filter(|wallet| *wallet == owner.key()).collect();
+
+        // emit an event to make known the collaborators may change
+        emit!(CollaboratorsEvent {
+            label: "PROPOSE".to_string(),
+            game_id: game_account.key(),
+            wallet: owner.key(),
+            shares: proposal.shares.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// This function approves the proposed collaborators of a game for one of the collaborators involved.
+    pub fn approve_collaborators(ctx: Context<ApproveCollaborators>) -> Result<()> {
+        let game_account = &ctx.accounts.game_account;
+        let proposal = &mut ctx.accounts.proposal;
+        let wallet = &ctx.accounts.wallet;
+
+        if !game_account.involved_collaborators(&proposal.shares).contains(&wallet.key()) {
+            return Err(Errors::NotInvolvedCollaborator.into());
+        }
+        if !proposal.approvals.contains(&wallet.key()) {
+            proposal.approvals.push(wallet.key());
+        }
+
+        // emit an event to make known a collaborator approved the change
+        emit!(CollaboratorsEvent {
+            label: "APPROVE".to_string(),
+            game_id: game_account.key(),
+            wallet: wallet.key(),
+            shares: proposal.shares.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// This function replaces a game's collaborators with the proposed ones once every collaborator involved has approved.
+    /// Collaborators can only be removed once they have claimed everything they are owed.
+    pub fn apply_collaborators(ctx: Context<ApplyCollaborators>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
+        let proposal = &ctx.accounts.proposal;
+
+        if game_account.involved_collaborators(&proposal.shares).iter().any(|wallet| !proposal.approvals.contains(wallet)) {
+            return Err(Errors::MissingCollaboratorApprovals.into());
+        }
+        game_account.set_collaborators(&proposal.shares)?;
 
-        arcade_state.most_recent_game_pot_key = game_pot_account.next_game_pot;
+        // emit an event to make known the collaborators changed
+        emit!(CollaboratorsEvent {
+            label: "APPLY".to_string(),
+            game_id: game_account.key(),
+            wallet: ctx.accounts.owner.key(),
+            shares: proposal.shares.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// This function withdraws a game's proposed collaborators.
+    pub fn cancel_collaborators(ctx: Context<CancelCollaborators>) -> Result<()> {
+        // emit an event to make known the proposal was withdrawn
+        emit!(CollaboratorsEvent {
+            label: "CANCEL".to_string(),
+            game_id: ctx.accounts.game_account.key(),
+            wallet: ctx.accounts.owner.key(),
+            shares: ctx.accounts.proposal.shares.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// This function pays a collaborator the lamports (and tokens) they are owed from a game's payouts.
+    /// 
+    /// When tokens are owed the game's escrow, the collaborator's token account and the token program are passed as remaining
+    /// accounts.
+    pub fn claim_collaborator_share<'info>(ctx: Context<'_, '_, '_, 'info, ClaimCollaboratorShare<'info>>) -> Result<()> {
+        let game_account = &mut ctx.accounts.game_account;
+        let wallet = &ctx.accounts.wallet;
+
+        let game_key = game_account.key();
+        let collaborator = game_account.collaborators.iter_mut().find(|collaborator| collaborator.wallet == wallet.key()).ok_or(Errors::NotInvolvedCollaborator)?;
+        let lamports = collaborator.unclaimed_lamports;
+        let tokens = collaborator.unclaimed_tokens;
+        collaborator.unclaimed_lamports = 0;
+        collaborator.unclaimed_tokens = 0;
+
+        if tokens > 0 {
+            match ctx.remaining_accounts {
+                [escrow_account, token_account, token_program] => {
+                    if Some(escrow_account.key()) != game_account.escrow {
+                        return Err(Errors::WrongEscrow.into());
+                    }
+                    if token_program.key() != spl_token::ID {
+                        return Err(Errors::WrongTokenProgram.into());
+                    }
+                    transfer_from_escrow(token_program, escrow_account, &game_key, token_account, tokens, ctx.program_id)?;
+                }
+                _ => return Err(Errors::MissingClaimTokenAccounts.into()),
+            }
+        }
+
+        let game_info = game_account.to_account_info();
+        **game_info.lamports.borrow_mut() = game_info.lamports().checked_sub(lamports).ok_or(Errors::InsufficientFundsForPayout)?;
+        credit_lamports(&wallet.to_account_info(), lamports)?;
+
+        // emit an event to make known the collaborator has been paid
+        emit!(CollaboratorClaimEvent {
+            game_id: game_key,
+            wallet: wallet.key(),
+            lamports,
+            tokens,
+        });
+
+        Ok(())
+    }
+
+    /// This function creates a wallet's profile on an arcade, registering the initials its scores are shown with.  No two
+    /// wallets on an arcade can hold the same initials.
+    pub fn create_player_profile(ctx: Context<CreatePlayerProfile>, initials: String) -> Result<()> {
+        let player_profile = &mut ctx.accounts.player_profile;
+        let arcade_account = &ctx.accounts.arcade_account;
+        let wallet = &ctx.accounts.wallet;
+
+        let initials = pad_initials(&initials)?;
+        claim_initials(&arcade_account.key(), &initials, wallet, &ctx.accounts.initials_claim, &ctx.accounts.system_program, ctx.program_id)?;
+
+        player_profile.arcade = arcade_account.key();
+        player_profile.wallet = wallet.key();
+        player_profile.initials = initials.clone();
+        player_profile.plays = 0;
+        player_profile.wins = 0;
+        player_profile.lifetime_winnings = 0;
+
+        // emit an event to make known the initials have been registered
+        emit!(InitialsEvent {
+            arcade_id: arcade_account.key(),
+            wallet: wallet.key(),
+            old_initials: None,
+            initials,
+        });
+
+        Ok(())
+    }
+
+    /// This function registers new initials to a wallet's profile and frees up its old ones.  Places already on leaderboards
+    /// keep the initials they were posted with.
+    pub fn change_initials(ctx: Context<ChangeInitials>, initials: String) -> Result<()> {
+        let player_profile = &mut ctx.accounts.player_profile;
+        let wallet = &ctx.accounts.wallet;
+
+        let initials = pad_initials(&initials)?;
+        claim_initials(&player_profile.arcade, &initials, wallet, &ctx.accounts.new_initials_claim, &ctx.accounts.system_program, ctx.program_id)?;
+
+        let old_initials = mem::replace(&mut player_profile.initials, initials.clone());
+
+        // emit an event to make known the initials have been changed
+        emit!(InitialsEvent {
+            arcade_id: player_profile.arcade,
+            wallet: wallet.key(),
+            old_initials: Some(old_initials),
+            initials,
+        });
 
         Ok(())
     }
@@ -1869,1154 +1880,2850 @@ pub mod arcade {
     }
 }
 
-#[derive(Accounts)]
-pub struct Initialize {}
+/// Checks a list of collaborators can split the owner's share of a game's payouts: no more than `MAX_COLLABORATORS` different
+/// wallets, each with a share, adding up to 100% (or nobody, which pays the owner directly).
+pub fn validate_collaborator_shares(shares: &[CollaboratorShare]) -> Result<()> {
+    if shares.len() > MAX_COLLABORATORS {
+        return Err(Errors::InvalidCollaborators.into());
+    }
+    for (i, share) in shares.iter().enumerate() {
+        if share.share_bps == 0 || shares[..i].iter().any(|other| other.wallet == share.wallet) {
+            return Err(Errors::InvalidCollaborators.into());
+        }
+    }
 
-#[derive(Accounts)]
-/// Context used to initialize the arcade.
-pub struct InitArcade<'info> {
-    #[account(init, payer = authority, space = 8 + ArcadeState::MAX_SIZE)]
-    pub arcade_account: Account<'info, ArcadeState>, // The accound for the arcade state (i.e. the pointer to the newest game).
-    #[account(init, payer = authority, space = 8 + Game::MAX_SIZE)]
-    pub genesis_game_account: Account<'info, Game>, // The first game (i.e. the game that began the arcade).
-    #[account(mut)]
-    pub authority: Signer<'info>, // The person who pays for initializing the arcade (i.e. me).
-    pub system_program: Program<'info, System>, // The system program to make sure the account created is associated with this program.
+    let total = shares.iter().fold(0u32, |total, share| total + share.share_bps as u32);
+    if !shares.is_empty() && total != BASIS_POINTS as u32 {
+        return Err(Errors::InvalidCollaborators.into());
+    }
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-/// Context used to create a new game.
-pub struct MakeGame<'info> {
-    #[account(init, payer = owner, space = 8 + Game::MAX_SIZE)]
-    pub game_account: Account<'info, Game>,
-    #[account(mut)]
-    pub arcade_account: Account<'info, ArcadeState>,
-    #[account(mut)]
-    pub most_recent_game_account: Account<'info, Game>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Pads initials to the 3 characters shown on leaderboards, upper casing letters so "abc" and "ABC" are the same initials.
+/// 
+/// Initials can only use the letters A to Z, the digits and spaces, so look-alike characters can not pass for registered
+/// initials.
+pub fn pad_initials(initials: &str) -> Result<String> {
+    let initials = initials.to_ascii_uppercase();
+    if !initials.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == ' ') {
+        return Err(Errors::IllegalInitialsCharacter.into());
+    }
+    match initials.len() {
+        1 => Ok(initials + "  "),
+        2 => Ok(initials + " "),
+        3 => Ok(initials),
+        _ => Err(Errors::IllegalName.into()),
+    }
 }
 
-#[derive(Accounts)]
-/// Context used to delete a game.
-pub struct DeleteGame<'info> {
-    #[account(
-        mut,
-        close = owner,
-        constraint = game_account.key() == younger_game.older_game_key,
-        constraint = game_account.key() == older_game.younger_game_key,
-        constraint = game_account.owner_wallet == owner.key(),
-    )]
-    pub game_account: Box<Account<'info, Game>>,
-    #[account(mut)]
-    pub younger_game: Account<'info, Game>,
-    #[account(mut)]
-    pub older_game: Account<'info, Game>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Registers `initials` on an arcade to `wallet`, failing if another wallet holds them already.
+fn claim_initials<'info>(
+    arcade: &Pubkey,
+    initials: &str,
+    wallet: &Signer<'info>,
+    initials_claim: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    if initials_claim.owner == program_id {
+        return Err(Errors::InitialsTaken.into());
+    }
+
+    create_pda_account(
+        &wallet.to_account_info(),
+        initials_claim,
+        8 + InitialsClaim::MAX_SIZE,
+        &[b"initials", arcade.as_ref(), initials.as_bytes()],
+        &system_program.to_account_info(),
+        program_id,
+    )?;
+
+    let claim = InitialsClaim {
+        arcade: *arcade,
+        wallet: wallet.key(),
+    };
+    claim.try_serialize(&mut &mut initials_claim.try_borrow_mut_data()?[..])?;
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-/// Context used to delete the most recent game in an arcade.
-pub struct DeleteMostRecentGame<'info> {
-    #[account(
-        mut,
-        close = owner,
-        constraint = game_account.key() == older_game.younger_game_key,
-        constraint = game_account.owner_wallet == owner.key(),
-    )]
-    pub game_account: Account<'info, Game>,
-    #[account(mut)]
-    arcade_state: Account<'info, ArcadeState>,
-    #[account(mut)]
-    pub older_game: Account<'info, Game>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Loads the claim index of `wallet`, creating it (paid for by `payer`) the first time the wallet wins a pot.
+fn load_claim_index<'info>(
+    payer: &AccountInfo<'info>,
+    claim_index_account: &AccountInfo<'info>,
+    wallet: &Pubkey,
+    system_program: &Program<'info, System>,
+    program_id: &Pubkey,
+) -> Result<ClaimIndex> {
+    if claim_index_account.owner == program_id {
+        let (address, _) = Pubkey::find_program_address(&[b"claim_index", wallet.as_ref()], program_id);
+        if claim_index_account.key() != address {
+            return Err(Errors::WrongAccountAddress.into());
+        }
+        return ClaimIndex::try_deserialize(&mut &claim_index_account.try_borrow_data()?[..]);
+    }
+
+    create_pda_account(
+        payer,
+        claim_index_account,
+        8 + ClaimIndex::MAX_SIZE,
+        &[b"claim_index", wallet.as_ref()],
+        &system_program.to_account_info(),
+        program_id,
+    )?;
+
+    Ok(ClaimIndex {
+        wallet: *wallet,
+        next_index: 0,
+        unclaimed_pots: 0,
+    })
 }
 
-#[derive(Accounts)]
-/// Context used to signify a game has ended and it is time to update the leaderboard.
-pub struct GameEnd<'info> {
-    #[account(mut)]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Adds a cashed out pot to its winner's record.  The winnings are the pot's lamports above its rent.
+fn record_win(player_profile: &mut PlayerProfile, game_pot: &AccountInfo) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(game_pot.data_len());
+    let winnings = game_pot.lamports().saturating_sub(rent);
+
+    player_profile.wins += 1;
+    player_profile.lifetime_winnings = player_profile.lifetime_winnings.checked_add(winnings).ok_or(Errors::PayoutOverflow)?;
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-/// Context used to initialize the queue of a 1 player normal game.
-pub struct InitOnePlayerGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 1,
-        constraint = (game_account.game_queues.get(0) == None) || ((*game_account.game_queues.get(0).unwrap()) == game_account.key())
-    )]
-    pub game_account: Account<'info, Game>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Builds the message the score authority has to sign for a score to be accepted.
+/// 
+/// message = game (32 bytes) | player wallet (32 bytes) | score (16 bytes little endian) | nonce (8 bytes little endian)
+pub fn score_attestation_message(game: &Pubkey, wallet_key: &Pubkey, score: u128, nonce: u64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(2 * mem::size_of::<Pubkey>() + mem::size_of::<u128>() + mem::size_of::<u64>());
+    message.extend_from_slice(game.as_ref());
+    message.extend_from_slice(wallet_key.as_ref());
+    message.extend_from_slice(&score.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message
 }
 
-#[derive(Accounts)]
-/// Context used to join a 1 player normal game queue.
-pub struct JoinOnePlayerGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(mut, constraint = last_player.next_player == None)]
-    pub last_player: Account<'info, Player>,
-    #[account(mut, constraint = game_queue_account.last_player == last_player.key())]
-    pub game_queue_account: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 1,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account.key()
-    )]
-    pub game_account: Account<'info, Game>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Checks that the instruction right before the current one is a native ed25519 program instruction that verified `message`
+/// was signed by `signer`.
+/// 
+/// The ed25519 program will fail the whole transaction if the signature is invalid, so all that is left to do here is make sure
+/// the verified public key and message are the ones we expect.
+fn verify_ed25519_attestation(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)?;
+    if current_index == 0 {
+        return Err(Errors::MissingScoreAttestation.into());
+    }
+
+    let ed25519_index = current_index - 1;
+    let ed25519_ix = load_instruction_at_checked(ed25519_index as usize, instructions)?;
+    if ed25519_ix.program_id != ed25519_program::ID || !ed25519_ix.accounts.is_empty() {
+        return Err(Errors::MissingScoreAttestation.into());
+    }
+
+    let data = &ed25519_ix.data;
+    if data.len() < ED25519_HEADER_SIZE + ED25519_OFFSETS_SIZE || data[0] != 1 {
+        return Err(Errors::InvalidScoreAttestation.into());
+    }
+
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let signature_instruction_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_instruction_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_instruction_index = read_u16(14);
+
+    // The signature, public key and message all have to live in the ed25519 instruction itself, otherwise the offsets could
+    // point at data in some other instruction.
+    for instruction_index in [signature_instruction_index, public_key_instruction_index, message_instruction_index] {
+        if instruction_index != u16::MAX && instruction_index != ed25519_index {
+            return Err(Errors::InvalidScoreAttestation.into());
+        }
+    }
+
+    let signed_public_key = data.get(public_key_offset..public_key_offset + mem::size_of::<Pubkey>());
+    if signed_public_key != Some(signer.as_ref()) {
+        return Err(Errors::UnauthorizedScoreAuthority.into());
+    }
+
+    let signed_message = data.get(message_offset..message_offset + message_size);
+    if signed_message != Some(message) {
+        return Err(Errors::InvalidScoreAttestation.into());
+    }
+
+    Ok(())
+}
+
+/// Charges the price of a play and adds it to the game's funds.
+/// 
+/// Prices in US cents are converted to lamports with the arcade's price feed.  Games priced in tokens have to be paid for with
+/// the `_with_tokens` instructions instead.
+fn charge_play_fee<'info>(
+    game_account: &Account<'info, Game>,
+    arcade_account: &ArcadeState,
+    price_feed: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+) -> Result<PlayFee> {
+    let lamports = play_fee_lamports(game_account, arcade_account, price_feed)?;
+    if lamports == 0 {
+        return Ok(PlayFee { amount: 0, mint: None, credits: None, discount: 0 });
+    }
+
+    let ix = anchor_lang::solana_program::system_instruction::transfer(
+        &payer.key(),
+        &game_account.key(),
+        lamports,
+    );
+
+    anchor_lang::solana_program::program::invoke(
+        &ix,
+        &[
+            payer.to_account_info(),
+            game_account.to_account_info(),
+        ],
+    )?;
+
+    Ok(PlayFee { amount: lamports, mint: None, credits: None, discount: 0 })
+}
+
+/// The price of a play of a game that is not priced in tokens, in lamports.
+fn play_fee_lamports(game_account: &Game, arcade_account: &ArcadeState, price_feed: &AccountInfo) -> Result<u64> {
+    match game_account.play_price(arcade_account) {
+        PricePolicy::Free => Ok(0),
+        PricePolicy::Lamports(lamports) => Ok(lamports),
+        PricePolicy::UsdCents(cents) => {
+            let sol_price = SolPrice::load(arcade_account.price_feed_kind, price_feed)?;
+            sol_price.check(Clock::get()?.unix_timestamp, arcade_account.max_price_age_secs, arcade_account.max_price_confidence_bps)?;
+            sol_price.lamports_for_cents(cents)
+        }
+        PricePolicy::Token { .. } => Err(Errors::PlayPricedInTokens.into()),
+    }
+}
+
+/// Reads an SPL token account.
+fn unpack_token_account(account: &AccountInfo) -> Result<spl_token::state::Account> {
+    if account.owner != &spl_token::ID {
+        return Err(Errors::InvalidTokenAccount.into());
+    }
+    let token_account = spl_token::state::Account::unpack(&account.try_borrow_data()?)?;
+    Ok(token_account)
+}
+
+/// Moves tokens from a token account owned by `authority` with an SPL token transfer, signing with `signer_seeds` when the
+/// authority is a PDA.
+fn transfer_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let ix = spl_token::instruction::transfer(token_program.key, source.key, destination.key, authority.key, &[], amount)?;
+    anchor_lang::solana_program::program::invoke_signed(
+        &ix,
+        &[
+            source.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+/// Moves tokens out of a game's escrow, which signs for itself with its seeds.
+fn transfer_from_escrow<'info>(
+    token_program: &AccountInfo<'info>,
+    escrow_account: &AccountInfo<'info>,
+    game_key: &Pubkey,
+    destination: &AccountInfo<'info>,
+    amount: u64,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (_, bump) = Pubkey::find_program_address(&[b"escrow", game_key.as_ref()], program_id);
+    transfer_tokens(token_program, escrow_account, destination, escrow_account, amount, &[&[b"escrow", game_key.as_ref(), &[bump]]])
+}
+
+/// Loads a game's queues, which are passed in queue order at the front of the remaining accounts.
+fn load_game_queues<'info>(game_account: &Account<'info, Game>, accounts: &[AccountInfo<'info>]) -> Result<Vec<Account<'info, GameQueue>>> {
+    let num_queues = game_account.max_players as usize;
+    if accounts.len() < num_queues || game_account.game_queues.len() != num_queues {
+        return Err(Errors::CannotAdvanceGameQueueWrongGameQueue.into());
+    }
+
+    let mut queues = Vec::with_capacity(num_queues);
+    for (queue_account, game_queue) in accounts.iter().zip(game_account.game_queues.iter()) {
+        if queue_account.key() != *game_queue {
+            return Err(Errors::CannotAdvanceGameQueueWrongGameQueue.into());
+        }
+
+        let queue = Account::<GameQueue>::try_from(queue_account)?;
+        if queue.game != game_account.key() {
+            return Err(Errors::CannotAdvanceGameQueueIncorrectGameKey.into());
+        }
+        queues.push(queue);
+    }
+
+    Ok(queues)
+}
+
+/// Loads the current player of every queue that is not empty, which are passed in queue order after the queues.
+/// 
+/// The players are returned next to their queue, with `None` for the empty queues.
+fn load_current_players<'info>(queues: &[Account<'info, GameQueue>], accounts: &[AccountInfo<'info>]) -> Result<Vec<Option<Account<'info, Player>>>> {
+    let mut player_accounts = accounts.iter();
+    let mut players = Vec::with_capacity(queues.len());
+    for queue in queues.iter() {
+        if queue.is_empty() {
+            players.push(None);
+            continue;
+        }
+
+        let player_account = player_accounts.next().ok_or(Errors::CannotAdvanceGameQueueIncorrectPlayers)?;
+        if player_account.key() != queue.current_player {
+            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
+        }
+        players.push(Some(Account::<Player>::try_from(player_account)?));
+    }
+
+    Ok(players)
+}
+
+/// Checks that `authority` may move a game's queues on: the game's operator, the wallet of a player whose turn is ending, or
+/// anybody once the session of one of the current players has run out.
+fn check_queue_authority(game: &Game, authority: &Pubkey, queues: &[Account<GameQueue>], finishing_wallets: &[Pubkey], now: i64) -> Result<()> {
+    if *authority == game.operator || finishing_wallets.contains(authority) || queues.iter().any(|queue| game.session_expired(queue, now)) {
+        Ok(())
+    } else {
+        Err(Errors::NotQueueAuthority.into())
+    }
+}
+
+/// Creates the player account of `wallet`, who is joining `queue` as its `sequence`th player after paying `fee`.  The account is
+/// paid for by `payer`.
+#[allow(clippy::too_many_arguments)]
+fn create_player<'info>(
+    payer: &Signer<'info>,
+    wallet: Pubkey,
+    player_account: &AccountInfo<'info>,
+    queue: &Pubkey,
+    sequence: u64,
+    fee: &PlayFee,
+    system_program: &Program<'info, System>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    create_pda_account(
+        &payer.to_account_info(),
+        player_account,
+        8 + Player::MAX_SIZE,
+        &[b"player", queue.as_ref(), &sequence.to_le_bytes()],
+        &system_program.to_account_info(),
+        program_id,
+    )?;
+
+    let player = Player {
+        wallet_key: wallet,
+        next_player: None,
+        fee_paid: fee.amount,
+        fee_mint: fee.mint,
+        fee_credits: fee.credits,
+        credit_discount: fee.discount,
+    };
+    player.try_serialize(&mut &mut player_account.try_borrow_mut_data()?[..])
+}
+
+/// Turns the account of a player whose session is over into their session receipt, which lives at the address derived from
+/// the player and takes over the player account's rent.
+fn issue_session_receipt<'info>(
+    game_key: &Pubkey,
+    player: &Account<'info, Player>,
+    receipt_account: &AccountInfo<'info>,
+    started_at: i64,
+    ended_at: i64,
+    system_program: &Program<'info, System>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let player_key = player.key();
+    let (address, bump) = Pubkey::find_program_address(&[b"session_receipt", player_key.as_ref()], program_id);
+    if receipt_account.key() != address {
+        return Err(Errors::WrongAccountAddress.into());
+    }
+
+    player.close(receipt_account.clone())?;
+
+    let signer_seeds: &[&[u8]] = &[b"session_receipt", player_key.as_ref(), &[bump]];
+    let accounts = [receipt_account.clone(), system_program.to_account_info()];
+    anchor_lang::solana_program::program::invoke_signed(
+        &anchor_lang::solana_program::system_instruction::allocate(&address, (8 + SessionReceipt::MAX_SIZE) as u64),
+        &accounts,
+        &[signer_seeds],
+    )?;
+    anchor_lang::solana_program::program::invoke_signed(
+        &anchor_lang::solana_program::system_instruction::assign(&address, program_id),
+        &accounts,
+        &[signer_seeds],
+    )?;
+
+    let receipt = SessionReceipt {
+        game: *game_key,
+        wallet: player.wallet_key,
+        started_at,
+        ended_at,
+    };
+    receipt.try_serialize(&mut &mut receipt_account.try_borrow_mut_data()?[..])
+}
+
+/// Mutably borrows two different items of a slice at once.
+fn pair_mut<T>(items: &mut [T], first: usize, second: usize) -> (&mut T, &mut T) {
+    if first < second {
+        let (left, right) = items.split_at_mut(second);
+        (&mut left[first], &mut right[0])
+    } else {
+        let (left, right) = items.split_at_mut(first);
+        (&mut right[0], &mut left[second])
+    }
+}
+
+/// The lamports of a credits account above its rent, which are available to spend on plays.
+fn credits_balance(player_credits: &AccountInfo) -> Result<u64> {
+    let rent = Rent::get()?.minimum_balance(player_credits.data_len());
+    Ok(player_credits.lamports().saturating_sub(rent))
+}
+
+/// The lamports of a treasury above its rent, which the arcade's authority can withdraw.
+fn treasury_balance(treasury: &AccountInfo) -> Result<u64> {
+    let rent = Rent::get()?.minimum_balance(treasury.data_len());
+    Ok(treasury.lamports().saturating_sub(rent))
+}
+
+/// Checks `admin` can run an admin instruction of `role` on an arcade, which it can if it is the arcade's authority or the first
+/// remaining account is its grant of `role`.
+fn authorize_admin(arcade_account: &Account<ArcadeState>, admin: &Pubkey, role: Role, remaining_accounts: &[AccountInfo], program_id: &Pubkey) -> Result<()> {
+    if *admin == arcade_account.authority {
+        return Ok(());
+    }
+
+    let role_grant = remaining_accounts.first().ok_or(Errors::NotArcadeAdmin)?;
+    let (address, _) = Pubkey::find_program_address(&[b"role", arcade_account.key().as_ref(), admin.as_ref(), &[role.seed()]], program_id);
+    if role_grant.key() != address || Account::<RoleGrant>::try_from(role_grant).is_err() {
+        return Err(Errors::NotArcadeAdmin.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that `wallet` may play `game`.  18+ games need the wallet's age attestation (at its address derived from the arcade
+/// and the wallet) to have been issued by the arcade's current age attester and not to have expired, other games need nothing.
+fn check_age(game: &Game, arcade_account: &Account<ArcadeState>, age_attestation: &AccountInfo, wallet: &Pubkey, program_id: &Pubkey) -> Result<()> {
+    if !game.is_adult() {
+        return Ok(());
+    }
+
+    let (address, _) = Pubkey::find_program_address(&[b"age", arcade_account.key().as_ref(), wallet.as_ref()], program_id);
+    if age_attestation.key() != address {
+        return Err(Errors::MissingAgeAttestation.into());
+    }
+    let attestation = Account::<AgeAttestation>::try_from(age_attestation).map_err(|_| Errors::MissingAgeAttestation)?;
+
+    attestation.check(&arcade_account.age_attester, Clock::get()?.unix_timestamp)
+}
+
+/// Counts a play on the profile of `wallet` (at its address derived from the arcade and the wallet).  Wallets without a profile
+/// can still play, their plays are just not counted.
+fn count_play(player_profile: &AccountInfo, arcade_key: &Pubkey, wallet: &Pubkey, program_id: &Pubkey) -> Result<()> {
+    let (address, _) = Pubkey::find_program_address(&[b"profile", arcade_key.as_ref(), wallet.as_ref()], program_id);
+    if player_profile.key() != address {
+        return Err(Errors::WrongAccountAddress.into());
+    }
+    if player_profile.owner != program_id {
+        return Ok(());
+    }
+
+    let mut profile = Account::<PlayerProfile>::try_from(player_profile)?;
+    profile.plays += 1;
+    profile.exit(program_id)
+}
+
+/// Adds lamports to an account, failing instead of wrapping around.
+fn credit_lamports(account: &AccountInfo, lamports: u64) -> Result<()> {
+    let balance = account.lamports().checked_add(lamports).ok_or(Errors::PayoutOverflow)?;
+    **account.lamports.borrow_mut() = balance;
+    Ok(())
+}
+
+/// Creates an account of this program at the address derived from `seeds`, failing if `new_account` is not at that address.
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    new_account: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (address, bump) = Pubkey::find_program_address(seeds, program_id);
+    if new_account.key() != address {
+        return Err(Errors::WrongAccountAddress.into());
+    }
+
+    let bump = [bump];
+    let mut signer_seeds = seeds.to_vec();
+    signer_seeds.push(&bump);
+    create_account(payer, new_account, space, program_id, system_program, &[&signer_seeds])
+}
+
+/// Creates an account owned by `owner` paid for by `payer`, signing with `signer_seeds` when the new account is a PDA.
+/// 
+/// Used for accounts that anchor can not create itself, like accounts passed in through the remaining accounts.
+fn create_account<'info>(
+    payer: &AccountInfo<'info>,
+    new_account: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let lamports = Rent::get()?.minimum_balance(space);
+    let ix = anchor_lang::solana_program::system_instruction::create_account(
+        payer.key,
+        new_account.key,
+        lamports,
+        space as u64,
+        owner,
+    );
+
+    anchor_lang::solana_program::program::invoke_signed(
+        &ix,
+        &[
+            payer.clone(),
+            new_account.clone(),
+            system_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
 }
 
 #[derive(Accounts)]
-/// Context used to advance a 1 player normal game queue.
-pub struct AdvanceOnePlayerGameQueue<'info> {
-    #[account(mut, close = game_account)]
-    pub current_player: Account<'info, Player>,
+pub struct Initialize {}
+
+#[derive(Accounts)]
+/// Context used to initialize the arcade.
+pub struct InitArcade<'info> {
+    #[account(init, payer = authority, space = 8 + ArcadeState::MAX_SIZE)]
+    pub arcade_account: Account<'info, ArcadeState>, // The accound for the arcade state (i.e. the pointer to the newest game).
     #[account(
-        mut,
-        constraint = game_queue_account.current_player == current_player.key(),
-        constraint = game_queue_account.game == game_account.key()
+        init,
+        payer = authority,
+        space = 8 + Game::MAX_SIZE,
+        seeds = [b"game", arcade_account.key().as_ref(), &0u64.to_le_bytes()],
+        bump
     )]
-    pub game_queue_account: Account<'info, GameQueue>,
+    pub genesis_game_account: Account<'info, Game>, // The first game (i.e. the game that began the arcade), it has index 0.
     #[account(
-        mut,
-        constraint = game_account.max_players == 1,
-        constraint = game_account.game_type == 0,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account.key()
+        init,
+        payer = authority,
+        space = 8 + Treasury::MAX_SIZE,
+        seeds = [b"treasury", arcade_account.key().as_ref()],
+        bump
     )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub treasury: Account<'info, Treasury>, // The arcade's treasury, which collects the arcade's fees.
+    #[account(mut)]
+    pub authority: Signer<'info>, // The person who pays for initializing the arcade (i.e. me).
+    pub system_program: Program<'info, System>, // The system program to make sure the account created is associated with this program.
 }
 
 #[derive(Accounts)]
-/// Context used to finish (destroy queues) for a 1 player normal game.
-pub struct FinishOnePlayerGameQueue<'info> {
-    #[account(
-        mut,
-        close = game_account,
-        constraint = current_player.next_player == None
-    )]
-    pub current_player: Account<'info, Player>,
+#[instruction(
+    title: String,
+    web_gl_hash: String,
+    game_art_hash: String,
+    num_players: u8,
+    game_type: u8,
+    score_authority: Pubkey,
+    operator: Pubkey,
+    leaderboard_depth: u8
+)]
+/// Context used to create a new game.
+/// 
+/// The game's address is derived from the arcade and the game's index on the arcade.
+pub struct MakeGame<'info> {
     #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account.current_player == current_player.key(),
-        constraint = game_queue_account.last_player == current_player.key(),
-        constraint = game_queue_account.game == game_account.key()
+        init,
+        payer = owner,
+        space = 8 + Game::MAX_SIZE,
+        seeds = [b"game", arcade_account.key().as_ref(), &arcade_account.game_count.to_le_bytes()],
+        bump
     )]
-    pub game_queue_account: Account<'info, GameQueue>,
+    pub game_account: Box<Account<'info, Game>>,
     #[account(
-        mut,
-        constraint = game_account.max_players == 1,
-        constraint = game_account.game_type == 0,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account.key()
+        init,
+        payer = owner,
+        space = 8 + Leaderboard::size(leaderboard_depth),
+        seeds = [b"leaderboard", game_account.key().as_ref()],
+        bump
     )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
+    pub leaderboard_account: Box<Account<'info, Leaderboard>>,
+    #[account(mut)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    #[account(mut)]
+    pub most_recent_game_account: Account<'info, Game>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to initialize the game queues of a 2 player game.
-pub struct InitTwoPlayerGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_two: Account<'info, GameQueue>,
+/// Context used to delete a game.
+pub struct DeleteGame<'info> {
     #[account(
         mut,
-        constraint = game_account.max_players == 2,
-        constraint = (game_account.game_queues.get(0) == None) || ((*game_account.game_queues.get(0).unwrap()) == game_account.key()),
-        constraint = (game_account.game_queues.get(1) == None) || ((*game_account.game_queues.get(1).unwrap()) == game_account.key())
+        close = owner,
+        constraint = game_account.key() == younger_game.older_game_key,
+        constraint = game_account.key() == older_game.younger_game_key,
+        constraint = game_account.owner_wallet == owner.key(),
+        constraint = !game_account.owes_collaborators() @Errors::UnclaimedCollaboratorShare,
+        constraint = game_account.queued_fees == 0 @Errors::QueuedFeesOwed,
+        constraint = game_account.queued_tokens == 0 @Errors::QueuedTokensOwed,
+        constraint = game_account.escrow_reserved == 0 @Errors::UnclaimedTokenPots,
     )]
-    pub game_account: Account<'info, Game>,
+    pub game_account: Box<Account<'info, Game>>,
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub younger_game: Account<'info, Game>,
+    #[account(mut)]
+    pub older_game: Account<'info, Game>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to join a normal 2 player game.
-pub struct JoinTwoPlayerGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(mut, constraint = q1_last_player.next_player == None)]
-    pub q1_last_player: Account<'info, Player>,
-    #[account(mut, constraint = q2_last_player.next_player == None)]
-    pub q2_last_player: Account<'info, Player>,
-    #[account(mut, constraint = game_queue_account_one.last_player == q1_last_player.key())]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    // constraint = game_queue_account_two.last_player == q2_last_player.key() has been removed to allow for situations where second player does not
-    // exist to be handeled by sending in the same player as q1 and q2 last players.
-    #[account(mut)]
-    pub game_queue_account_two: Account<'info, GameQueue>,
+/// Context used to delete the most recent game in an arcade.
+pub struct DeleteMostRecentGame<'info> {
     #[account(
         mut,
-        constraint = game_account.max_players == 2,
-        constraint = game_account.game_type == 0,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key()
+        close = owner,
+        constraint = game_account.key() == older_game.younger_game_key,
+        constraint = game_account.owner_wallet == owner.key(),
+        constraint = !game_account.owes_collaborators() @Errors::UnclaimedCollaboratorShare,
+        constraint = game_account.queued_fees == 0 @Errors::QueuedFeesOwed,
+        constraint = game_account.queued_tokens == 0 @Errors::QueuedTokensOwed,
+        constraint = game_account.escrow_reserved == 0 @Errors::UnclaimedTokenPots,
     )]
     pub game_account: Account<'info, Game>,
     #[account(mut)]
-    pub payer: Signer<'info>,
+    arcade_state: Account<'info, ArcadeState>,
+    #[account(mut)]
+    pub older_game: Account<'info, Game>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to advance a normal 2 player game.
-pub struct AdvanceTwoPlayerGameQueue<'info> {
-    #[account(mut, close = game_account)]
-    pub player_one: Account<'info, Player>,
-    #[account(mut, close = game_account)]
-    pub player_two: Account<'info, Player>,
-    #[account(
-        mut,
-        constraint = game_queue_account_one.current_player == player_one.key() @Errors::CannotAdvanceGameQueueIncorrectPlayers,
-        constraint = game_queue_account_one.game == game_account.key() @Errors::CannotAdvanceGameQueueIncorrectGameKey
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_two.current_player == player_two.key() @Errors::CannotAdvanceGameQueueIncorrectPlayers,
-        constraint = game_queue_account_two.game == game_account.key() @Errors::CannotAdvanceGameQueueIncorrectGameKey
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 2 @Errors::CannotAdvanceGameQueueWrongMaxPlayers,
-        constraint = game_account.game_type == 0 @Errors::CannotAdvanceGameQueueWrongGameType,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key() @Errors::CannotAdvanceGameQueueWrongGameQueue,
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key() @Errors::CannotAdvanceGameQueueWrongGameQueue
-    )]
+/// Context used to replace the score authority of a game.
+pub struct SetScoreAuthority<'info> {
+    #[account(mut, constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
     pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to advance a king of the hill 2 player game.
-pub struct AdvanceTwoPlayerKingOfHillQueue<'info> {
-    #[account(mut)]
-    pub winning_player: Account<'info, Player>,
-    #[account(mut, close = game_account)]
-    pub losing_player: Account<'info, Player>,
-    #[account(
-        mut,
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 2,
-        constraint = game_account.game_type == 1,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key()
-    )]
+/// Context used to set (or clear) the payout schedule of a game.
+pub struct SetPayoutSchedule<'info> {
+    #[account(mut, constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
     pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to finish (destroy queues) of a normal 2 player game.
-pub struct FinishTwoPlayerGameQueue<'info> {
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_one.next_player == None
-    )]
-    pub player_one: Account<'info, Player>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_two.next_player == None
-    )]
-    pub player_two: Account<'info, Player>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_one.current_player == player_one.key(),
-        constraint = game_queue_account_one.last_player == player_one.key(),
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = (game_queue_account_two.current_player == player_two.key()) || (game_queue_account_two.current_player == game_account.key()),
-        constraint = (game_queue_account_two.last_player == player_two.key()) || (game_queue_account_two.last_player == game_account.key()),
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 2,
-        constraint = game_account.game_type == 0,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-    )]
-    pub game_account: Account<'info, Game>,
+/// Context used to propose a new authority for an arcade.
+pub struct ProposeAuthority<'info> {
+    #[account(mut, constraint = arcade_account.authority == authority.key() @Errors::NotArcadeAuthority)]
+    pub arcade_account: Account<'info, ArcadeState>,
     pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to finish (destroy queues) of a king of the hill 2 player game.
-pub struct FinishTwoPlayerKingOfHillQueue<'info> {
-    #[account(mut, close = game_account)]
-    pub losing_player: Account<'info, Player>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
+/// Context used by the proposed authority of an arcade to take it over.
+pub struct AcceptAuthority<'info> {
+    #[account(mut, constraint = arcade_account.pending_authority == Some(new_authority.key()) @Errors::NotPendingAuthority)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey, role: Role)]
+/// Context used to grant a wallet one of an arcade's admin roles.
+pub struct GrantRole<'info> {
+    #[account(constraint = arcade_account.authority == authority.key() @Errors::NotArcadeAuthority)]
+    pub arcade_account: Account<'info, ArcadeState>,
     #[account(
-        mut,
-        constraint = game_account.max_players == 2,
-        constraint = game_account.game_type == 1,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key()
+        init,
+        payer = authority,
+        space = 8 + RoleGrant::MAX_SIZE,
+        seeds = [b"role", arcade_account.key().as_ref(), wallet.as_ref(), &[role.seed()]],
+        bump
     )]
-    pub game_account: Account<'info, Game>,
+    pub role_grant: Account<'info, RoleGrant>,
+    #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to initialize the queues for a 3 player game.
-pub struct InitThreePlayerGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 3,
-        constraint = (game_account.game_queues.get(0) == None) || ((*game_account.game_queues.get(0).unwrap()) == game_account.key()),
-        constraint = (game_account.game_queues.get(1) == None) || ((*game_account.game_queues.get(1).unwrap()) == game_account.key()),
-        constraint = (game_account.game_queues.get(2) == None) || ((*game_account.game_queues.get(2).unwrap()) == game_account.key())
-    )]
-    pub game_account: Account<'info, Game>,
+/// Context used to take an admin role away from a wallet.
+pub struct RevokeRole<'info> {
+    #[account(constraint = arcade_account.authority == authority.key() @Errors::NotArcadeAuthority)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    #[account(mut, close = authority, constraint = role_grant.arcade == arcade_account.key() @Errors::WrongArcade)]
+    pub role_grant: Account<'info, RoleGrant>,
     #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to join a normal 3 player game.
-pub struct JoinThreePlayerGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(mut, constraint = q1_last_player.next_player == None)]
-    pub q1_last_player: Account<'info, Player>,
-    #[account(mut, constraint = q2_last_player.next_player == None)]
-    pub q2_last_player: Account<'info, Player>,
-    #[account(mut, constraint = q3_last_player.next_player == None)]
-    pub q3_last_player: Account<'info, Player>,
-    #[account(mut, constraint = game_queue_account_one.last_player == q1_last_player.key())]
-    pub game_queue_account_one: Box<Account<'info, GameQueue>>,
-    #[account(mut)]
-    pub game_queue_account_two: Box<Account<'info, GameQueue>>,
-    #[account(mut)]
-    pub game_queue_account_three: Box<Account<'info, GameQueue>>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 3,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key()
-    )]
-    pub game_account: Account<'info, Game>,
+/// Context used to set the default payout schedule of an arcade.
+/// 
+/// The authority is the arcade's authority or a curator, who passes their role grant as the first remaining account.
+pub struct SetDefaultPayoutSchedule<'info> {
     #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub arcade_account: Account<'info, ArcadeState>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to advance a normal 3 player game queue.
-pub struct AdvanceThreePlayerGameQueue<'info> {
-    #[account(mut, close = game_account)]
-    pub player_one: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub player_two: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub player_three: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        constraint = game_queue_account_one.current_player == player_one.key(),
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_two.current_player == player_two.key(),
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_three.current_player == player_three.key(),
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 3,
-        constraint = game_account.game_type == 0,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key()
-    )]
+/// Context used to replace the operator of a game.
+pub struct SetOperator<'info> {
+    #[account(mut, constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
     pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to advance a king of the hill 3 player game.
-pub struct AdvanceThreePlayerKingOfHillQueue<'info> {
-    #[account(mut)]
-    pub winning_player: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_one: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_two: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
+/// Context used to propose new collaborators for a game.
+pub struct ProposeCollaborators<'info> {
+    #[account(constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
+    pub game_account: Box<Account<'info, Game>>,
     #[account(
-        mut,
-        constraint = game_account.max_players == 3,
-        constraint = game_account.game_type == 1,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key()
+        init,
+        payer = owner,
+        space = 8 + CollaboratorsProposal::MAX_SIZE,
+        seeds = [b"collaborators", game_account.key().as_ref()],
+        bump
     )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
+    pub proposal: Account<'info, CollaboratorsProposal>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to finish (destroy queues) of a normal 3 player game.
-pub struct FinishThreePlayerGameQueue<'info> {
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_one.next_player == None
-    )]
-    pub player_one: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_two.next_player == None
-    )]
-    pub player_two: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_three.next_player == None
-    )]
-    pub player_three: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_one.current_player == player_one.key(),
-        constraint = game_queue_account_one.last_player == player_one.key(),
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = (game_queue_account_two.current_player == player_two.key()) || (game_queue_account_two.current_player == game_account.key()),
-        constraint = (game_queue_account_two.last_player == player_two.key()) || (game_queue_account_two.last_player == game_account.key()),
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = (game_queue_account_three.current_player == player_three.key()) || (game_queue_account_three.current_player == game_account.key()),
-        constraint = (game_queue_account_three.last_player == player_three.key()) || (game_queue_account_three.last_player == game_account.key()),
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 3,
-        constraint = game_account.game_type == 0,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key()
-    )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Context used by a collaborator to approve a game's proposed collaborators.
+pub struct ApproveCollaborators<'info> {
+    #[account(address = proposal.game @Errors::WrongGame)]
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(mut, seeds = [b"collaborators", game_account.key().as_ref()], bump)]
+    pub proposal: Account<'info, CollaboratorsProposal>,
+    pub wallet: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to finish (destroy queues) of a king of the hill 3 player game.
-pub struct FinishThreePlayerKingOfHillQueue<'info> {
-    #[account(mut, close = game_account)]
-    pub losing_player_one: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_two: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 3,
-        constraint = game_account.game_type == 1,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key()
-    )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Context used to apply a game's approved collaborators.
+pub struct ApplyCollaborators<'info> {
+    #[account(mut, constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(mut, close = owner, seeds = [b"collaborators", game_account.key().as_ref()], bump)]
+    pub proposal: Account<'info, CollaboratorsProposal>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to initialize the queues of a 4 player game.
-pub struct InitFourPlayerGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(init, payer = payer, space = 8 + GameQueue::MAX_SIZE)]
-    pub game_queue_account_four: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 4,
-        constraint = (game_account.game_queues.get(0) == None) || ((*game_account.game_queues.get(0).unwrap()) == game_account.key()),
-        constraint = (game_account.game_queues.get(1) == None) || ((*game_account.game_queues.get(1).unwrap()) == game_account.key()),
-        constraint = (game_account.game_queues.get(2) == None) || ((*game_account.game_queues.get(2).unwrap()) == game_account.key()),
-        constraint = (game_account.game_queues.get(3) == None) || ((*game_account.game_queues.get(3).unwrap()) == game_account.key())
-    )]
+/// Context used to withdraw a game's proposed collaborators.
+pub struct CancelCollaborators<'info> {
+    #[account(constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
     pub game_account: Box<Account<'info, Game>>,
+    #[account(mut, close = owner, seeds = [b"collaborators", game_account.key().as_ref()], bump)]
+    pub proposal: Account<'info, CollaboratorsProposal>,
     #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to join a normal 4 player game's queues.
-pub struct JoinFourPlayerGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(mut, constraint = q1_last_player.next_player == None)]
-    pub q1_last_player: Account<'info, Player>,
-    #[account(mut, constraint = q2_last_player.next_player == None)]
-    pub q2_last_player: Account<'info, Player>,
-    #[account(mut, constraint = q3_last_player.next_player == None)]
-    pub q3_last_player: Account<'info, Player>,
-    #[account(mut, constraint = q4_last_player.next_player == None)]
-    pub q4_last_player: Account<'info, Player>,
-    #[account(mut, constraint = game_queue_account_one.last_player == q1_last_player.key())]
-    pub game_queue_account_one: Box<Account<'info, GameQueue>>,
-    #[account(mut)]
-    pub game_queue_account_two: Box<Account<'info, GameQueue>>,
-    #[account(mut)]
-    pub game_queue_account_three: Box<Account<'info, GameQueue>>,
-    #[account(mut)]
-    pub game_queue_account_four: Box<Account<'info, GameQueue>>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 4,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key(),
-        constraint = (*game_account.game_queues.get(3).unwrap()) == game_queue_account_four.key(),
-    )]
-    pub game_account: Account<'info, Game>,
+/// Context used by a collaborator to claim what they are owed from a game's payouts.
+pub struct ClaimCollaboratorShare<'info> {
     #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to advance a normal 4 player game queue.
-pub struct AdvanceFourPlayerGameQueue<'info> {
-    #[account(mut, close = game_account)]
-    pub player_one: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub player_two: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub player_three: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub player_four: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        constraint = game_queue_account_one.current_player == player_one.key(),
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_two.current_player == player_two.key(),
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_three.current_player == player_three.key(),
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_four.current_player == player_four.key(),
-        constraint = game_queue_account_four.game == game_account.key()
-    )]
-    pub game_queue_account_four: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 4,
-        constraint = game_account.game_type == 0,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key(),
-        constraint = (*game_account.game_queues.get(3).unwrap()) == game_queue_account_four.key(),
-    )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Context used to propose a new owner for a game.
+pub struct ProposeOwner<'info> {
+    #[account(mut, constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
+    pub game_account: Box<Account<'info, Game>>,
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to advance a king of the hill 4 player game's queues.
-pub struct AdvanceFourPlayerKingOfHillQueue<'info> {
-    #[account(mut)]
-    pub winning_player: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_one: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_two: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_three: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_four.game == game_account.key()
-    )]
-    pub game_queue_account_four: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 4,
-        constraint = game_account.game_type == 1,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key(),
-        constraint = (*game_account.game_queues.get(3).unwrap()) == game_queue_account_four.key()
-    )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Context used by the proposed owner of a game to take it over.
+pub struct AcceptOwner<'info> {
+    #[account(mut, constraint = game_account.pending_owner == Some(new_owner.key()) @Errors::NotPendingOwner)]
+    pub game_account: Box<Account<'info, Game>>,
+    pub new_owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to advance a team king of the hill game's queues.
-pub struct AdvanceTeamKingOfHillQueue<'info> {
-    #[account(mut)]
-    pub winning_player_one: Box<Account<'info, Player>>,
+/// Context used by a moderator to give a game a strike, dismiss its appeal or clear its strikes.
+/// 
+/// The moderator is the arcade's authority or a moderator, who passes their role grant as the first remaining account.
+pub struct ModerateGame<'info> {
     #[account(mut)]
-    pub winning_player_two: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_one: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_two: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_four.game == game_account.key()
-    )]
-    pub game_queue_account_four: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 4,
-        constraint = game_account.game_type == 2,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key(),
-        constraint = (*game_account.game_queues.get(3).unwrap()) == game_queue_account_four.key()
-    )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(address = game_account.arcade @Errors::WrongArcade)]
+    pub arcade_account: Box<Account<'info, ArcadeState>>,
+    pub moderator: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context to finish (destroy queues) a 4 player game.
-pub struct FinishFourPlayerGameQueue<'info> {
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_one.next_player == None
-    )]
-    pub player_one: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_two.next_player == None
-    )]
-    pub player_two: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_three.next_player == None
-    )]
-    pub player_three: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = player_four.next_player == None
-    )]
-    pub player_four: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_one.current_player == player_one.key(),
-        constraint = game_queue_account_one.last_player == player_one.key(),
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = (game_queue_account_two.current_player == player_two.key()) || (game_queue_account_two.current_player == game_account.key()),
-        constraint = (game_queue_account_two.last_player == player_two.key()) || (game_queue_account_two.last_player == game_account.key()),
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = (game_queue_account_three.current_player == player_three.key()) || (game_queue_account_three.current_player == game_account.key()),
-        constraint = (game_queue_account_three.last_player == player_three.key()) || (game_queue_account_three.last_player == game_account.key()),
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = (game_queue_account_four.current_player == player_four.key()) || (game_queue_account_four.current_player == game_account.key()),
-        constraint = (game_queue_account_four.last_player == player_four.key()) || (game_queue_account_four.last_player == game_account.key()),
-        constraint = game_queue_account_four.game == game_account.key()
-    )]
-    pub game_queue_account_four: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 4,
-        constraint = game_account.game_type == 0,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key(),
-        constraint = (*game_account.game_queues.get(3).unwrap()) == game_queue_account_four.key(),
-    )]
-    pub game_account: Account<'info, Game>,
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Context used by the owner of a game to appeal its strikes.
+pub struct AppealGame<'info> {
+    #[account(mut, constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
+    pub game_account: Box<Account<'info, Game>>,
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context to finish (destroy queues) for a 4 player king of the hill game.
-pub struct FinishFourPlayerKingOfHillQueue<'info> {
-    #[account(mut, close = game_account)]
-    pub losing_player_one: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_two: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_three: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        close = game_account,
-        constraint = game_queue_account_four.game == game_account.key()
-    )]
-    pub game_queue_account_four: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 4,
-        constraint = game_account.game_type == 1,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key(),
-        constraint = (*game_account.game_queues.get(3).unwrap()) == game_queue_account_four.key()
-    )]
-    pub game_account: Account<'info, Game>,
+/// Context used to set how many strikes hide a game.
+/// 
+/// The authority is the arcade's authority or a curator, who passes their role grant as the first remaining account.
+pub struct SetStrikeThreshold<'info> {
+    #[account(mut)]
+    pub arcade_account: Account<'info, ArcadeState>,
     pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to finish (destroy queues) for a 4 player team king of the hill game.
-pub struct FinishTeamKingOfHillQueue<'info> {
-    #[account(mut, close = game_account)]
-    pub losing_player_one: Box<Account<'info, Player>>,
-    #[account(mut, close = game_account)]
-    pub losing_player_two: Box<Account<'info, Player>>,
-    #[account(
-        mut,
-        constraint = game_queue_account_one.game == game_account.key()
-    )]
-    pub game_queue_account_one: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_two.game == game_account.key()
-    )]
-    pub game_queue_account_two: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_three.game == game_account.key()
-    )]
-    pub game_queue_account_three: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_queue_account_four.game == game_account.key()
-    )]
-    pub game_queue_account_four: Account<'info, GameQueue>,
-    #[account(
-        mut,
-        constraint = game_account.max_players == 4,
-        constraint = game_account.game_type == 2,
-        constraint = (*game_account.game_queues.get(0).unwrap()) == game_queue_account_one.key(),
-        constraint = (*game_account.game_queues.get(1).unwrap()) == game_queue_account_two.key(),
-        constraint = (*game_account.game_queues.get(2).unwrap()) == game_queue_account_three.key(),
-        constraint = (*game_account.game_queues.get(3).unwrap()) == game_queue_account_four.key()
-    )]
-    pub game_account: Account<'info, Game>,
+/// Context used to set the age attester of an arcade.
+pub struct SetAgeAttester<'info> {
+    #[account(mut, constraint = arcade_account.authority == authority.key() @Errors::NotArcadeAuthority)]
+    pub arcade_account: Account<'info, ArcadeState>,
     pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to join a king of the hill style game.
-pub struct JoinKingOfHillGameQueue<'info> {
-    #[account(init, payer = payer, space = 8 + Player::MAX_SIZE)]
-    pub player_account: Account<'info, Player>,
-    #[account(mut, constraint = last_player.next_player == None)]
-    pub last_player: Account<'info, Player>,
-    #[account(mut, constraint = (game_queue_account.last_player == last_player.key()) || (game_queue_account.last_player == game_account.key()))]
-    pub game_queue_account: Account<'info, GameQueue>,
-    #[account(mut, constraint = (game_account.game_type == 1) || (game_account.game_type == 2))]
-    pub game_account: Account<'info, Game>,
+/// Context used to attest (or renew the attestation of) a wallet's age.
+pub struct AttestAge<'info> {
+    #[account(constraint = arcade_account.age_attester == attester.key() @Errors::NotAgeAttester)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    /// CHECK: the attestation is created at (or rewritten in place at) its address, derived from the arcade and the wallet.
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub age_attestation: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub attester: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-/// Context used to begin the payback of a games funds to the owner and winners
-pub struct PaybackGameFunds<'info> {
-    #[account(init, payer = owner, space = 8 + GamePot::MAX_SIZE)]
-    pub player_one_pot: Account<'info, GamePot>,
-    #[account(init, payer = owner, space = 8 + GamePot::MAX_SIZE)]
-    pub player_two_pot: Account<'info, GamePot>,
-    #[account(init, payer = owner, space = 8 + GamePot::MAX_SIZE)]
-    pub player_three_pot: Account<'info, GamePot>,
-    #[account(mut, constraint = game_account.owner_wallet == owner.key())]
-    pub game_account: Account<'info, Game>,
-    #[account(mut)]
+/// Context used to revoke a wallet's age attestation, its rent goes to the attester.
+pub struct RevokeAgeAttestation<'info> {
+    #[account(constraint = arcade_account.age_attester == attester.key() @Errors::NotAgeAttester)]
     pub arcade_account: Account<'info, ArcadeState>,
+    #[account(mut, close = attester, constraint = age_attestation.arcade == arcade_account.key() @Errors::WrongArcade)]
+    pub age_attestation: Account<'info, AgeAttestation>,
     #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub attester: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to cash out a game pot to the respective winning player
-pub struct CashOutPot<'info> {
-    #[account(
-        mut,
-        close = winner,
-        constraint = game_pot_account.winner_wallet == winner.key()
-    )]
-    pub game_pot_account: Account<'info, GamePot>,
-    #[account(mut, constraint = previous_pot_account.next_game_pot.unwrap() == game_pot_account.key())]
-    pub previous_pot_account: Account<'info, GamePot>,
-    #[account(mut)]
-    pub winner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Context used to set the maximum session length of a game.
+pub struct SetMaxSession<'info> {
+    #[account(mut, constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
+    pub game_account: Account<'info, Game>,
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to cash out a game pot to the respective winning player (game pot is the most recent pot)
-pub struct CashOutMostRecentPot<'info> {
-    #[account(
-        mut,
-        close = winner,
-        constraint = game_pot_account.winner_wallet == winner.key()
-    )]
-    pub game_pot_account: Account<'info, GamePot>,
-    #[account(mut, constraint = arcade_state.most_recent_game_pot_key.unwrap() == game_pot_account.key())]
-    pub arcade_state: Account<'info, ArcadeState>,
-    #[account(mut)]
-    pub winner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// Context used to set (or clear) the price of a game.
+pub struct SetGamePrice<'info> {
+    #[account(mut, constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner)]
+    pub game_account: Account<'info, Game>,
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-/// Context used to add funds to a game account (could be used for donations or preventing games from going under rent exemption)
-pub struct RefillGameFunds<'info> {
-    #[account(mut)]
-    pub game_account: Account<'info, Game>,
+/// Context used to set the default price of an arcade.
+/// 
+/// The authority is the arcade's authority or a curator, who passes their role grant as the first remaining account.
+pub struct SetDefaultPrice<'info> {
     #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub arcade_account: Account<'info, ArcadeState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to set the prepaid credit terms of an arcade.
+/// 
+/// The authority is the arcade's authority or a treasurer, who passes their role grant as the first remaining account.
+pub struct SetCreditTerms<'info> {
+    #[account(mut)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to set how long winners have to cash out their pots.
+/// 
+/// The authority is the arcade's authority or a curator, who passes their role grant as the first remaining account.
+pub struct SetPotClaimWindow<'info> {
+    #[account(mut)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to set the arcade's fee.
+/// 
+/// The authority is the arcade's authority or a treasurer, who passes their role grant as the first remaining account.
+pub struct SetArcadeFee<'info> {
+    #[account(mut)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to withdraw lamports from the arcade's treasury.
+/// 
+/// The authority is the arcade's authority or a treasurer, who passes their role grant as the first remaining account.
+pub struct WithdrawTreasury<'info> {
+    pub arcade_account: Account<'info, ArcadeState>,
+    #[account(mut, seeds = [b"treasury", arcade_account.key().as_ref()], bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub destination: SystemAccount<'info>, // The account the lamports are sent to.
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to withdraw tokens from one of the treasury's token accounts.
+/// 
+/// The authority is the arcade's authority or a treasurer, who passes their role grant as the first remaining account.
+pub struct WithdrawTreasuryTokens<'info> {
+    pub arcade_account: Account<'info, ArcadeState>,
+    #[account(seeds = [b"treasury", arcade_account.key().as_ref()], bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: checked to be a token account owned by the treasury before any tokens are moved.
+    #[account(mut)]
+    pub treasury_token_account: UncheckedAccount<'info>,
+    /// CHECK: the token account the tokens are sent to, the token program makes sure it is in the same mint.
+    #[account(mut)]
+    pub destination_token_account: UncheckedAccount<'info>,
+    /// CHECK: this is the SPL token program.
+    #[account(address = spl_token::ID @Errors::WrongTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to create the token escrow of a game.
+pub struct InitTokenEscrow<'info> {
+    #[account(
+        mut,
+        constraint = game_account.owner_wallet == owner.key() @Errors::NotGameOwner,
+        constraint = game_account.escrow.is_none() @Errors::EscrowAlreadyInitialized
+    )]
+    pub game_account: Account<'info, Game>,
+    /// CHECK: created as a token account by init_token_escrow.
+    #[account(mut, seeds = [b"escrow", game_account.key().as_ref()], bump)]
+    pub escrow_account: UncheckedAccount<'info>,
+    /// CHECK: the token program makes sure this is a mint when the escrow is initialized.
+    pub mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: this is the SPL token program.
+    #[account(address = spl_token::ID @Errors::WrongTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// The accounts used to pay for a play in tokens.
+pub struct TokenPayment<'info> {
+    /// CHECK: the token program makes sure the payer owns this account and that it is in the escrow's mint.
+    #[account(mut)]
+    pub payer_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked against the game's escrow before any tokens are moved.
+    #[account(mut)]
+    pub escrow_account: UncheckedAccount<'info>,
+    /// CHECK: this is the SPL token program.
+    #[account(address = spl_token::ID @Errors::WrongTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+}
+
+impl<'info> TokenPayment<'info> {
+    /// Moves the game's token price from the payer into the game's escrow.
+    fn charge(&self, game_account: &Account<'info, Game>, arcade_account: &ArcadeState, payer: &Signer<'info>) -> Result<PlayFee> {
+        let (mint, amount) = match game_account.play_price(arcade_account) {
+            PricePolicy::Token { mint, amount } => (mint, amount),
+            _ => return Err(Errors::PlayNotPricedInTokens.into()),
+        };
+
+        if game_account.escrow != Some(self.escrow_account.key()) || unpack_token_account(&self.escrow_account)?.mint != mint {
+            return Err(Errors::WrongEscrow.into());
+        }
+
+        transfer_tokens(
+            &self.token_program,
+            &self.payer_token_account,
+            &self.escrow_account,
+            &payer.to_account_info(),
+            amount,
+            &[],
+        )?;
+
+        Ok(PlayFee { amount, mint: Some(mint), credits: None, discount: 0 })
+    }
+}
+
+#[derive(Accounts)]
+/// The accounts used to pay for a play from prepaid credits.
+pub struct CreditPayment<'info> {
+    #[account(mut)]
+    pub player_credits: Account<'info, PlayerCredits>,
+}
+
+impl<'info> CreditPayment<'info> {
+    /// Moves the game's discounted price from the credits into the game.  The credits can be spent by their wallet or its kiosk.
+    fn charge(&mut self, game_account: &Account<'info, Game>, arcade_account: &ArcadeState, price_feed: &AccountInfo, spender: &Signer<'info>) -> Result<PlayFee> {
+        let player_credits = &mut self.player_credits;
+
+        if player_credits.arcade != game_account.arcade {
+            return Err(Errors::WrongPlayerCredits.into());
+        }
+        if spender.key() != player_credits.wallet && Some(spender.key()) != player_credits.kiosk {
+            return Err(Errors::NotCreditsSpender.into());
+        }
+
+        let price = play_fee_lamports(game_account, arcade_account, price_feed)?;
+        let discount = u64::try_from((price as u128) * (arcade_account.credit_discount_bps as u128) / (BASIS_POINTS as u128)).map_err(|_| Errors::PriceOverflow)?;
+        let amount = price - discount;
+        if amount > credits_balance(&player_credits.to_account_info())? {
+            return Err(Errors::InsufficientCredits.into());
+        }
+
+        let credits_info = player_credits.to_account_info();
+        **credits_info.lamports.borrow_mut() = credits_info.lamports() - amount;
+        credit_lamports(&game_account.to_account_info(), amount)?;
+        player_credits.discounts_received = player_credits.discounts_received.checked_add(discount).ok_or(Errors::PayoutOverflow)?;
+
+        emit!(CreditsEvent {
+            label: "PLAY".to_string(),
+            credits_id: player_credits.key(),
+            wallet: player_credits.wallet,
+            lamports: amount,
+            balance: credits_balance(&player_credits.to_account_info())?,
+        });
+
+        Ok(PlayFee { amount, mint: None, credits: Some(player_credits.key()), discount })
+    }
+}
+
+#[derive(Accounts)]
+/// Context used to open the prepaid play credits of a wallet.
+pub struct OpenPlayerCredits<'info> {
+    #[account(
+        init,
+        payer = wallet,
+        space = 8 + PlayerCredits::MAX_SIZE,
+        seeds = [b"credits", arcade_account.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub player_credits: Account<'info, PlayerCredits>,
+    pub arcade_account: Account<'info, ArcadeState>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to buy play credits.
+pub struct TopUpCredits<'info> {
+    #[account(mut)]
+    pub player_credits: Account<'info, PlayerCredits>,
+    #[account(address = player_credits.arcade)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to set the kiosk that can spend a wallet's credits.
+pub struct SetCreditsKiosk<'info> {
+    #[account(mut, constraint = player_credits.wallet == wallet.key() @Errors::NotPlayerWallet)]
+    pub player_credits: Account<'info, PlayerCredits>,
+    pub wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to withdraw unused credits.
+pub struct WithdrawCredits<'info> {
+    #[account(mut, constraint = player_credits.wallet == wallet.key() @Errors::NotPlayerWallet)]
+    pub player_credits: Account<'info, PlayerCredits>,
+    #[account(mut, seeds = [b"treasury", player_credits.arcade.as_ref()], bump)]
+    pub treasury: Account<'info, Treasury>, // The arcade's treasury, which gets back the discounts received on plays.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to create a wallet's profile on an arcade.
+pub struct CreatePlayerProfile<'info> {
+    #[account(
+        init,
+        payer = wallet,
+        space = 8 + PlayerProfile::MAX_SIZE,
+        seeds = [b"profile", arcade_account.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    /// CHECK: the initials are registered at their address (derived from the arcade and the initials) by the instruction.
+    #[account(mut)]
+    pub initials_claim: UncheckedAccount<'info>,
+    pub arcade_account: Account<'info, ArcadeState>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to change the initials of a wallet's profile.
+pub struct ChangeInitials<'info> {
+    #[account(mut, constraint = player_profile.wallet == wallet.key() @Errors::NotPlayerWallet)]
+    pub player_profile: Account<'info, PlayerProfile>,
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"initials", player_profile.arcade.as_ref(), player_profile.initials.as_bytes()],
+        bump
+    )]
+    pub old_initials_claim: Account<'info, InitialsClaim>,
+    /// CHECK: the new initials are registered at their address (derived from the arcade and the initials) by the instruction.
+    #[account(mut)]
+    pub new_initials_claim: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to set the price feed of an arcade.
+/// 
+/// The authority is the arcade's authority or a curator, who passes their role grant as the first remaining account.
+pub struct SetPriceFeed<'info> {
+    #[account(mut)]
+    pub arcade_account: Account<'info, ArcadeState>,
+    /// CHECK: the feed is parsed according to the price feed kind before it is stored.
+    pub price_feed: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to create a manual price feed.
+pub struct InitManualPriceFeed<'info> {
+    #[account(init, payer = authority, space = 8 + ManualPriceFeed::MAX_SIZE)]
+    pub price_feed: Account<'info, ManualPriceFeed>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to publish a new price to a manual price feed.
+pub struct UpdateManualPriceFeed<'info> {
+    #[account(mut, has_one = authority)]
+    pub price_feed: Account<'info, ManualPriceFeed>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(score: u128, wallet_key: Pubkey, nonce: u64)]
+/// Context used to signify a game has ended and it is time to update the leaderboard.
+pub struct GameEnd<'info> {
+    #[account(mut)]
+    pub game_account: Account<'info, Game>,
+    #[account(mut, seeds = [b"leaderboard", game_account.key().as_ref()], bump)]
+    pub leaderboard_account: Account<'info, Leaderboard>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ScoreNonce::MAX_SIZE,
+        seeds = [b"score_nonce", game_account.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub score_nonce_account: Account<'info, ScoreNonce>, // Exists once a nonce has been used so the same score cannot be posted twice.
+    #[account(
+        mut,
+        close = wallet,
+        constraint = session_receipt.game == game_account.key() @Errors::WrongSessionReceipt,
+        constraint = session_receipt.wallet == wallet_key @Errors::WrongSessionReceipt
+    )]
+    pub session_receipt: Account<'info, SessionReceipt>, // A session the wallet played, which is used up by the score.
+    #[account(mut, address = session_receipt.wallet @Errors::NotPlayerWallet)]
+    pub wallet: SystemAccount<'info>, // The wallet that played the session, which gets the receipt's rent back.
+    #[account(seeds = [b"profile", game_account.arcade.as_ref(), wallet_key.as_ref()], bump)]
+    pub player_profile: Account<'info, PlayerProfile>, // The wallet's profile, which holds the initials the score is shown with.
+    /// CHECK: The address is checked to be the instructions sysvar.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to initialize the game queues of a game.
+/// 
+/// The new queue accounts (at the addresses derived from the game and queue numbers) are passed as remaining accounts.
+pub struct InitGameQueue<'info> {
+    /// CHECK: the player is created at its address (derived from the first queue and the game's first player sequence) by the instruction.
+    #[account(mut)]
+    pub player_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = game_account.game_queues.iter().all(|game_queue| *game_queue == game_account.key()) @Errors::AlreadyInitializedGameQueue,
+        constraint = !game_account.hidden @Errors::GameHidden
+    )]
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(address = game_account.arcade)]
+    pub arcade_account: Box<Account<'info, ArcadeState>>,
+    /// CHECK: this is the arcade's price feed, it is only read (according to the arcade's price feed kind) for US cent prices.
+    #[account(address = arcade_account.price_feed @Errors::WrongPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+    /// CHECK: the playing wallet's age attestation, only read for 18+ games (any account can be passed for the others).
+    pub age_attestation: UncheckedAccount<'info>,
+    /// CHECK: the playing wallet's profile, which counts the play if the wallet has one (checked in count_play).
+    #[account(mut)]
+    pub player_profile: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitGameQueue<'info> {
+    /// Creates the game's queues with the paying player at the front of the first one.
+    fn init_queues(&mut self, queue_accounts: &[AccountInfo<'info>], program_id: &Pubkey, fee: PlayFee, wallet: Pubkey) -> Result<()> {
+        check_age(&self.game_account, &self.arcade_account, &self.age_attestation, &wallet, program_id)?;
+        count_play(&self.player_profile, &self.arcade_account.key(), &wallet, program_id)?;
+
+        let player_account = &self.player_account;
+        let game_account = &mut self.game_account;
+        let payer = &self.payer;
+
+        if queue_accounts.len() != game_account.max_players as usize {
+            return Err(Errors::CannotAdvanceGameQueueWrongMaxPlayers.into());
+        }
+
+        let mut new_player_ids = Vec::with_capacity(queue_accounts.len());
+        let mut num_players = Vec::with_capacity(queue_accounts.len());
+        for (i, queue_account) in queue_accounts.iter().enumerate() {
+            let game_key = game_account.key();
+            create_pda_account(
+                &payer.to_account_info(),
+                queue_account,
+                8 + GameQueue::MAX_SIZE,
+                &[b"game_queue", game_key.as_ref(), &[(i + 1) as u8]],
+                &self.system_program.to_account_info(),
+                program_id,
+            )?;
+
+            let mut game_queue = GameQueue {
+                game: game_key,
+                current_player: game_key,
+                last_player: game_key,
+                num_players_in_queue: 0,
+                next_sequence: game_account.first_player_sequence,
+                current_started_at: 0,
+            };
+            if i == 0 {
+                create_player(payer, wallet, player_account, &queue_account.key(), game_queue.next_sequence, &fee, &self.system_program, program_id)?;
+                game_queue.current_player = player_account.key();
+                game_queue.last_player = player_account.key();
+                game_queue.num_players_in_queue = 1;
+                game_queue.next_sequence += 1;
+                game_queue.current_started_at = Clock::get()?.unix_timestamp;
+            }
+            game_queue.try_serialize(&mut &mut queue_account.try_borrow_mut_data()?[..])?;
+
+            new_player_ids.push(if i == 0 { Some(player_account.key()) } else { None });
+            num_players.push(game_queue.num_players_in_queue);
+        }
+
+        game_account.game_queues = queue_accounts.iter().map(|queue_account| queue_account.key()).collect();
+        game_account.hold_fee(&fee)?;
+
+        // emit an event to make known the queues were initialized
+        emit!(NewQueueEvent {
+            game_id: game_account.key(),
+            queue_ids: game_account.game_queues.clone(),
+            queue_nums: (1..=game_account.max_players).collect(),
+            new_player_ids,
+            num_players,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+/// Context used for init_game_queue when the play is paid in tokens.
+pub struct InitGameQueueWithTokens<'info> {
+    pub queue: InitGameQueue<'info>,
+    pub payment: TokenPayment<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used for init_game_queue when the play is paid from prepaid credits.
+pub struct InitGameQueueWithCredits<'info> {
+    pub queue: InitGameQueue<'info>,
+    pub credits: CreditPayment<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to join the queues of a normal game.
+/// 
+/// The game's queues and the last player of the queue being joined are passed as remaining accounts, the new player's address
+/// is derived from the queue being joined.
+pub struct JoinGameQueue<'info> {
+    /// CHECK: the player is created at its address (derived from the queue it joins and its sequence number) by the instruction.
+    #[account(mut)]
+    pub player_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = game_account.base_game_type() == 0 @Errors::CannotAdvanceGameQueueWrongGameType,
+        constraint = !game_account.hidden @Errors::GameHidden
+    )]
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(address = game_account.arcade)]
+    pub arcade_account: Box<Account<'info, ArcadeState>>,
+    /// CHECK: this is the arcade's price feed, it is only read (according to the arcade's price feed kind) for US cent prices.
+    #[account(address = arcade_account.price_feed @Errors::WrongPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+    /// CHECK: the playing wallet's age attestation, only read for 18+ games (any account can be passed for the others).
+    pub age_attestation: UncheckedAccount<'info>,
+    /// CHECK: the playing wallet's profile, which counts the play if the wallet has one (checked in count_play).
+    #[account(mut)]
+    pub player_profile: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> JoinGameQueue<'info> {
+    /// Adds the paying player to the back of the shortest of the game's queues.
+    fn join_queue(&mut self, accounts: &[AccountInfo<'info>], program_id: &Pubkey, fee: PlayFee, wallet: Pubkey) -> Result<()> {
+        check_age(&self.game_account, &self.arcade_account, &self.age_attestation, &wallet, program_id)?;
+        count_play(&self.player_profile, &self.arcade_account.key(), &wallet, program_id)?;
+
+        let player_account = &self.player_account;
+        let game_account = &mut self.game_account;
+        let payer = &self.payer;
+
+        let mut queues = load_game_queues(game_account, accounts)?;
+        let num_queues = queues.len();
+
+        // Empty queues hold no players, so they are filled before anybody lines up behind another player.
+        let (queue_index, game_queue_account) = queues
+            .iter_mut()
+            .enumerate()
+            .min_by_key(|(_, queue)| queue.num_players_in_queue)
+            .ok_or(Errors::CannotAdvanceGameQueueWrongGameQueue)?;
+
+        create_player(payer, wallet, player_account, &game_queue_account.key(), game_queue_account.next_sequence, &fee, &self.system_program, program_id)?;
+        game_queue_account.next_sequence += 1;
+
+        if game_queue_account.is_empty() {
+            game_queue_account.current_player = player_account.key();
+            game_queue_account.current_started_at = Clock::get()?.unix_timestamp;
+        } else {
+            let last_player_info = accounts.get(num_queues).ok_or(Errors::CannotAdvanceGameQueueIncorrectPlayers)?;
+            if last_player_info.key() != game_queue_account.last_player {
+                return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
+            }
+
+            let mut last_player = Account::<Player>::try_from(last_player_info)?;
+            last_player.next_player = Some(player_account.key());
+            last_player.exit(program_id)?;
+        }
+        game_queue_account.last_player = player_account.key();
+        game_queue_account.num_players_in_queue += 1;
+        game_queue_account.exit(program_id)?;
+        game_account.hold_fee(&fee)?;
+
+        // emit an event to make known a player has joined a game queue
+        emit!(JoinQueueEvent {
+            game_id: game_account.key(),
+            queue_id: game_queue_account.key(),
+            queue_num: (queue_index + 1) as u8,
+            last_player: player_account.key(),
+            num_players: game_queue_account.num_players_in_queue,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+/// Context used for join_game_queue when the play is paid in tokens.
+pub struct JoinGameQueueWithTokens<'info> {
+    pub queue: JoinGameQueue<'info>,
+    pub payment: TokenPayment<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used for join_game_queue when the play is paid from prepaid credits.
+pub struct JoinGameQueueWithCredits<'info> {
+    pub queue: JoinGameQueue<'info>,
+    pub credits: CreditPayment<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to advance a normal game queue.
+/// 
+/// The game's queues and their current players are passed as remaining accounts.
+pub struct AdvanceGameQueue<'info> {
+    #[account(mut, constraint = game_account.base_game_type() == 0 @Errors::CannotAdvanceGameQueueWrongGameType)]
+    pub game_account: Account<'info, Game>,
+    pub authority: Signer<'info>, // the game's operator, a player whose turn is ending, or anybody once a session has run out.
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to advance a king of the hill or team king of the hill game queue.
+/// 
+/// The game's queues and their current players are passed as remaining accounts.
+pub struct AdvanceKingOfHillQueue<'info> {
+    #[account(
+        mut,
+        constraint = game_account.base_game_type() != 0 @Errors::CannotAdvanceGameQueueWrongGameType
+    )]
+    pub game_account: Account<'info, Game>,
+    pub authority: Signer<'info>, // the game's operator or a losing player.
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to finish (destroy queues) of a game.
+/// 
+/// The game's queues and their current players are passed as remaining accounts.
+pub struct FinishGameQueue<'info> {
+    #[account(mut)]
+    pub game_account: Account<'info, Game>,
+    pub authority: Signer<'info>, // the game's operator, a player whose turn is ending, or anybody once a session has run out.
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to join a king of the hill style game.
+pub struct JoinKingOfHillGameQueue<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Player::MAX_SIZE,
+        seeds = [b"player", game_queue_account.key().as_ref(), &game_queue_account.next_sequence.to_le_bytes()],
+        bump
+    )]
+    pub player_account: Account<'info, Player>,
+    #[account(mut, constraint = last_player.next_player == None)]
+    pub last_player: Account<'info, Player>,
+    #[account(mut, constraint = (game_queue_account.last_player == last_player.key()) || (game_queue_account.last_player == game_account.key()))]
+    pub game_queue_account: Account<'info, GameQueue>,
+    #[account(
+        mut,
+        constraint = game_account.base_game_type() != 0,
+        constraint = !game_account.hidden @Errors::GameHidden
+    )]
+    pub game_account: Account<'info, Game>,
+    #[account(address = game_account.arcade)]
+    pub arcade_account: Box<Account<'info, ArcadeState>>,
+    /// CHECK: this is the arcade's price feed, it is only read (according to the arcade's price feed kind) for US cent prices.
+    #[account(address = arcade_account.price_feed @Errors::WrongPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+    /// CHECK: the playing wallet's age attestation, only read for 18+ games (any account can be passed for the others).
+    pub age_attestation: UncheckedAccount<'info>,
+    /// CHECK: the playing wallet's profile, which counts the play if the wallet has one (checked in count_play).
+    #[account(mut)]
+    pub player_profile: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> JoinKingOfHillGameQueue<'info> {
+    /// Adds the paying player to the back of the game's queues.
+    fn join_queue(&mut self, program_id: &Pubkey, fee: PlayFee, wallet: Pubkey) -> Result<()> {
+        check_age(&self.game_account, &self.arcade_account, &self.age_attestation, &wallet, program_id)?;
+        count_play(&self.player_profile, &self.arcade_account.key(), &wallet, program_id)?;
+
+        let player_account = &mut self.player_account;
+        let last_player = &mut self.last_player;
+        let game_queue_account = &mut self.game_queue_account;
+        let game_account = &mut self.game_account;
+
+        // The queue has to be one of the game's (there are none until the game's queues are initialized).
+        let queue_index = game_account
+            .game_queues
+            .iter()
+            .position(|game_queue| *game_queue == game_queue_account.key())
+            .ok_or(Errors::CannotAdvanceGameQueueWrongGameQueue)?;
+
+        player_account.wallet_key = wallet;
+        player_account.next_player = None;
+        player_account.fee_paid = fee.amount;
+        player_account.fee_mint = fee.mint;
+        player_account.fee_credits = fee.credits;
+        player_account.credit_discount = fee.discount;
+        game_account.hold_fee(&fee)?;
+
+        if game_queue_account.current_player == game_account.key() {
+            game_queue_account.current_player = player_account.key();
+            game_queue_account.current_started_at = Clock::get()?.unix_timestamp;
+        }
+
+        if game_queue_account.last_player == last_player.key() {
+            last_player.next_player = Some(player_account.key());
+        }
+        game_queue_account.last_player = player_account.key();
+        game_queue_account.num_players_in_queue += 1;
+        game_queue_account.next_sequence += 1;
+
+        // emit an event to make known the queue has been joined
+        emit!(JoinQueueEvent {
+            game_id: game_account.key(),
+            queue_id: game_queue_account.key(),
+            queue_num: (queue_index + 1) as u8,
+            last_player: player_account.key(),
+            num_players: game_queue_account.num_players_in_queue,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+/// Context used for join_king_of_hill_game_queue when the play is paid in tokens.
+pub struct JoinKingOfHillGameQueueWithTokens<'info> {
+    pub queue: JoinKingOfHillGameQueue<'info>,
+    pub payment: TokenPayment<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used for join_king_of_hill_game_queue when the play is paid from prepaid credits.
+pub struct JoinKingOfHillGameQueueWithCredits<'info> {
+    pub queue: JoinKingOfHillGameQueue<'info>,
+    pub credits: CreditPayment<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to skip the idle current player of a game queue.
+pub struct SkipIdlePlayer<'info> {
+    #[account(mut, constraint = player_account.key() == game_queue_account.current_player @Errors::CannotAdvanceGameQueueIncorrectPlayers)]
+    pub player_account: Account<'info, Player>,
+    /// CHECK: the skipped player's session receipt is created at its address (derived from the player) by the instruction.
+    #[account(mut)]
+    pub session_receipt: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = game_queue_account.game == game_account.key() @Errors::CannotAdvanceGameQueueIncorrectGameKey,
+        constraint = game_account.game_queues.contains(&game_queue_account.key()) @Errors::CannotAdvanceGameQueueWrongGameQueue
+    )]
+    pub game_queue_account: Account<'info, GameQueue>,
+    #[account(mut)]
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to leave a game queue before it is the player's turn.
+/// 
+/// The players waiting behind the leaving player (and the token accounts or credits for the refund) are passed as remaining accounts.
+pub struct LeaveQueue<'info> {
+    #[account(
+        mut,
+        close = wallet,
+        constraint = player_account.wallet_key == wallet.key() @Errors::NotPlayerWallet,
+        constraint = player_account.key() != game_queue_account.current_player @Errors::CannotLeaveWhilePlaying
+    )]
+    pub player_account: Account<'info, Player>,
+    #[account(mut, constraint = previous_player.next_player == Some(player_account.key()) @Errors::CannotAdvanceGameQueueIncorrectPlayers)]
+    pub previous_player: Account<'info, Player>,
+    #[account(
+        mut,
+        constraint = game_queue_account.game == game_account.key() @Errors::CannotAdvanceGameQueueIncorrectGameKey,
+        constraint = game_account.game_queues.contains(&game_queue_account.key()) @Errors::CannotAdvanceGameQueueWrongGameQueue
+    )]
+    pub game_queue_account: Account<'info, GameQueue>,
+    #[account(mut)]
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+}
+
+impl<'info> LeaveQueue<'info> {
+    /// Takes the player out of the queue's line, returning the remaining accounts after the players waiting behind them.
+    fn unlink<'a>(&mut self, accounts: &'a [AccountInfo<'info>]) -> Result<&'a [AccountInfo<'info>]> {
+        // Following the line from the leaving player to the back of the queue proves which queue they are waiting in.
+        let mut last_player = self.player_account.key();
+        let mut next_player = self.player_account.next_player;
+        let mut num_waiting = 0;
+        while let Some(player_key) = next_player {
+            let player_info = accounts.get(num_waiting).ok_or(Errors::CannotAdvanceGameQueueIncorrectPlayers)?;
+            if player_info.key() != player_key {
+                return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
+            }
+
+            last_player = player_key;
+            next_player = Account::<Player>::try_from(player_info)?.next_player;
+            num_waiting += 1;
+        }
+
+        if last_player != self.game_queue_account.last_player {
+            return Err(Errors::CannotAdvanceGameQueueIncorrectPlayers.into());
+        }
+
+        self.previous_player.next_player = self.player_account.next_player;
+        if self.player_account.next_player.is_none() {
+            self.game_queue_account.last_player = self.previous_player.key();
+        }
+        self.game_queue_account.num_players_in_queue -= 1;
+
+        Ok(&accounts[num_waiting..])
+    }
+
+    /// Pays the player's play fee back to them, out of the game account or out of the game's escrow for fees paid in tokens.
+    /// Fees paid from prepaid credits go back into the credits, which no longer owe the discount received on the play.
+    fn refund(&mut self, refund_accounts: &[AccountInfo<'info>], program_id: &Pubkey) -> Result<()> {
+        let player = &self.player_account;
+        let game_account = &mut self.game_account;
+
+        game_account.release_fee(player);
+
+        match (player.fee_mint, player.fee_credits, refund_accounts) {
+            (None, None, []) => {
+                let game_info = game_account.to_account_info();
+                let remaining_game_lamports = game_info.lamports().checked_sub(player.fee_paid).ok_or(Errors::InsufficientFundsForPayout)?;
+                **game_info.lamports.borrow_mut() = remaining_game_lamports;
+                credit_lamports(&self.wallet.to_account_info(), player.fee_paid)?;
+            }
+            (None, Some(credits_key), [credits_account]) => {
+                if credits_account.key() != credits_key {
+                    return Err(Errors::WrongRefundAccounts.into());
+                }
+                let mut player_credits = Account::<PlayerCredits>::try_from(credits_account)?;
+
+                let game_info = game_account.to_account_info();
+                let remaining_game_lamports = game_info.lamports().checked_sub(player.fee_paid).ok_or(Errors::InsufficientFundsForPayout)?;
+                **game_info.lamports.borrow_mut() = remaining_game_lamports;
+                credit_lamports(credits_account, player.fee_paid)?;
+                player_credits.discounts_received = player_credits.discounts_received.saturating_sub(player.credit_discount);
+                player_credits.exit(program_id)?;
+
+                emit!(CreditsEvent {
+                    label: "REFUND".to_string(),
+                    credits_id: credits_key,
+                    wallet: player_credits.wallet,
+                    lamports: player.fee_paid,
+                    balance: credits_balance(credits_account)?,
+                });
+            }
+            (Some(_), None, [escrow_account, refund_token_account, token_program]) => {
+                if Some(escrow_account.key()) != game_account.escrow {
+                    return Err(Errors::WrongEscrow.into());
+                }
+                if token_program.key() != spl_token::ID {
+                    return Err(Errors::WrongTokenProgram.into());
+                }
+                transfer_from_escrow(token_program, escrow_account, &game_account.key(), refund_token_account, player.fee_paid, program_id)?;
+            }
+            _ => return Err(Errors::WrongRefundAccounts.into()),
+        }
+
+        // emit an event to make known a player has left a game queue
+        emit!(LeaveQueueEvent {
+            game_id: game_account.key(),
+            queue_id: self.game_queue_account.key(),
+            player: player.key(),
+            wallet: player.wallet_key,
+            refund: player.fee_paid,
+            refund_mint: player.fee_mint,
+            num_players: self.game_queue_account.num_players_in_queue,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+/// Context used to begin the payback of a games funds to the owner and winners
+/// 
+/// The new pot accounts (one per paid place held on the leaderboard, first place first) are passed as remaining accounts, at the
+/// addresses derived from their winner's wallet and the next index of the winner's claim index, followed by those claim indexes.
+pub struct PaybackGameFunds<'info> {
+    #[account(
+        mut,
+        constraint = game_account.owner_wallet == owner.key(),
+        constraint = game_account.arcade == arcade_account.key()
+    )]
+    pub game_account: Box<Account<'info, Game>>,
+    #[account(mut, seeds = [b"leaderboard", game_account.key().as_ref()], bump)]
+    pub leaderboard_account: Box<Account<'info, Leaderboard>>,
+    pub arcade_account: Box<Account<'info, ArcadeState>>,
+    #[account(mut, seeds = [b"treasury", arcade_account.key().as_ref()], bump)]
+    pub treasury: Box<Account<'info, Treasury>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+/// Context used to cash out a game pot to the respective winning player
+pub struct CashOutPot<'info> {
+    #[account(
+        mut,
+        close = winner,
+        constraint = game_pot_account.winner_wallet == winner.key()
+    )]
+    pub game_pot_account: Account<'info, GamePot>,
+    #[account(mut, seeds = [b"claim_index", winner.key().as_ref()], bump)]
+    pub claim_index: Account<'info, ClaimIndex>,
+    #[account(mut, seeds = [b"profile", game_pot_account.arcade.as_ref(), winner.key().as_ref()], bump)]
+    pub player_profile: Account<'info, PlayerProfile>,
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CashOutPot<'info> {
+    /// Takes the pot off the winner's unclaimed pots and adds it to their record.
+    fn settle(&mut self) -> Result<()> {
+        self.claim_index.unclaimed_pots = self.claim_index.unclaimed_pots.saturating_sub(1);
+        record_win(&mut self.player_profile, &self.game_pot_account.to_account_info())
+    }
+}
+
+#[derive(Accounts)]
+/// Context used to cash out all of a wallet's pots on an arcade.
+/// 
+/// The pots are passed as remaining accounts.
+pub struct ClaimAll<'info> {
+    #[account(mut, seeds = [b"claim_index", winner.key().as_ref()], bump)]
+    pub claim_index: Account<'info, ClaimIndex>,
+    #[account(mut, constraint = player_profile.wallet == winner.key() @Errors::NotPlayerWallet)]
+    pub player_profile: Account<'info, PlayerProfile>,
+    #[account(mut)]
+    pub winner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+/// Context used to send an expired pot back to its game.
+pub struct ReclaimExpiredPot<'info> {
+    #[account(mut)]
+    pub game_pot_account: Account<'info, GamePot>,
+    /// CHECK: this is the pot's game, which is only loaded if it has not been deleted.
+    #[account(mut, address = game_pot_account.game @Errors::WrongGame)]
+    pub game_account: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"treasury", game_pot_account.arcade.as_ref()], bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, seeds = [b"claim_index", game_pot_account.winner_wallet.as_ref()], bump)]
+    pub claim_index: Account<'info, ClaimIndex>,
+}
+
+#[derive(Accounts)]
+/// Context used to cash out a game pot holding tokens to the respective winning player
+pub struct CashOutTokenPot<'info> {
+    pub cash_out: CashOutPot<'info>,
+    pub payout: TokenPotPayout<'info>,
+}
+
+#[derive(Accounts)]
+/// The accounts used to pay out the tokens of a game pot.
+pub struct TokenPotPayout<'info> {
+    #[account(mut)]
+    pub game_account: Account<'info, Game>,
+    /// CHECK: checked against the game's escrow before any tokens are moved.
+    #[account(mut)]
+    pub escrow_account: UncheckedAccount<'info>,
+    /// CHECK: the token account the winner wants their tokens in, the token program makes sure it is in the escrow's mint.
+    #[account(mut)]
+    pub winner_token_account: UncheckedAccount<'info>,
+    /// CHECK: this is the SPL token program.
+    #[account(address = spl_token::ID @Errors::WrongTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+}
+
+impl<'info> TokenPotPayout<'info> {
+    /// Moves the pot's tokens from the game's escrow to the winner's token account.
+    fn pay(&mut self, game_pot: &GamePot, program_id: &Pubkey) -> Result<()> {
+        if game_pot.game != self.game_account.key() {
+            return Err(Errors::WrongGame.into());
+        }
+        if self.game_account.escrow != Some(self.escrow_account.key()) {
+            return Err(Errors::WrongEscrow.into());
+        }
+
+        transfer_from_escrow(
+            &self.token_program,
+            &self.escrow_account,
+            &self.game_account.key(),
+            &self.winner_token_account,
+            game_pot.token_amount,
+            program_id,
+        )?;
+        self.game_account.escrow_reserved = self.game_account.escrow_reserved.checked_sub(game_pot.token_amount).ok_or(Errors::PayoutOverflow)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+/// Context used to add funds to a game account (could be used for donations or preventing games from going under rent exemption)
+pub struct RefillGameFunds<'info> {
+    #[account(mut)]
+    pub game_account: Account<'info, Game>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+/// The ArcadeState is the account that points to the most current game uploaded to the arcade.
+/// 
+/// I will probably end up paying rent for this to make sure it never dissappears, but it should honestly be incredibly cheap because the rent-exempt
+/// minimum for this size is currently 0.00133632, which is about $0.044232.
+pub struct ArcadeState {
+    pub most_recent_game_key: Pubkey, // the key of the most recent game to be added to the arcade.
+    pub game_count: u64, // the number of games created on the arcade (including the genesis game), which is the index of the next game.
+    pub authority: Pubkey, // the initializer of the arcade's key (aka my key).
+    pub default_payout_schedule: PayoutSchedule, // the payout schedule of games that do not have their own.
+    pub default_price: PricePolicy, // the price of a play for games that do not have their own.
+    pub price_feed: Pubkey, // the SOL/USD price feed used to convert the play fee to lamports.
+    pub price_feed_kind: PriceFeedKind, // how the price feed account is laid out.
+    pub max_price_age_secs: u32, // prices older than this are rejected.
+    pub max_price_confidence_bps: u16, // prices less certain than this (confidence / price in basis points) are rejected.
+    pub credit_discount_bps: u16, // the discount (in basis points of the price) on plays paid from prepaid credits.
+    pub min_credit_top_up: u64, // the fewest lamports of credits that can be bought at once.
+    pub pot_claim_window_secs: u32, // how long winners have to cash out their pots (0 for no deadline).
+    pub arcade_fee_bps: u16, // the share (in basis points) of every payout collected in the arcade's treasury.
+    pub pending_authority: Option<Pubkey>, // the wallet proposed to take over the arcade, until it accepts.
+    pub strike_threshold: u8, // games are hidden once they get this many strikes (0 never hides them).
+    pub age_attester: Pubkey, // the key trusted to attest the age of wallets playing 18+ games (the default key trusts nobody).
+}
+
+impl ArcadeState {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + mem::size_of::<u64>() + mem::size_of::<Pubkey>() +
+                                PayoutSchedule::MAX_SIZE +
+                                PricePolicy::MAX_SIZE + mem::size_of::<Pubkey>() + 1 + mem::size_of::<u32>() + mem::size_of::<u16>() +
+                                mem::size_of::<u16>() + mem::size_of::<u64>() + mem::size_of::<u32>() + mem::size_of::<u16>() +
+                                mem::size_of::<Option<Pubkey>>() + mem::size_of::<u8>() + mem::size_of::<Pubkey>();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+/// What a play of a game costs.
+pub enum PricePolicy {
+    Free,
+    Lamports(u64), // a fixed amount of lamports.
+    UsdCents(u64), // an amount of US cents converted to lamports with the arcade's price feed.
+    Token { mint: Pubkey, amount: u64 }, // an amount of an SPL token paid into the game's escrow.
+}
+
+impl PricePolicy {
+    pub const MAX_SIZE: usize = 1 + mem::size_of::<Pubkey>() + mem::size_of::<u64>();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A play fee that was paid to join a queue.
+pub struct PlayFee {
+    pub amount: u64, // lamports, or tokens when there is a mint.
+    pub mint: Option<Pubkey>,
+    pub credits: Option<Pubkey>, // the prepaid credits the fee was paid from, if any.
+    pub discount: u64, // the credit discount received on the play.
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+/// An admin role the arcade's authority can grant to a wallet, each allowing its own admin instructions (the authority can run
+/// all of them).
+pub enum Role {
+    Moderator, // gives games strikes and clears them or dismisses appeals.
+    Curator, // sets the arcade's default payout schedule, default price, price feed, pot claim window and strike threshold.
+    Treasurer, // sets the arcade's fee and credit terms, and withdraws from its treasury.
+}
+
+impl Role {
+    pub const MAX_SIZE: usize = 1;
+
+    /// The role's byte in the address of its grants.
+    pub fn seed(&self) -> u8 {
+        *self as u8
+    }
+}
+
+#[account]
+/// A grant of an admin role on an arcade to a wallet.
+/// 
+/// Its address is derived from the arcade, the wallet and the role, so holding a role is just having its grant.
+/// 
+/// size(RoleGrant) = 2 * size(Pubkey) + size(Role) = 64 + 1 = 65 Bytes
+pub struct RoleGrant {
+    pub arcade: Pubkey,
+    pub wallet: Pubkey,
+    pub role: Role,
+}
+
+impl RoleGrant {
+    pub const MAX_SIZE: usize = (2 * mem::size_of::<Pubkey>()) + Role::MAX_SIZE;
+}
+
+#[account]
+/// An attestation by an arcade's age attester that a wallet is old enough to play its 18+ games.
+/// 
+/// Its address is derived from the arcade and the wallet, so a wallet has at most one attestation per arcade.
+/// 
+/// size(AgeAttestation) = 3 * size(Pubkey) + size(i64) = 96 + 8 = 104 Bytes
+pub struct AgeAttestation {
+    pub arcade: Pubkey,
+    pub wallet: Pubkey,
+    pub attester: Pubkey, // the attester that issued it, it stops counting if the arcade changes attester.
+    pub expires_at: i64, // the unix timestamp the attestation stops counting at.
+}
+
+impl AgeAttestation {
+    pub const MAX_SIZE: usize = (3 * mem::size_of::<Pubkey>()) + mem::size_of::<i64>();
+
+    /// Checks that the attestation counts at `now` on an arcade whose age attester is `age_attester`: it was issued by that
+    /// attester and has not expired.
+    pub fn check(&self, age_attester: &Pubkey, now: i64) -> Result<()> {
+        if *age_attester == Pubkey::default() || self.attester != *age_attester {
+            return Err(Errors::UntrustedAgeAttestation.into());
+        }
+        if now >= self.expires_at {
+            return Err(Errors::AgeAttestationExpired.into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+/// The layouts of price feed accounts the arcade knows how to read.
+pub enum PriceFeedKind {
+    Pyth, // a Pyth (v2) price account.
+    Manual, // a ManualPriceFeed account owned by this program.
+}
+
+#[account]
+/// A price feed whose price is written by its authority instead of an oracle network.
+/// 
+/// size(ManualPriceFeed) = size(Pubkey) + size(i64) + size(u64) + size(i32) + size(i64) = 32 + 8 + 8 + 4 + 8 = 60 Bytes
+pub struct ManualPriceFeed {
+    pub authority: Pubkey,
+    pub price: i64, // the price of one SOL is price * 10^expo US dollars.
+    pub conf: u64, // the price is give or take conf * 10^expo US dollars.
+    pub expo: i32,
+    pub publish_time: i64, // unix timestamp of the last update.
+}
+
+impl ManualPriceFeed {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + mem::size_of::<i64>() + mem::size_of::<u64>() + mem::size_of::<i32>() + mem::size_of::<i64>();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A SOL/USD price read from a price feed: one SOL is worth price * 10^expo US dollars, give or take conf * 10^expo.
+pub struct SolPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl SolPrice {
+    /// Reads the price out of a price feed account laid out as `kind`.
+    pub fn load(kind: PriceFeedKind, price_feed: &AccountInfo) -> Result<SolPrice> {
+        let data = price_feed.try_borrow_data()?;
+        match kind {
+            PriceFeedKind::Pyth => SolPrice::from_pyth(&data),
+            PriceFeedKind::Manual => {
+                if price_feed.owner != &crate::ID {
+                    return Err(Errors::InvalidPriceFeed.into());
+                }
+                let feed = ManualPriceFeed::try_deserialize(&mut &data[..])?;
+                Ok(SolPrice {
+                    price: feed.price,
+                    conf: feed.conf,
+                    expo: feed.expo,
+                    publish_time: feed.publish_time,
+                })
+            }
+        }
+    }
+
+    /// Reads the aggregate price out of a Pyth price account.
+    pub fn from_pyth(data: &[u8]) -> Result<SolPrice> {
+        let read = |offset: usize, size: usize| data.get(offset..offset + size).ok_or(Errors::InvalidPriceFeed);
+        let read_u32 = |offset: usize| read(offset, 4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+        let read_u64 = |offset: usize| read(offset, 8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()));
+
+        if read_u32(0)? != PYTH_MAGIC || read_u32(4)? != PYTH_VERSION || read_u32(8)? != PYTH_PRICE_ACCOUNT_TYPE {
+            return Err(Errors::InvalidPriceFeed.into());
+        }
+        if read_u32(PYTH_AGG_STATUS_OFFSET)? != PYTH_STATUS_TRADING {
+            return Err(Errors::InvalidPrice.into());
+        }
+
+        Ok(SolPrice {
+            price: read_u64(PYTH_AGG_PRICE_OFFSET)? as i64,
+            conf: read_u64(PYTH_AGG_CONF_OFFSET)?,
+            expo: read_u32(PYTH_EXPO_OFFSET)? as i32,
+            publish_time: read_u64(PYTH_TIMESTAMP_OFFSET)? as i64,
+        })
+    }
+
+    /// Rejects prices that are not positive, older than `max_age_secs` or whose confidence interval is wider than
+    /// `max_confidence_bps` of the price.
+    pub fn check(&self, now: i64, max_age_secs: u32, max_confidence_bps: u16) -> Result<()> {
+        if self.price <= 0 {
+            return Err(Errors::InvalidPrice.into());
+        }
+        if now.saturating_sub(self.publish_time) > max_age_secs as i64 {
+            return Err(Errors::StalePrice.into());
+        }
+        if (self.conf as u128) * (BASIS_POINTS as u128) > (self.price as u128) * (max_confidence_bps as u128) {
+            return Err(Errors::UncertainPrice.into());
+        }
+        Ok(())
+    }
+
+    /// Converts an amount of US cents to lamports, rounding up so the arcade is never underpaid.
+    pub fn lamports_for_cents(&self, cents: u64) -> Result<u64> {
+        let price = u128::try_from(self.price).map_err(|_| Errors::InvalidPrice)?;
+        let scale = 10u128.checked_pow(self.expo.unsigned_abs()).ok_or(Errors::PriceOverflow)?;
+
+        // lamports = cents / 100 * LAMPORTS_PER_SOL / (price * 10^expo)
+        let mut numerator = (cents as u128).checked_mul(LAMPORTS_PER_SOL as u128).ok_or(Errors::PriceOverflow)?;
+        let mut denominator = price.checked_mul(100).ok_or(Errors::PriceOverflow)?;
+        if self.expo < 0 {
+            numerator = numerator.checked_mul(scale).ok_or(Errors::PriceOverflow)?;
+        } else {
+            denominator = denominator.checked_mul(scale).ok_or(Errors::PriceOverflow)?;
+        }
+
+        let lamports = numerator / denominator + u128::from(numerator % denominator != 0);
+        u64::try_from(lamports).map_err(|_| Errors::PriceOverflow.into())
+    }
+}
+
+#[account]
+/// A game is the on-chain block that contains all important information about a game.
+/// 
+/// NOTE: All actual game data and game art will be stored on arweave to keep the gas prices down.  The only unintended consequence of this
+/// is that games may not be modified after their upload, however we can delete a game if the person initializing the delete has the same
+/// wallet public key as the owner_wallet.  The owner_wallet can hand the game over to a new owner, who has to accept it.
+/// 
+/// Game Type:
+/// 0 -> Normal
+/// 1 -> King of the Hill
+/// 2 -> Team King of the Hill
+/// 3 -> 18+ Normal
+/// 4 -> 18+ King of the Hill
+/// 5 -> 18+ Team King of the Hill
+pub struct Game {
+    pub title: String,
+    pub web_gl_hash: String,
+    pub game_art_hash: String,
+    pub max_players: u8,
+    pub game_type: u8,
+    pub strikes: u8,
+    pub game_queues: Vec<Pubkey>,
+    pub younger_game_key: Pubkey,
+    pub older_game_key: Pubkey,
+    pub owner_wallet: Pubkey,
+    pub score_authority: Pubkey,
+    pub operator: Pubkey, // the key allowed to move the game's queues on at any time.
+    pub arcade: Pubkey,
+    pub payout_schedule: Option<PayoutSchedule>,
+    pub price: Option<PricePolicy>,
+    pub escrow: Option<Pubkey>, // the token account holding the tokens paid to play, if the game takes tokens.
+    pub escrow_reserved: u64, // tokens in the escrow that belong to pots that have not been cashed out yet.
+    pub queued_fees: u64, // lamports paid by players still in the queues, which are refunded if they leave.
+    pub queued_tokens: u64, // tokens in the escrow paid by players still in the queues, which are refunded if they leave.
+    pub max_session_secs: u32, // how long a player can be at the front of a queue before they can be skipped, 0 for no limit.
+    pub collaborators: Vec<Collaborator>, // the wallets splitting the owner's share of payouts, empty when the owner gets it all.
+    pub pending_owner: Option<Pubkey>, // the wallet proposed to take over the game, until it accepts.
+    pub hidden: bool, // whether the game has been delisted by moderation, hidden games take no new plays.
+    pub appeal_pending: bool, // whether the owner has appealed the game's strikes.
+    pub first_player_sequence: u64, // the sequence number the players of newly initialized queues start at, which never goes down.
+}
+
+impl Game {
+    pub const MAX_SIZE: usize = (30 * mem::size_of::<char>()) + // size of title
+                                (2 * 256 * mem::size_of::<char>()) + // size of webgl hash and game art hash
+                                (3 * mem::size_of::<u8>()) + // size of max players + game type
+                                (4 + MAX_PLAYERS as usize * mem::size_of::<Pubkey>()) + // size of game queues vector
+                                (6 * mem::size_of::<Pubkey>()) + // size of younger_game_key older_game_key owner wallet score authority operator and arcade
+                                (1 + PayoutSchedule::MAX_SIZE) + // size of the optional payout schedule
+                                (1 + PricePolicy::MAX_SIZE) + // size of the optional price
+                                (1 + mem::size_of::<Pubkey>()) + mem::size_of::<u64>() + // size of the optional escrow and its reserved tokens
+                                (2 * mem::size_of::<u64>()) + // size of the queued fees and tokens
+                                mem::size_of::<u32>() + // size of the max session length
+                                (4 + MAX_COLLABORATORS * Collaborator::MAX_SIZE) + // size of the collaborators vector
+                                mem::size_of::<Option<Pubkey>>() + // size of the pending owner
+                                (2 * mem::size_of::<bool>()) + // size of the hidden and appeal pending flags
+                                mem::size_of::<u64>(); // size of the first player sequence
+
+    /// The price of a play, which is the arcade's default price unless the game has its own.
+    pub fn play_price(&self, arcade: &ArcadeState) -> PricePolicy {
+        self.price.unwrap_or(arcade.default_price)
+    }
+
+    /// Whether only wallets with an age attestation can play the game, which is the case for the 18+ game types (3 to 5).
+    pub fn is_adult(&self) -> bool {
+        self.game_type >= 3
+    }
+
+    /// The game type without its 18+ flag: 0 for normal, 1 for king of the hill and 2 for team king of the hill games.
+    pub fn base_game_type(&self) -> u8 {
+        self.game_type % 3
+    }
+
+    /// Whether the current player of `queue` has been at the front of it for longer than the game's maximum session.
+    pub fn session_expired(&self, queue: &GameQueue, now: i64) -> bool {
+        self.max_session_secs != 0 && !queue.is_empty() && now > queue.current_started_at.saturating_add(self.max_session_secs as i64)
+    }
+
+    /// Keeps a play fee out of payouts while the player who paid it is in a queue.
+    fn hold_fee(&mut self, fee: &PlayFee) -> Result<()> {
+        let queued = if fee.mint.is_some() { &mut self.queued_tokens } else { &mut self.queued_fees };
+        *queued = queued.checked_add(fee.amount).ok_or(Errors::PayoutOverflow)?;
+        Ok(())
+    }
+
+    /// Lets payouts have the play fee of a player who is leaving the queues, either after playing or to get a refund.
+    fn release_fee(&mut self, player: &Player) {
+        let queued = if player.fee_mint.is_some() { &mut self.queued_tokens } else { &mut self.queued_fees };
+        *queued = queued.saturating_sub(player.fee_paid);
+    }
+
+    /// The lamports of the game that payouts can not touch: its rent, the fees of queued players and what its collaborators have
+    /// not claimed yet.
+    pub fn reserved_lamports(&self, rent: u64) -> Option<u64> {
+        self.collaborators.iter().try_fold(rent.checked_add(self.queued_fees)?, |reserved, collaborator| reserved.checked_add(collaborator.unclaimed_lamports))
+    }
+
+    /// The tokens in the game's escrow that payouts can not touch: the unclaimed pots, the fees of queued players and what its
+    /// collaborators have not claimed yet.
+    pub fn reserved_tokens(&self) -> Option<u64> {
+        self.collaborators.iter().try_fold(self.escrow_reserved.checked_add(self.queued_tokens)?, |reserved, collaborator| reserved.checked_add(collaborator.unclaimed_tokens))
+    }
+
+    /// Gives the game a strike, hiding it once it has `threshold` strikes (a threshold of 0 never hides it).  Returns whether
+    /// this strike hid the game.
+    pub fn add_strike(&mut self, threshold: u8) -> bool {
+        self.strikes = self.strikes.saturating_add(1);
+        let delisted = !self.hidden && threshold != 0 && self.strikes >= threshold;
+        self.hidden |= delisted;
+        delisted
+    }
+
+    /// Whether any collaborator is still owed lamports or tokens.
+    pub fn owes_collaborators(&self) -> bool {
+        self.collaborators.iter().any(|collaborator| collaborator.unclaimed_lamports > 0 || collaborator.unclaimed_tokens > 0)
+    }
+
+    /// The wallets whose share changes between the game's collaborators and `shares`, including the ones added or removed.
+    pub fn involved_collaborators(&self, shares: &[CollaboratorShare]) -> Vec<Pubkey> {
+        let current_share = |wallet: &Pubkey| self.collaborators.iter().find(|collaborator| collaborator.wallet == *wallet).map(|collaborator| collaborator.share_bps);
+        let proposed_share = |wallet: &Pubkey| shares.iter().find(|share| share.wallet == *wallet).map(|share| share.share_bps);
+
+        let mut involved: Vec<Pubkey> = shares.iter().filter(|share| current_share(&share.wallet) != Some(share.share_bps)).map(|share| share.wallet).collect();
+        involved.extend(self.collaborators.iter().filter(|collaborator| proposed_share(&collaborator.wallet).is_none()).map(|collaborator| collaborator.wallet));
+        involved
+    }
+
+    /// Replaces the game's collaborators with `shares`, keeping what the remaining collaborators are owed.  Collaborators that
+    /// are still owed something can not be removed.
+    fn set_collaborators(&mut self, shares: &[CollaboratorShare]) -> Result<()> {
+        let mut collaborators = Vec::with_capacity(shares.len());
+        for share in shares {
+            let (unclaimed_lamports, unclaimed_tokens) = self.collaborators.iter()
+                .find(|collaborator| collaborator.wallet == share.wallet)
+                .map_or((0, 0), |collaborator| (collaborator.unclaimed_lamports, collaborator.unclaimed_tokens));
+            collaborators.push(Collaborator { wallet: share.wallet, share_bps: share.share_bps, unclaimed_lamports, unclaimed_tokens });
+        }
+
+        let removed_unclaimed = self.collaborators.iter()
+            .filter(|collaborator| !shares.iter().any(|share| share.wallet == collaborator.wallet))
+            .any(|collaborator| collaborator.unclaimed_lamports > 0 || collaborator.unclaimed_tokens > 0);
+        if removed_unclaimed {
+            return Err(Errors::UnclaimedCollaboratorShare.into());
+        }
+
+        self.collaborators = collaborators;
+        Ok(())
+    }
+
+    /// Splits the owner's share of a payout between the collaborators.  Every share is rounded down and the first collaborator
+    /// gets whatever is left over after rounding.
+    fn split_owner_share(&mut self, lamports: u64, tokens: u64) -> Option<()> {
+        let share = |amount: u64, bps: u16| u64::try_from((amount as u128) * (bps as u128) / (BASIS_POINTS as u128)).ok();
+
+        let (mut paid_lamports, mut paid_tokens) = (0u64, 0u64);
+        for collaborator in self.collaborators.iter_mut() {
+            let (lamports_share, tokens_share) = (share(lamports, collaborator.share_bps)?, share(tokens, collaborator.share_bps)?);
+            collaborator.unclaimed_lamports = collaborator.unclaimed_lamports.checked_add(lamports_share)?;
+            collaborator.unclaimed_tokens = collaborator.unclaimed_tokens.checked_add(tokens_share)?;
+            paid_lamports = paid_lamports.checked_add(lamports_share)?;
+            paid_tokens = paid_tokens.checked_add(tokens_share)?;
+        }
+
+        let first = self.collaborators.first_mut()?;
+        first.unclaimed_lamports = first.unclaimed_lamports.checked_add(lamports.checked_sub(paid_lamports)?)?;
+        first.unclaimed_tokens = first.unclaimed_tokens.checked_add(tokens.checked_sub(paid_tokens)?)?;
+        Some(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+/// A wallet's share (in basis points) of the owner's share of a game's payouts.
+/// size(CollaboratorShare) = size(Pubkey) + size(u16) = 32 + 2 = 34 Bytes
+pub struct CollaboratorShare {
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+}
+
+impl CollaboratorShare {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + mem::size_of::<u16>();
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+/// A collaborator of a game and what they are owed from its payouts.
+/// size(Collaborator) = size(Pubkey) + size(u16) + 2 * size(u64) = 32 + 2 + 16 = 50 Bytes
+pub struct Collaborator {
+    pub wallet: Pubkey,
+    pub share_bps: u16, // share (in basis points) of the owner's share of every payout.
+    pub unclaimed_lamports: u64, // lamports held in the game until the collaborator claims them.
+    pub unclaimed_tokens: u64, // tokens held in the game's escrow until the collaborator claims them.
+}
+
+impl Collaborator {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + mem::size_of::<u16>() + (2 * mem::size_of::<u64>());
 }
 
 #[account]
-/// The ArcadeState is the account that points to the most current game uploaded to the arcade.
+/// A proposed list of collaborators for a game, waiting for the approval of the collaborators involved.
 /// 
-/// I will probably end up paying rent for this to make sure it never dissappears, but it should honestly be incredibly cheap because the rent-exempt
-/// minimum for this size is currently 0.00133632, which is about $0.044232.
-pub struct ArcadeState {
-    pub most_recent_game_key: Pubkey, // the key of the most recent game to be added to the arcade.
-    pub most_recent_game_pot_key: Option<Pubkey>, // the key of the most recent game pot
-    pub authority: Pubkey, // the initializer of the arcade's key (aka my key).
+/// Its address is derived from the game, so a game has at most one proposal at a time.
+/// 
+/// size(CollaboratorsProposal) = size(Pubkey) + 4 + MAX_COLLABORATORS * size(CollaboratorShare) + 4 + 2 * MAX_COLLABORATORS * size(Pubkey)
+///                             = 32 + 4 + 170 + 4 + 320 = 530 Bytes
+pub struct CollaboratorsProposal {
+    pub game: Pubkey,
+    pub shares: Vec<CollaboratorShare>, // the proposed collaborators.
+    pub approvals: Vec<Pubkey>, // the involved wallets that approved so far.
 }
 
-impl ArcadeState {
-    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + mem::size_of::<Pubkey>() + mem::size_of::<Option<Pubkey>>();
+impl CollaboratorsProposal {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() +
+                                (4 + MAX_COLLABORATORS * CollaboratorShare::MAX_SIZE) +
+                                (4 + 2 * MAX_COLLABORATORS * mem::size_of::<Pubkey>());
+}
+
+#[account]
+/// The game queue is a game's player queue.  It seems that this would make the game too big, so it gets its own account.
+/// 
+/// Its address is derived from the game and the queue number (1 for the first queue).
+/// 
+/// size (GameQueue) = 3 * size(Pubkey) + size(u128) + size(u64) + size(i64) = 96 + 16 + 8 + 8 = 128 Bytes
+pub struct GameQueue {
+    pub game: Pubkey,
+    pub current_player: Pubkey,
+    pub last_player: Pubkey,
+    pub num_players_in_queue: u128,
+    pub next_sequence: u64, // the sequence number (and address seed) of the next player to join the queue.
+    pub current_started_at: i64, // the unix timestamp the current player's turn (or king of the hill round) started at.
+}
+
+impl GameQueue {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + (2 * mem::size_of::<Pubkey>()) + mem::size_of::<u128>() + mem::size_of::<u64>() +
+                                mem::size_of::<i64>();
+
+    /// An empty queue points back at its game instead of at players.
+    pub fn is_empty(&self) -> bool {
+        self.current_player == self.game
+    }
+
+    /// Empties the queue.
+    fn clear(&mut self) {
+        self.current_player = self.game;
+        self.last_player = self.game;
+        self.num_players_in_queue = 0;
+    }
+
+    /// Moves the queue on from its current player to the player behind them, emptying it when nobody is waiting.
+    fn advance(&mut self, current_player: &Player) {
+        match current_player.next_player {
+            Some(player) => {
+                self.current_player = player;
+                self.num_players_in_queue -= 1;
+            },
+            None => self.clear(),
+        }
+    }
+
+    /// Moves the players waiting behind the current player of `winners_queue` over to this (empty) queue, which leaves the
+    /// winner on their own.
+    fn take_waiting(&mut self, winners_queue: &mut GameQueue, winner: &mut Player) {
+        if let Some(player) = winner.next_player {
+            self.current_player = player;
+            self.last_player = winners_queue.last_player;
+            self.num_players_in_queue = winners_queue.num_players_in_queue - 1;
+
+            winner.next_player = None;
+            winners_queue.last_player = winners_queue.current_player;
+            winners_queue.num_players_in_queue = 1;
+        }
+    }
+}
+
+#[account]
+/// The game pot is a struct created whenever a game is paying out where the funds for a player is stored.
+/// 
+/// Pots of games that take tokens also hold a claim on some of the tokens in the game's escrow.
+/// 
+/// Its address is derived from the winner's wallet and the pot's index in the winner's claim index.
+/// 
+/// Pots that are not cashed out before their claim deadline can be sent back to their game by anybody.
+/// 
+/// size (GamePot) = 3 * size(Pubkey) + 2 * size(u64) + 2 * size(i64) = 3 * 32 + 16 + 16 = 128 Bytes
+pub struct GamePot {
+    pub game: Pubkey,
+    pub arcade: Pubkey, // the arcade of the game, whose profile of the winner records the win.
+    pub winner_wallet: Pubkey,
+    pub index: u64, // the pot's index among the winner's pots.
+    pub token_amount: u64,
+    pub created_at: i64, // the unix timestamp of the payout that created the pot.
+    pub claim_deadline: i64, // the unix timestamp after which the pot can no longer be cashed out (0 for no deadline).
+}
+
+impl GamePot {
+    pub const MAX_SIZE: usize = 3 * mem::size_of::<Pubkey>() + 2 * mem::size_of::<u64>() + 2 * mem::size_of::<i64>();
+
+    /// Whether the winner ran out of time to cash the pot out.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.claim_deadline != 0 && now > self.claim_deadline
+    }
+}
+
+#[account]
+/// Counts the pots a wallet has won, which are found at the addresses derived from the wallet and the indexes below the next
+/// index.
+/// 
+/// Its address is derived from the wallet.
+/// 
+/// size(ClaimIndex) = size(Pubkey) + 2 * size(u64) = 32 + 16 = 48 Bytes
+pub struct ClaimIndex {
+    pub wallet: Pubkey,
+    pub next_index: u64, // the index the wallet's next pot gets.
+    pub unclaimed_pots: u64, // the number of the wallet's pots that have not been cashed out yet.
+}
+
+impl ClaimIndex {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + 2 * mem::size_of::<u64>();
+}
+
+#[account]
+/// The on chain reference for players to represent a player in a game's queue.
+/// 
+/// Its address is derived from the queue and the player's sequence number in that queue.
+/// 
+/// size(Player) = 1 * size(Pubkey) + 3 * size(Option<Pubkey>) + 2 * size(u64) = 32 + 99 + 16 = 147 Bytes
+pub struct Player {
+    pub wallet_key: Pubkey,
+    pub next_player: Option<Pubkey>,
+    pub fee_paid: u64, // the play fee paid to join, refunded if the player leaves before their turn.
+    pub fee_mint: Option<Pubkey>, // the token the fee was paid in, or None for lamports.
+    pub fee_credits: Option<Pubkey>, // the prepaid credits the fee was paid from, which refunds go back to.
+    pub credit_discount: u64, // the credit discount received on the play, taken back off the credits' discounts on a refund.
+}
+
+impl Player {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + (3 * mem::size_of::<Option<Pubkey>>()) + (2 * mem::size_of::<u64>());
+}
+
+#[account]
+/// The treasury of an arcade, which collects the arcade's fees.  The lamports in it are the account's lamports above its rent, and
+/// its tokens are held in token accounts whose owner is the treasury.
+/// 
+/// Its address is derived from the arcade.
+/// 
+/// size(Treasury) = size(Pubkey) = 32 Bytes
+pub struct Treasury {
+    pub arcade: Pubkey,
+}
+
+impl Treasury {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>();
+}
+
+#[account]
+/// The prepaid play credits of a wallet on an arcade.  The credits are the account's lamports above its rent.
+/// 
+/// Its address is derived from the arcade and the wallet.
+/// 
+/// size(PlayerCredits) = 2 * size(Pubkey) + size(Option<Pubkey>) + size(u64) = 64 + 33 + 8 = 105 Bytes
+pub struct PlayerCredits {
+    pub arcade: Pubkey,
+    pub wallet: Pubkey,
+    pub kiosk: Option<Pubkey>, // a key that can spend the credits on plays for the wallet.
+    pub discounts_received: u64, // the discounts received on plays since the last withdrawal, paid back when withdrawing.
+}
+
+impl PlayerCredits {
+    pub const MAX_SIZE: usize = (2 * mem::size_of::<Pubkey>()) + mem::size_of::<Option<Pubkey>>() + mem::size_of::<u64>();
+}
+
+#[account]
+/// A wallet's profile on an arcade, which holds the initials its scores are shown with and its record.
+/// 
+/// Its address is derived from the arcade and the wallet.
+/// 
+/// size(PlayerProfile) = 2 * size(Pubkey) + 4 + 3 * size(char) + 3 * size(u64) = 64 + 16 + 24 = 104 Bytes
+pub struct PlayerProfile {
+    pub arcade: Pubkey,
+    pub wallet: Pubkey,
+    pub initials: String, // the 3 character name registered to this wallet, shown with its scores.
+    pub plays: u64, // the number of times the wallet joined one of the arcade's queues, whether or not it posted a score.
+    pub wins: u64, // the number of pots the wallet cashed out.
+    pub lifetime_winnings: u64, // the lamports the wallet cashed out of pots.
+}
+
+impl PlayerProfile {
+    pub const MAX_SIZE: usize = (2 * mem::size_of::<Pubkey>()) + 4 + (3 * mem::size_of::<char>()) + (3 * mem::size_of::<u64>());
+}
+
+#[account]
+/// The registration of a set of initials on an arcade, which stops two wallets from using the same initials.
+/// 
+/// Its address is derived from the arcade and the initials.
+/// 
+/// size(InitialsClaim) = 2 * size(Pubkey) = 64 Bytes
+pub struct InitialsClaim {
+    pub arcade: Pubkey,
+    pub wallet: Pubkey,
+}
+
+impl InitialsClaim {
+    pub const MAX_SIZE: usize = 2 * mem::size_of::<Pubkey>();
+}
+
+#[account]
+/// Proof that a wallet played a session of a game, issued when its player leaves the current player slot.  Posting a score
+/// uses up one receipt.
+/// 
+/// Its address is derived from the player account it replaced.
+/// 
+/// size(SessionReceipt) = 2 * size(Pubkey) + 2 * size(i64) = 64 + 16 = 80 Bytes
+pub struct SessionReceipt {
+    pub game: Pubkey,
+    pub wallet: Pubkey,
+    pub started_at: i64, // the unix timestamp the session started at.
+    pub ended_at: i64, // the unix timestamp the session ended at.
+}
+
+impl SessionReceipt {
+    pub const MAX_SIZE: usize = (2 * mem::size_of::<Pubkey>()) + (2 * mem::size_of::<i64>());
+}
+
+#[account]
+/// A used score nonce.  The account's address is derived from the game and the nonce, so it can only ever be created once.
+/// 
+/// size(ScoreNonce) = size(Pubkey) + size(u64) = 32 + 8 = 40 Bytes
+pub struct ScoreNonce {
+    pub game: Pubkey,
+    pub nonce: u64,
+}
+
+impl ScoreNonce {
+    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + mem::size_of::<u64>();
+}
+
+#[account]
+/// The Leaderboard organizes the different player's places by score (highest score first).
+/// 
+/// Every game has its own leaderboard whose address is derived from the game's key, and the number of places it keeps
+/// (its depth) is chosen when the game is created.
+/// 
+/// size(Leaderboard) = size(Pubkey) + size(u8) + 4 + depth * size(Place) = 32 + 1 + 4 + depth * 64 Bytes
+pub struct Leaderboard {
+    pub game: Pubkey, // The game this leaderboard belongs to.
+    pub depth: u8, // The maximum number of places on the leaderboard.
+    pub places: Vec<Place>, // The places sorted from first to last.
+}
+
+impl Leaderboard {
+    pub fn size(depth: u8) -> usize {
+        mem::size_of::<Pubkey>() + mem::size_of::<u8>() + 4 + (depth as usize * Place::MAX_SIZE)
+    }
+
+    /// Inserts a place into the leaderboard keeping the places sorted and returns the rank (0 is first place) of the new place.
+    /// 
+    /// A wallet only keeps its best score, so a worse score from a wallet already on the leaderboard is ignored and a better one
+    /// replaces the old place.  Ties keep the older score ahead of the newer one.
+    pub fn insert(&mut self, place: Place) -> Option<usize> {
+        if let Some(existing_rank) = self.places.iter().position(|existing| existing.wallet_key == place.wallet_key) {
+            if self.places[existing_rank].score >= place.score {
+                return None;
+            }
+            self.places.remove(existing_rank);
+        }
+
+        let rank = self.places.iter().position(|existing| existing.score < place.score).unwrap_or(self.places.len());
+        if rank >= self.depth as usize {
+            return None;
+        }
+
+        self.places.insert(rank, place);
+        self.places.truncate(self.depth as usize);
+
+        Some(rank)
+    }
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+/// A place is a player's place on the leaderboard.
+/// size(Place) = 4 + 3*size(char) + size(Pubkey) + size(u128) = 4 + 3*4 + 32 + 16 = 4 + 12 + 32 + 16 = 64 Bytes
+pub struct Place {
+    pub name: String, // 3 character string for traditional arcade scoreboard names.
+    pub wallet_key: Pubkey, // public key of the placeholder to allow the transfer of funds.
+    pub score: u128, // High score achieved by this individual.
+}
+
+impl Place {
+    pub const MAX_SIZE: usize = 4 + (3 * mem::size_of::<char>()) + (mem::size_of::<Pubkey>()) + (mem::size_of::<u128>());
+}
+
+#[derive(Debug, Clone, PartialEq, AnchorSerialize, AnchorDeserialize)]
+/// A payout schedule splits a game's funds between the owner, the ranked places on the leaderboard and the arcade.
+/// 
+/// Every share is in basis points (10,000 is the whole pot) and the shares have to add up to exactly 10,000.
+/// size(PayoutSchedule) = size(u16) + 4 + MAX_PAYOUT_PLACES * size(u16) + size(u16) = 2 + 4 + 20 + 2 = 28 Bytes
+pub struct PayoutSchedule {
+    pub owner_bps: u16, // share paid to the game's owner.
+    pub place_bps: Vec<u16>, // share paid to each ranked place (first place first).
+    pub arcade_fee_bps: u16, // share paid into the arcade's treasury.
+}
+
+impl PayoutSchedule {
+    pub const MAX_SIZE: usize = mem::size_of::<u16>() + (4 + MAX_PAYOUT_PLACES * mem::size_of::<u16>()) + mem::size_of::<u16>();
+
+    pub fn validate(&self) -> Result<()> {
+        let total = self.place_bps.iter().fold(self.owner_bps as u32 + self.arcade_fee_bps as u32, |total, bps| total + *bps as u32);
+        if self.place_bps.len() > MAX_PAYOUT_PLACES || total != BASIS_POINTS as u32 {
+            return Err(Errors::InvalidPayoutSchedule.into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The exact amounts a payout moves out of a game, either lamports out of the game account or tokens out of its escrow.
+pub struct PayoutAmounts {
+    pub pot_amounts: Vec<u64>, // amount added to each pot (first place first).
+    pub owner_amount: u64, // the owner's share, plus the shares of unclaimed places and any rounding dust.
+    pub arcade_fee_amount: u64, // the arcade's share, collected in its treasury.
+}
+
+impl PayoutAmounts {
+    /// Splits everything in `balance` above `reserved` following `schedule`, paying the first `num_pots` places.
+    /// 
+    /// Every share is rounded down, and whatever is left over after rounding is paid to the owner, so exactly `reserved` is left
+    /// behind (the game's rent-exempt minimum for lamports, the unclaimed token pots for tokens).
+    pub fn calculate(balance: u64, reserved: u64, schedule: &PayoutSchedule, num_pots: usize) -> Result<PayoutAmounts> {
+        if num_pots > schedule.place_bps.len() {
+            return Err(Errors::WrongNumberOfPots.into());
+        }
+
+        let distribution = balance.checked_sub(reserved).ok_or(Errors::InsufficientFundsForPayout)?;
+        let share = |bps: u16| u64::try_from((distribution as u128) * (bps as u128) / (BASIS_POINTS as u128)).ok();
+
+        let pot_amounts = schedule.place_bps[..num_pots].iter().map(|bps| share(*bps)).collect::<Option<Vec<u64>>>().ok_or(Errors::PayoutOverflow)?;
+        let arcade_fee_amount = share(schedule.arcade_fee_bps).ok_or(Errors::PayoutOverflow)?;
+
+        let mut paid = arcade_fee_amount;
+        for amount in pot_amounts.iter() {
+            paid = paid.checked_add(*amount).ok_or(Errors::PayoutOverflow)?;
+        }
+        let owner_amount = distribution.checked_sub(paid).ok_or(Errors::PayoutOverflow)?;
+
+        Ok(PayoutAmounts {
+            pot_amounts,
+            owner_amount,
+            arcade_fee_amount,
+        })
+    }
+
+    /// Takes the arcade's fee of `arcade_fee_bps` off the top of everything in `balance` above `reserved` and splits the rest
+    /// following `schedule`.  The arcade's fee is added to the schedule's own arcade fee share, both going to the treasury.
+    pub fn calculate_with_arcade_fee(balance: u64, reserved: u64, schedule: &PayoutSchedule, num_pots: usize, arcade_fee_bps: u16) -> Result<PayoutAmounts> {
+        if arcade_fee_bps > BASIS_POINTS {
+            return Err(Errors::InvalidArcadeFee.into());
+        }
+
+        let distribution = balance.checked_sub(reserved).ok_or(Errors::InsufficientFundsForPayout)?;
+        let arcade_fee = u64::try_from((distribution as u128) * (arcade_fee_bps as u128) / (BASIS_POINTS as u128)).map_err(|_| Errors::PayoutOverflow)?;
+
+        let mut payout = PayoutAmounts::calculate(balance, reserved + arcade_fee, schedule, num_pots)?;
+        payout.arcade_fee_amount = payout.arcade_fee_amount.checked_add(arcade_fee).ok_or(Errors::PayoutOverflow)?;
+        Ok(payout)
+    }
+
+    /// The total amount taken out of the game.
+    pub fn total(&self) -> Option<u64> {
+        self.pot_amounts.iter().try_fold(self.owner_amount.checked_add(self.arcade_fee_amount)?, |total, amount| total.checked_add(*amount))
+    }
+
+    /// The total amount put into pots.
+    pub fn pots_total(&self) -> Option<u64> {
+        self.pot_amounts.iter().try_fold(0u64, |total, amount| total.checked_add(*amount))
+    }
+}
+
+impl Default for PayoutSchedule {
+    /// The original arcade split: half to the owner and 4/7, 2/7 and 1/7 of the other half to the top three players.
+    fn default() -> Self {
+        PayoutSchedule {
+            owner_bps: 5000,
+            place_bps: vec![2858, 1428, 714],
+            arcade_fee_bps: 0,
+        }
+    }
+}
+
+#[event]
+/// This is the event issues whenever a game is added or deleted from an arcade.
+pub struct GameEvent {
+    pub label: String, // label will be 'CREATE' and 'DELETE'.
+    pub game_id: Pubkey, // created game.
+    pub more_recent_game_id: Option<Pubkey>, // Useful for deleting games.
+    pub less_recent_game_id: Option<Pubkey>, // Useful for creating games.
+}
+
+#[event]
+/// This is the event issued whenever a game gets a strike, is hidden, appeals or has its appeal dismissed or strikes cleared.
+pub struct ModerationEvent {
+    pub label: String, // label will be 'STRIKE', 'DELIST', 'APPEAL', 'DISMISS' or 'CLEAR'.
+    pub game_id: Pubkey,
+    pub wallet: Pubkey, // the moderator, or the owner for appeals.
+    pub reason: u8, // the reason code of a strike, 0 for the other steps.
+    pub strikes: u8, // the game's strikes afterwards.
+    pub hidden: bool, // whether the game is hidden afterwards.
+}
+
+#[event]
+/// This is the event issued whenever the number of strikes that hide a game changes.
+pub struct StrikeThresholdEvent {
+    pub arcade_id: Pubkey,
+    pub strike_threshold: u8, // 0 means games are never hidden.
+}
+
+#[event]
+/// This is the event issued whenever a new authority is proposed for an arcade or takes it over.
+pub struct AuthorityEvent {
+    pub label: String, // label will be 'PROPOSE' or 'ACCEPT'.
+    pub arcade_id: Pubkey,
+    pub old_authority: Pubkey, // the authority before this step.
+    pub new_authority: Option<Pubkey>, // the proposed (or accepting) authority, None when a proposal is withdrawn.
+}
+
+#[event]
+/// This is the event issued whenever an admin role is granted or revoked.
+pub struct RoleEvent {
+    pub label: String, // label will be 'GRANT' or 'REVOKE'.
+    pub arcade_id: Pubkey,
+    pub wallet: Pubkey,
+    pub role: Role,
 }
 
-#[account]
-/// A game is the on-chain block that contains all important information about a game.
-/// 
-/// NOTE: All actual game data and game art will be stored on arweave to keep the gas prices down.  The only unintended consequence of this
-/// is that games may not be modified after their upload, however we can delete a game if the person initializing the delete has the same
-/// wallet public key as the owner_wallet.
-/// 
-/// Game Type:
-/// 0 -> Normal
-/// 1 -> King of the Hill
-/// 2 -> Team King of the Hill
-/// 3 -> 18+ Normal
-/// 4 -> 18+ King of the Hill
-/// 5 -> 18+ Team King of the Hill
-pub struct Game {
-    pub title: String,
-    pub web_gl_hash: String,
-    pub game_art_hash: String,
-    pub max_players: u8,
-    pub game_type: u8,
-    pub strikes: u8,
-    pub leaderboard: Leaderboard,
-    pub game_queues: Vec<Pubkey>,
-    pub younger_game_key: Pubkey,
-    pub older_game_key: Pubkey,
-    pub owner_wallet: Pubkey,
+#[event]
+/// This is the event issued whenever the age attester of an arcade is replaced.
+pub struct AgeAttesterEvent {
+    pub arcade_id: Pubkey,
+    pub old_age_attester: Pubkey,
+    pub new_age_attester: Pubkey, // the default key means nobody is trusted to attest ages.
 }
 
-impl Game {
-    pub const MAX_SIZE: usize = (30 * mem::size_of::<char>()) + // size of title
-                                (2 * 256 * mem::size_of::<char>()) + // size of webgl hash and game art hash
-                                (3 * mem::size_of::<u8>()) + // size of max players + game type
-                                (Leaderboard::MAX_SIZE) + // size of leaderboard
-                                (4 + 4 * mem::size_of::<Pubkey>()) + // size of game queues vector
-                                (3 * mem::size_of::<Pubkey>()); // size of younger_game_key older_game_key and owner wallet
+#[event]
+/// This is the event issued whenever a wallet's age is attested or its attestation revoked.
+pub struct AgeAttestationEvent {
+    pub label: String, // label will be 'ATTEST' or 'REVOKE'.
+    pub arcade_id: Pubkey,
+    pub wallet: Pubkey,
+    pub attester: Pubkey,
+    pub expires_at: i64,
 }
 
-#[account]
-/// The game queue is a game's player queue.  It seems that this would make the game too big, so it gets its own account.
-/// 
-/// size (GameQueue) = size(Pubkey) + 2 * size(Option<Pubkey>) = 32 + 66 = 98 Bytes
-pub struct GameQueue {
-    pub game: Pubkey,
-    pub current_player: Pubkey,
-    pub last_player: Pubkey,
-    pub num_players_in_queue: u128,
+#[event]
+/// This is the event issued whenever a new owner is proposed for a game or takes it over.
+pub struct OwnerEvent {
+    pub label: String, // label will be 'PROPOSE' or 'ACCEPT'.
+    pub game_id: Pubkey,
+    pub old_owner: Pubkey, // the owner before this step.
+    pub new_owner: Option<Pubkey>, // the proposed (or accepting) owner, None when a proposal is withdrawn.
 }
 
-impl GameQueue {
-    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + (2 * mem::size_of::<Pubkey>()) + mem::size_of::<u128>();
+#[event]
+/// This is the event issued whenever play credits are bought, spent or withdrawn.
+pub struct CreditsEvent {
+    pub label: String, // label will be 'TOP_UP', 'PLAY', 'REFUND' or 'WITHDRAW'.
+    pub credits_id: Pubkey, // the id of the credits account.
+    pub wallet: Pubkey, // the wallet the credits belong to.
+    pub lamports: u64, // the lamports bought, spent or withdrawn.
+    pub balance: u64, // the credits left afterwards.
 }
 
-#[account]
-/// The game pot is a struct created whenever a game is paying out where the funds for a player is stored.
-/// 
-/// size (GamePot) = 2 * size(Pubkey) + size(Option<Pubkey>) = 2 * 32 + 33 = 97 Bytes
-pub struct GamePot {
-    pub game: Pubkey,
-    pub winner_wallet: Pubkey,
-    pub next_game_pot: Option<Pubkey>
+#[event]
+/// This is the event issued whenever an arcade's credit discount or smallest top up changes.
+pub struct CreditTermsEvent {
+    pub arcade_id: Pubkey,
+    pub credit_discount_bps: u16,
+    pub min_credit_top_up: u64, // in lamports.
 }
 
-impl GamePot {
-    pub const MAX_SIZE: usize = 2 * mem::size_of::<Pubkey>() + mem::size_of::<Option<Pubkey>>();
+#[event]
+/// This is the event issued whenever funds move into or out of an arcade's treasury.
+pub struct TreasuryEvent {
+    pub label: String, // label will be 'PAYOUT_FEE', 'CREDIT_DISCOUNTS', 'EXPIRED_POT' or 'WITHDRAW'.
+    pub arcade_id: Pubkey, // the arcade the treasury belongs to.
+    pub treasury_id: Pubkey, // the id of the treasury.
+    pub lamports: u64, // the lamports moved.
+    pub tokens: u64, // the tokens moved.
+    pub mint: Option<Pubkey>, // the mint of the tokens moved, if any.
 }
 
-#[account]
-/// The on chain reference for players to represent a player in a game's queue.
-/// 
-/// size(Player) = 1 * size(Pubkey) + 1 * size(Option<Pubkey>) = 32 + 33 = 65 Bytes
-pub struct Player {
-    pub wallet_key: Pubkey,
-    pub next_player: Option<Pubkey>,
+#[event]
+/// This is the event issued whenever the share of payouts an arcade collects in its treasury changes.
+pub struct ArcadeFeeEvent {
+    pub arcade_id: Pubkey,
+    pub arcade_fee_bps: u16,
 }
 
-impl Player {
-    pub const MAX_SIZE: usize = mem::size_of::<Pubkey>() + mem::size_of::<Option<Pubkey>>();
+#[event]
+/// This is the event issued whenever a game's collaborators are proposed, approved, applied or the proposal is withdrawn.
+pub struct CollaboratorsEvent {
+    pub label: String, // label will be 'PROPOSE', 'APPROVE', 'APPLY' or 'CANCEL'.
+    pub game_id: Pubkey,
+    pub wallet: Pubkey, // the wallet that signed.
+    pub shares: Vec<CollaboratorShare>, // the proposed collaborators.
 }
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
-/// The Leaderboard organizes the different player's places by score.
-/// size(Leaderboard) = 3 * size(Pace) = 3 * 60 = 180 Bytes
-pub struct Leaderboard {
-    pub first_place: Place, // The person in first place.
-    pub second_place: Place, // The person in second place.
-    pub third_place: Place, // The person in third place.
+#[event]
+/// This is the event issued whenever a collaborator claims what they are owed from a game's payouts.
+pub struct CollaboratorClaimEvent {
+    pub game_id: Pubkey,
+    pub wallet: Pubkey,
+    pub lamports: u64,
+    pub tokens: u64,
 }
 
-impl Leaderboard {
-    pub const MAX_SIZE: usize = (3 * Place::MAX_SIZE);
+#[event]
+/// This is the event issued whenever a wallet claims all of its pots at once.
+pub struct ClaimEvent {
+    pub wallet: Pubkey, // the wallet that won the pots.
+    pub arcade_id: Pubkey, // the arcade the pots were won on.
+    pub pot_keys: Vec<Pubkey>, // the pots claimed.
+    pub lamports: u64, // the lamports paid out, including the pots' rent.
 }
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
-/// A place is a player's place on the leaderboard.
-/// size(Place) = 3*size(char) + size(Pubkey) + size(u128) = 3*4 + 32 + 16 = 12 + 32 + 16 = 60 Bytes
-pub struct Place {
-    pub name: String, // 3 character string for traditional arcade scoreboard names.
-    pub wallet_key: Pubkey, // public key of the placeholder to allow the transfer of funds.
-    pub score: u128, // High score achieved by this individual.
+#[event]
+/// This is the event issued whenever an expired pot is sent back.
+pub struct PotReclaimedEvent {
+    pub pot_id: Pubkey, // the closed pot.
+    pub game_id: Pubkey, // the game the pot was won on.
+    pub winner_wallet: Pubkey, // the wallet that did not cash the pot out in time.
+    pub recipient: Pubkey, // the game, or the arcade's treasury if the game has been deleted.
+    pub lamports: u64, // the lamports sent back, including the pot's rent.
+    pub tokens: u64, // the tokens freed up in the game's escrow.
 }
 
-impl Place {
-    pub const MAX_SIZE: usize = (3 * mem::size_of::<char>()) + (mem::size_of::<Pubkey>()) + (mem::size_of::<u128>());
+#[event]
+/// This is the event issued whenever the time winners have to cash out new pots changes.
+pub struct PotClaimWindowEvent {
+    pub arcade_id: Pubkey,
+    pub pot_claim_window_secs: u32, // 0 means pots never expire.
 }
 
 #[event]
-/// This is the event issues whenever a game is added or deleted from an arcade.
-pub struct GameEvent {
-    pub label: String, // label will be 'CREATE' and 'DELETE'.
-    pub game_id: Pubkey, // created game.
-    pub more_recent_game_id: Option<Pubkey>, // Useful for deleting games.
-    pub less_recent_game_id: Option<Pubkey>, // Useful for creating games.
+/// This is the event issued whenever a wallet registers initials.
+pub struct InitialsEvent {
+    pub arcade_id: Pubkey, // the arcade the initials are registered on.
+    pub wallet: Pubkey, // the wallet the initials are registered to.
+    pub old_initials: Option<String>, // the initials the wallet gave up, or None for a new profile.
+    pub initials: String, // the newly registered initials.
 }
 
 #[event]
 /// This is the event issued whenever a leaderboard is updated
 pub struct LeaderboardEvent {
     pub game_id: Pubkey, // the id of the game whose leaderboard is being updated.
+    pub leaderboard_id: Pubkey, // the id of the leaderboard account.
     pub player_name: String, // player_name will be the 3 character name chosen by the player.
-    pub first_place_name: String, // The 3 character name of the first place player.
-    pub second_place_name: String, // The 3 character name of the second place player.
-    pub third_place_name: String, // The 3 character name of the third place player.
+    pub score: u128, // the submitted score.
+    pub rank: Option<u8>, // the place the score took on the leaderboard (0 is first place) or None if it did not place.
+}
+
+#[event]
+/// This is the event issued whenever the score authority of a game is rotated.
+pub struct ScoreAuthorityEvent {
+    pub game_id: Pubkey,
+    pub old_score_authority: Pubkey,
+    pub new_score_authority: Pubkey,
+}
+
+#[event]
+/// This is the event issued whenever the operator of a game is replaced.
+pub struct OperatorEvent {
+    pub game_id: Pubkey,
+    pub old_operator: Pubkey,
+    pub new_operator: Pubkey,
+}
+
+#[event]
+/// This is the event issued whenever the payout schedule of a game or the default one of an arcade changes.
+pub struct PayoutScheduleEvent {
+    pub account_id: Pubkey, // the game or arcade whose schedule changed.
+    pub payout_schedule: Option<PayoutSchedule>, // None means the game went back to the arcade's default.
+}
+
+#[event]
+/// This is the event issued whenever the price of a game or the default one of an arcade changes.
+pub struct PriceEvent {
+    pub account_id: Pubkey, // the game or arcade whose price changed.
+    pub price: Option<PricePolicy>, // None means the game went back to the arcade's default.
+}
+
+#[event]
+/// This is the event issued whenever the maximum session length of a game changes.
+pub struct MaxSessionEvent {
+    pub game_id: Pubkey,
+    pub max_session_secs: u32, // 0 means players are never skipped.
 }
 
 #[event]
@@ -3039,6 +4746,31 @@ pub struct JoinQueueEvent {
     pub num_players: u128,
 }
 
+#[event]
+/// This is the event issued whenever a player leaves a game queue before their turn.
+pub struct LeaveQueueEvent {
+    pub game_id: Pubkey,
+    pub queue_id: Pubkey,
+    pub player: Pubkey,
+    pub wallet: Pubkey,
+    pub refund: u64, // lamports, or tokens when there is a refund mint.
+    pub refund_mint: Option<Pubkey>,
+    pub num_players: u128,
+}
+
+#[event]
+/// This is the event issued whenever the idle current player of a game queue is skipped.
+pub struct SkipPlayerEvent {
+    pub game_id: Pubkey,
+    pub queue_id: Pubkey,
+    pub skipped_player: Pubkey,
+    pub skipped_wallet: Pubkey,
+    pub new_player: Option<Pubkey>, // the player now at the front of the queue, None if the queue is empty.
+    pub num_players: u128,
+    pub caller: Pubkey,
+    pub reward_lamports: u64,
+}
+
 #[event]
 /// This is the event issued whenever a game queue is advanced.
 pub struct AdvanceQueueEvent {
@@ -3061,12 +4793,12 @@ pub struct FinishQueueEvent {
 pub struct PayoutEvent {
     pub game_name: String,
     pub arcade_id: Pubkey,
-    pub player_one_pot_key: Pubkey,
-    pub player_two_pot_key: Pubkey,
-    pub player_three_pot_key: Pubkey,
-    pub player_one_name: String,
-    pub player_two_name: String,
-    pub player_three_name: String,
+    pub pot_keys: Vec<Pubkey>, // pots in rank order (first place first).
+    pub winner_names: Vec<String>,
+    pub owner_lamports: u64,
+    pub arcade_fee_lamports: u64,
+    pub owner_tokens: u64,
+    pub arcade_fee_tokens: u64,
 }
 
 #[error_code]
@@ -3116,12 +4848,495 @@ pub enum Errors {
     #[msg("Insufficient funds to payout")]
     InsufficientFundsForPayout,
 
-    #[msg("Insufficient funds to payout to pot 1")]
-    InsufficientFundsPotOne,
+    #[msg("A payout needs exactly one new pot for every paid place on the leaderboard (and the token accounts for games with an escrow)")]
+    WrongNumberOfPots,
+
+    #[msg("Only the game's owner can change the game's settings")]
+    NotGameOwner,
+
+    #[msg("Only the game's score authority can post scores to the leaderboard")]
+    UnauthorizedScoreAuthority,
+
+    #[msg("The score must be attested by an ed25519 instruction right before the leaderboard update")]
+    MissingScoreAttestation,
+
+    #[msg("The score attestation does not match the submitted score")]
+    InvalidScoreAttestation,
+
+    #[msg("A leaderboard must keep between 1 and 100 places")]
+    InvalidLeaderboardDepth,
+
+    #[msg("A game must have between 1 and 8 players, and team games an even number of them")]
+    InvalidNumPlayers,
+
+    #[msg("The account is not at the address derived for it")]
+    WrongAccountAddress,
+
+    #[msg("A payout schedule must pay at most 10 places and add up to 10,000 basis points")]
+    InvalidPayoutSchedule,
+
+    #[msg("Only the arcade's authority can do this")]
+    NotArcadeAuthority,
+
+    #[msg("The payout amounts overflowed")]
+    PayoutOverflow,
 
-    #[msg("Insufficient funds to payout to pot 2")]
-    InsufficientFundsPotTwo,
+    #[msg("The price feed given is not the arcade's price feed")]
+    WrongPriceFeed,
 
-    #[msg("Insufficient funds to payout to pot 3")]
-    InsufficientFundsPotThree,
-}
\ No newline at end of file
+    #[msg("The price feed account could not be read")]
+    InvalidPriceFeed,
+
+    #[msg("The price feed does not have a usable price")]
+    InvalidPrice,
+
+    #[msg("The price feed's price is too old")]
+    StalePrice,
+
+    #[msg("The price feed's confidence interval is too wide")]
+    UncertainPrice,
+
+    #[msg("The play fee overflowed when converting it to lamports")]
+    PriceOverflow,
+
+    #[msg("This game is priced in tokens, use the token version of this instruction")]
+    PlayPricedInTokens,
+
+    #[msg("This game is not priced in tokens")]
+    PlayNotPricedInTokens,
+
+    #[msg("This game already has a token escrow")]
+    EscrowAlreadyInitialized,
+
+    #[msg("The escrow given is not the game's escrow or is not in the game's token")]
+    WrongEscrow,
+
+    #[msg("The token program given is not the SPL token program")]
+    WrongTokenProgram,
+
+    #[msg("The account given is not a token account")]
+    InvalidTokenAccount,
+
+    #[msg("This pot holds tokens, use the token version of this instruction")]
+    TokenPot,
+
+    #[msg("The pot does not belong to this game")]
+    WrongGame,
+
+    #[msg("Only the wallet that joined the queue can take its player out of it")]
+    NotPlayerWallet,
+
+    #[msg("The current player of a queue is already playing and can not leave it")]
+    CannotLeaveWhilePlaying,
+
+    #[msg("A refund in tokens needs the game's escrow, the token account to refund to and the token program, and a refund in lamports none of them")]
+    WrongRefundAccounts,
+
+    #[msg("The current player's session has not run out yet")]
+    SessionNotExpired,
+
+    #[msg("Only the game's operator or the players whose turn is ending can move the queues on before a session runs out")]
+    NotQueueAuthority,
+
+    #[msg("Every player whose session ends needs the account of their session receipt")]
+    MissingSessionReceipt,
+
+    #[msg("The session receipt is not one of this wallet's sessions of this game")]
+    WrongSessionReceipt,
+
+    #[msg("Only the wallet the credits belong to or its kiosk can spend them")]
+    NotCreditsSpender,
+
+    #[msg("The credits are not for this game's arcade")]
+    WrongPlayerCredits,
+
+    #[msg("There are not enough credits for this")]
+    InsufficientCredits,
+
+    #[msg("Credits must be bought at least the arcade's minimum top up at a time")]
+    CreditTopUpTooSmall,
+
+    #[msg("The credit discount cannot be more than 10,000 basis points")]
+    InvalidCreditTerms,
+
+    #[msg("These initials are registered to another wallet")]
+    InitialsTaken,
+
+    #[msg("Only the wallet that won the pot can cash it out")]
+    NotPotWinner,
+
+    #[msg("The pot was not won on this arcade")]
+    WrongArcade,
+
+    #[msg("The pot's claim deadline has passed")]
+    PotExpired,
+
+    #[msg("The pot can still be claimed by its winner")]
+    PotNotExpired,
+
+    #[msg("The arcade fee cannot be more than 10,000 basis points")]
+    InvalidArcadeFee,
+
+    #[msg("The treasury does not hold that many lamports above its rent")]
+    InsufficientTreasuryFunds,
+
+    #[msg("The token account is not owned by the arcade's treasury")]
+    WrongTreasuryTokenAccount,
+
+    #[msg("Collaborators need different wallets with non-zero shares adding up to 10,000 basis points, and have to change something")]
+    InvalidCollaborators,
+
+    #[msg("This wallet is not one of the collaborators involved")]
+    NotInvolvedCollaborator,
+
+    #[msg("Not every collaborator involved has approved the change")]
+    MissingCollaboratorApprovals,
+
+    #[msg("A collaborator is still owed part of the game's payouts")]
+    UnclaimedCollaboratorShare,
+
+    #[msg("Claiming tokens needs the game's escrow, the token account to pay to and the token program")]
+    MissingClaimTokenAccounts,
+
+    #[msg("This wallet has not been proposed as the game's new owner")]
+    NotPendingOwner,
+
+    #[msg("This wallet has not been proposed as the arcade's new authority")]
+    NotPendingAuthority,
+
+    #[msg("Only the arcade's authority or a wallet granted the role for this instruction can do this")]
+    NotArcadeAdmin,
+
+    #[msg("This game has been hidden by the arcade's moderators")]
+    GameHidden,
+
+    #[msg("This game has no strikes to appeal")]
+    NothingToAppeal,
+
+    #[msg("This game has no appeal pending")]
+    NoAppealPending,
+
+    #[msg("Game type must be between 0 and 5")]
+    InvalidGameType,
+
+    #[msg("Only the arcade's age attester can do this")]
+    NotAgeAttester,
+
+    #[msg("18+ games need the playing wallet's age attestation")]
+    MissingAgeAttestation,
+
+    #[msg("This age attestation was not issued by the arcade's current age attester")]
+    UntrustedAgeAttestation,
+
+    #[msg("This age attestation has expired")]
+    AgeAttestationExpired,
+
+    #[msg("This game still holds the play fees of queued players")]
+    QueuedFeesOwed,
+
+    #[msg("This game's escrow still holds the play fees of queued players")]
+    QueuedTokensOwed,
+
+    #[msg("This game's escrow still holds token pots that have not been cashed out")]
+    UnclaimedTokenPots,
+
+    #[msg("Initials can only use the letters A to Z, the digits 0 to 9 and spaces")]
+    IllegalInitialsCharacter,
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_rent() -> u64 {
+        Rent::default().minimum_balance(8 + Game::MAX_SIZE)
+    }
+
+    fn schedules() -> Vec<PayoutSchedule> {
+        vec![
+            PayoutSchedule::default(),
+            PayoutSchedule { owner_bps: 6000, place_bps: vec![3000], arcade_fee_bps: 1000 },
+            PayoutSchedule { owner_bps: 0, place_bps: vec![3333, 3333, 3333], arcade_fee_bps: 1 },
+            PayoutSchedule { owner_bps: 10_000, place_bps: vec![], arcade_fee_bps: 0 },
+            PayoutSchedule { owner_bps: 1, place_bps: vec![999; 10], arcade_fee_bps: 9 },
+        ]
+    }
+
+    #[test]
+    fn payout_debits_equal_credits() {
+        for schedule in schedules() {
+            for extra_lamports in [0, 1, 7, 9_999, 10_001, 1_000_000_007, u64::MAX - game_rent()] {
+                for num_pots in 0..=schedule.place_bps.len() {
+                    let game_lamports = game_rent() + extra_lamports;
+                    let payout = PayoutAmounts::calculate(game_lamports, game_rent(), &schedule, num_pots).unwrap();
+
+                    let credits = payout.pot_amounts.iter().map(|lamports| *lamports as u128).sum::<u128>() +
+                                  payout.owner_amount as u128 +
+                                  payout.arcade_fee_amount as u128;
+                    assert_eq!(payout.pot_amounts.len(), num_pots);
+                    assert_eq!(payout.total(), Some(extra_lamports));
+                    assert_eq!(credits, extra_lamports as u128);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn payout_leaves_game_rent_exempt() {
+        for schedule in schedules() {
+            let game_lamports = game_rent() + 123_456_789;
+            let payout = PayoutAmounts::calculate(game_lamports, game_rent(), &schedule, schedule.place_bps.len()).unwrap();
+
+            let remaining_lamports = game_lamports - payout.total().unwrap();
+            assert_eq!(remaining_lamports, game_rent());
+            assert!(Rent::default().is_exempt(remaining_lamports, 8 + Game::MAX_SIZE));
+        }
+    }
+
+    #[test]
+    fn payout_gives_dust_and_unclaimed_places_to_owner() {
+        let schedule = PayoutSchedule::default();
+
+        // 7 lamports split 50% / 28.58% / 14.28% / 7.14% rounds every place down to 2, 0 and 0 lamports.
+        let payout = PayoutAmounts::calculate(game_rent() + 7, game_rent(), &schedule, 3).unwrap();
+        assert_eq!(payout.pot_amounts, vec![2, 0, 0]);
+        assert_eq!(payout.owner_amount, 5);
+
+        // Only first place is held, so the owner also gets second and third place's shares.
+        let payout = PayoutAmounts::calculate(game_rent() + 1_000_000_000, game_rent(), &schedule, 1).unwrap();
+        assert_eq!(payout.pot_amounts, vec![285_800_000]);
+        assert_eq!(payout.owner_amount, 714_200_000);
+        assert_eq!(payout.arcade_fee_amount, 0);
+    }
+
+    #[test]
+    fn payout_takes_arcade_fee_off_the_top() {
+        let schedule = PayoutSchedule::default();
+
+        // A 10% arcade fee takes 100,000,000 of 1,000,000,000 lamports before the owner and the places split the rest.
+        let payout = PayoutAmounts::calculate_with_arcade_fee(game_rent() + 1_000_000_000, game_rent(), &schedule, 3, 1000).unwrap();
+        assert_eq!(payout.arcade_fee_amount, 100_000_000);
+        assert_eq!(payout, PayoutAmounts { arcade_fee_amount: 100_000_000, ..PayoutAmounts::calculate(game_rent() + 900_000_000, game_rent(), &schedule, 3).unwrap() });
+        assert_eq!(payout.total().unwrap(), 1_000_000_000);
+
+        assert_eq!(
+            PayoutAmounts::calculate_with_arcade_fee(game_rent(), game_rent(), &schedule, 3, BASIS_POINTS + 1),
+            Err(Errors::InvalidArcadeFee.into())
+        );
+    }
+
+    #[test]
+    fn payout_rejects_games_below_rent() {
+        let schedule = PayoutSchedule::default();
+
+        assert_eq!(
+            PayoutAmounts::calculate(game_rent() - 1, game_rent(), &schedule, 3),
+            Err(Errors::InsufficientFundsForPayout.into())
+        );
+        assert_eq!(
+            PayoutAmounts::calculate(game_rent(), game_rent(), &schedule, 4),
+            Err(Errors::WrongNumberOfPots.into())
+        );
+    }
+
+    #[test]
+    fn payout_schedule_must_add_up() {
+        for schedule in schedules() {
+            assert!(schedule.validate().is_ok());
+        }
+
+        let short = PayoutSchedule { owner_bps: 5000, place_bps: vec![4000], arcade_fee_bps: 0 };
+        let long = PayoutSchedule { owner_bps: 0, place_bps: vec![10_000 / 11; 11], arcade_fee_bps: 10_000 - 11 * (10_000 / 11) };
+        let over = PayoutSchedule { owner_bps: u16::MAX, place_bps: vec![u16::MAX], arcade_fee_bps: 10_000 };
+        for schedule in [short, long, over] {
+            assert_eq!(schedule.validate(), Err(Errors::InvalidPayoutSchedule.into()));
+        }
+    }
+
+    #[test]
+    fn pads_and_upper_cases_initials() {
+        assert_eq!(pad_initials("nbw").unwrap(), "NBW");
+        assert_eq!(pad_initials("P1").unwrap(), "P1 ");
+        assert_eq!(pad_initials("j").unwrap(), "J  ");
+        for initials in ["", "ABCD"] {
+            assert_eq!(pad_initials(initials), Err(Errors::IllegalName.into()));
+        }
+        for initials in ["é", "NBW\u{200b}", "ΑΒΓ", "A-B"] {
+            assert_eq!(pad_initials(initials), Err(Errors::IllegalInitialsCharacter.into()));
+        }
+    }
+
+    /// A 1 player game with everything else left empty, which the tests below fill in as they need.
+    fn test_game() -> Game {
+        Game {
+            title: String::new(),
+            web_gl_hash: String::new(),
+            game_art_hash: String::new(),
+            max_players: 1,
+            game_type: 0,
+            strikes: 0,
+            game_queues: vec![],
+            younger_game_key: Pubkey::default(),
+            older_game_key: Pubkey::default(),
+            owner_wallet: Pubkey::default(),
+            score_authority: Pubkey::default(),
+            operator: Pubkey::default(),
+            arcade: Pubkey::default(),
+            payout_schedule: None,
+            price: None,
+            escrow: None,
+            escrow_reserved: 0,
+            queued_fees: 0,
+            queued_tokens: 0,
+            max_session_secs: 0,
+            collaborators: vec![],
+            pending_owner: None,
+            hidden: false,
+            appeal_pending: false,
+            first_player_sequence: 0,
+        }
+    }
+
+    #[test]
+    fn collaborator_shares_must_add_up() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert!(validate_collaborator_shares(&[]).is_ok());
+        assert!(validate_collaborator_shares(&[CollaboratorShare { wallet: alice, share_bps: 6000 }, CollaboratorShare { wallet: bob, share_bps: 4000 }]).is_ok());
+
+        let short = vec![CollaboratorShare { wallet: alice, share_bps: 6000 }];
+        let twice = vec![CollaboratorShare { wallet: alice, share_bps: 5000 }, CollaboratorShare { wallet: alice, share_bps: 5000 }];
+        let empty_share = vec![CollaboratorShare { wallet: alice, share_bps: 10_000 }, CollaboratorShare { wallet: bob, share_bps: 0 }];
+        let crowd = (0..=MAX_COLLABORATORS).map(|i| CollaboratorShare { wallet: Pubkey::new_unique(), share_bps: if i == 0 { 5000 } else { 1000 } }).collect::<Vec<_>>();
+        for shares in [short, twice, empty_share, crowd] {
+            assert_eq!(validate_collaborator_shares(&shares), Err(Errors::InvalidCollaborators.into()));
+        }
+    }
+
+    #[test]
+    fn splits_owner_share_between_collaborators() {
+        let (alice, bob, carol) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut game = test_game();
+        game.collaborators = vec![
+            Collaborator { wallet: alice, share_bps: 3333, unclaimed_lamports: 0, unclaimed_tokens: 0 },
+            Collaborator { wallet: bob, share_bps: 6667, unclaimed_lamports: 0, unclaimed_tokens: 0 },
+        ];
+
+        // 100 lamports split 33.33% / 66.67% rounds down to 33 and 66, so the first collaborator also gets the lamport left over.
+        game.split_owner_share(100, 10).unwrap();
+        let owed = game.collaborators.iter().map(|collaborator| (collaborator.unclaimed_lamports, collaborator.unclaimed_tokens)).collect::<Vec<_>>();
+        assert_eq!(owed, vec![(34, 4), (66, 6)]);
+        assert_eq!(game.reserved_lamports(1_000).unwrap(), 1_100);
+        assert_eq!(game.reserved_tokens().unwrap(), 10);
+
+        // Only the wallets whose share changes are involved, and nobody can be removed while they are still owed something.
+        let proposed = vec![CollaboratorShare { wallet: alice, share_bps: 3333 }, CollaboratorShare { wallet: carol, share_bps: 6667 }];
+        assert_eq!(game.involved_collaborators(&proposed), vec![carol, bob]);
+        assert_eq!(game.set_collaborators(&proposed), Err(Errors::UnclaimedCollaboratorShare.into()));
+
+        game.collaborators[1].unclaimed_lamports = 0;
+        game.collaborators[1].unclaimed_tokens = 0;
+        game.set_collaborators(&proposed).unwrap();
+        assert_eq!(game.collaborators[0].unclaimed_lamports, 34);
+        assert_eq!(game.collaborators[1].wallet, carol);
+        assert!(game.owes_collaborators());
+    }
+
+    #[test]
+    fn hides_games_at_the_strike_threshold() {
+        let mut game = test_game();
+
+        assert!(!game.add_strike(2));
+        assert!(game.add_strike(2));
+        assert!(game.hidden);
+
+        // A hidden game is only delisted once, and a threshold of 0 never hides games.
+        assert!(!game.add_strike(2));
+        assert_eq!(game.strikes, 3);
+
+        let mut game = test_game();
+        game.strikes = u8::MAX;
+        assert!(!game.add_strike(0));
+        assert_eq!(game.strikes, u8::MAX);
+        assert!(!game.hidden);
+    }
+
+    #[test]
+    fn age_attestations_count_until_they_expire() {
+        let attester = Pubkey::new_unique();
+        let attestation = AgeAttestation {
+            arcade: Pubkey::new_unique(),
+            wallet: Pubkey::new_unique(),
+            attester,
+            expires_at: 1_000,
+        };
+
+        assert_eq!(attestation.check(&attester, 999), Ok(()));
+        assert_eq!(attestation.check(&attester, 1_000), Err(Errors::AgeAttestationExpired.into()));
+
+        // Attestations of a replaced attester, or of an arcade that trusts nobody, never count.
+        assert_eq!(attestation.check(&Pubkey::new_unique(), 999), Err(Errors::UntrustedAgeAttestation.into()));
+        assert_eq!(attestation.check(&Pubkey::default(), 999), Err(Errors::UntrustedAgeAttestation.into()));
+    }
+
+    #[test]
+    fn adult_games_play_like_their_base_type() {
+        let mut game = test_game();
+        for (game_type, base_game_type, adult) in [(0, 0, false), (2, 2, false), (3, 0, true), (4, 1, true), (5, 2, true)] {
+            game.game_type = game_type;
+            assert_eq!(game.base_game_type(), base_game_type);
+            assert_eq!(game.is_adult(), adult);
+        }
+    }
+
+    fn pyth_price_account(price: i64, conf: u64, expo: i32, publish_time: i64, status: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 240];
+        data[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[4..8].copy_from_slice(&PYTH_VERSION.to_le_bytes());
+        data[8..12].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[PYTH_EXPO_OFFSET..PYTH_EXPO_OFFSET + 4].copy_from_slice(&expo.to_le_bytes());
+        data[PYTH_TIMESTAMP_OFFSET..PYTH_TIMESTAMP_OFFSET + 8].copy_from_slice(&publish_time.to_le_bytes());
+        data[PYTH_AGG_PRICE_OFFSET..PYTH_AGG_PRICE_OFFSET + 8].copy_from_slice(&price.to_le_bytes());
+        data[PYTH_AGG_CONF_OFFSET..PYTH_AGG_CONF_OFFSET + 8].copy_from_slice(&conf.to_le_bytes());
+        data[PYTH_AGG_STATUS_OFFSET..PYTH_AGG_STATUS_OFFSET + 4].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn reads_pyth_price_accounts() {
+        let data = pyth_price_account(2_000_000_000, 1_000_000, -8, 1_700_000_000, PYTH_STATUS_TRADING);
+        assert_eq!(
+            SolPrice::from_pyth(&data),
+            Ok(SolPrice { price: 2_000_000_000, conf: 1_000_000, expo: -8, publish_time: 1_700_000_000 })
+        );
+
+        let halted = pyth_price_account(2_000_000_000, 1_000_000, -8, 1_700_000_000, 0);
+        assert_eq!(SolPrice::from_pyth(&halted), Err(Errors::InvalidPrice.into()));
+        assert_eq!(SolPrice::from_pyth(&data[..200]), Err(Errors::InvalidPriceFeed.into()));
+        assert_eq!(SolPrice::from_pyth(&[0u8; 240]), Err(Errors::InvalidPriceFeed.into()));
+    }
+
+    #[test]
+    fn rejects_stale_and_uncertain_prices() {
+        let sol_price = SolPrice { price: 2_000_000_000, conf: 20_000_000, expo: -8, publish_time: 1_000 };
+
+        assert!(sol_price.check(1_060, 60, 100).is_ok());
+        assert_eq!(sol_price.check(1_061, 60, 100), Err(Errors::StalePrice.into()));
+        assert_eq!(sol_price.check(1_000, 60, 99), Err(Errors::UncertainPrice.into()));
+        assert_eq!(SolPrice { price: 0, ..sol_price }.check(1_000, 60, 100), Err(Errors::InvalidPrice.into()));
+    }
+
+    #[test]
+    fn converts_cents_to_lamports() {
+        // $20.00 a SOL makes $0.25 0.0125 SOL.
+        let sol_price = SolPrice { price: 2_000_000_000, conf: 0, expo: -8, publish_time: 0 };
+        assert_eq!(sol_price.lamports_for_cents(25), Ok(12_500_000));
+        assert_eq!(sol_price.lamports_for_cents(0), Ok(0));
+
+        // $3.00 a SOL does not divide evenly, so the fee is rounded up.
+        let sol_price = SolPrice { price: 3, conf: 0, expo: 0, publish_time: 0 };
+        assert_eq!(sol_price.lamports_for_cents(25), Ok(83_333_334));
+
+        let sol_price = SolPrice { price: 1, conf: 0, expo: -30, publish_time: 0 };
+        assert_eq!(sol_price.lamports_for_cents(25), Err(Errors::PriceOverflow.into()));
+    }
+}
@@ -4,17 +4,32 @@ import { assert } from "chai";
 import { Arcade } from "../target/types/arcade";
 
 const { makeArcade } = require("./functions/makeArcade.js");
-const { makeGame } = require("./functions/makeGame.js");
+const { makeGame, findGameAddress, findLeaderboardAddress } = require("./functions/makeGame.js");
 const { deleteRecentGame } = require("./functions/deleteRecentGame.js");
 const { deleteGame } = require("./functions/deleteGame.js");
-const { updateLeaderboard } = require("./functions/updateLeaderboard.js");
-const { initOnePlayerQueue, initTwoPlayerQueue, initThreePlayerQueue, initFourPlayerQueue } = require("./functions/initQueue.js");
-const { joinOnePlayerQueue, joinTwoPlayerQueue, joinThreePlayerQueue, joinFourPlayerQueue, joinKingOfHillQueue } = require("./functions/joinQueue.js");
-const { advanceOnePlayerQueue, advanceTwoPlayerQueue, advanceTwoPlayerKingOfHillQueue, advanceThreePlayerQueue, advanceThreePlayerKingOfHillQueue,
+const { updateLeaderboard, setScoreAuthority } = require("./functions/updateLeaderboard.js");
+const { initGameQueue, initGameQueueWithCredits, initOnePlayerQueue, initOnePlayerQueueWithTokens, initTwoPlayerQueue, initThreePlayerQueue, initFourPlayerQueue } = require("./functions/initQueue.js");
+const { joinGameQueue, joinGameQueueWithCredits, joinOnePlayerQueue, joinOnePlayerQueueWithTokens, joinTwoPlayerQueue, joinThreePlayerQueue, joinFourPlayerQueue, joinKingOfHillQueue } = require("./functions/joinQueue.js");
+const { setOperator, advanceGameQueue, advanceKingOfHillQueue, advanceOnePlayerQueue, advanceTwoPlayerQueue, advanceTwoPlayerKingOfHillQueue, advanceThreePlayerQueue, advanceThreePlayerKingOfHillQueue,
         advanceFourPlayerQueue, advanceFourPlayerKingOfHillQueue, advanceTeamKingOfHillQueue } = require("./functions/advanceQueue.js");
-const { finishOnePlayerGameQueue, finishTwoPlayerGameQueue, finishTwoPlayerKingOfHillQueue, finishThreePlayerGameQueue,
+const { finishGameQueue, finishOnePlayerGameQueue, finishTwoPlayerGameQueue, finishTwoPlayerKingOfHillQueue, finishThreePlayerGameQueue,
         finishThreePlayerKingOfHillQueue, finishFourPlayerGameQueue, finishFourPlayerKingOfHillQueue, finishTeamKingOfHillQueue } = require("./functions/finishQueue.js");
-const { paybackFunds, cashOutPot, cashOutMostRecentPot, refillGameFunds } = require("./functions/payback.js");
+const { updateManualPriceFeed, setDefaultPrice, setGamePrice } = require("./functions/priceFeed.js");
+const { paybackFunds, setPayoutSchedule, setDefaultPayoutSchedule, cashOutPot, cashOutTokenPot, refillGameFunds, findClaimIndexAddress,
+        fetchUnclaimedPots, claimAll, reclaimExpiredPot, setPotClaimWindow } = require("./functions/payback.js");
+const { createMint, createTokenAccount, tokenBalance, initTokenEscrow } = require("./functions/tokens.js");
+const { fetchLine, leaveQueue } = require("./functions/leaveQueue.js");
+const { setMaxSession, skipIdlePlayer } = require("./functions/skipPlayer.js");
+const { findSessionReceiptAddress } = require("./functions/gameQueues.js");
+const { fetchSessionReceipts, playSession } = require("./functions/sessionReceipts.js");
+const { creditsBalance, openPlayerCredits, topUpCredits, setCreditsKiosk, withdrawCredits } = require("./functions/credits.js");
+const { findPlayerProfileAddress, createPlayerProfile, changeInitials } = require("./functions/profiles.js");
+const { findTreasuryAddress, treasuryBalance, setArcadeFee, withdrawTreasury, withdrawTreasuryTokens } = require("./functions/treasury.js");
+const { proposeCollaborators, approveCollaborators, applyCollaborators, claimCollaboratorShare } = require("./functions/collaborators.js");
+const { proposeOwner, acceptOwner } = require("./functions/gameOwner.js");
+const { roleGrantAccounts, grantRole, revokeRole, proposeAuthority, acceptAuthority } = require("./functions/roles.js");
+const { reportGame, appealGame, dismissAppeal, clearStrikes, setStrikeThreshold } = require("./functions/moderation.js");
+const { setAgeAttester, attestAge, revokeAgeAttestation } = require("./functions/ageAttestation.js");
 
 describe("arcade", () => {
   // Configure the client to use the local cluster.
@@ -55,6 +70,7 @@ describe("arcade", () => {
     assert.equal(game.youngerGameKey.toString(), gameAccount.publicKey.toString());
     assert.equal(game.olderGameKey.toString(), genesisGameAccount.publicKey.toString());
     assert.equal(game.ownerWallet.toString(), provider.wallet.publicKey.toString());
+    assert.equal(game.scoreAuthority.toString(), provider.wallet.publicKey.toString());
     assert.equal(updatedArcade.mostRecentGameKey.toString(), gameAccount.publicKey.toString());
   });
 
@@ -76,6 +92,11 @@ describe("arcade", () => {
 
     const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
     assert.equal(updatedArcade.mostRecentGameKey.toString(), gameAccount2.publicKey.toString());
+
+    // Games are found by their index on the arcade, the genesis game being the first one
+    assert.equal(updatedArcade.gameCount.toNumber(), 3);
+    assert.equal((await findGameAddress(program, arcadeAccount, 0)).toString(), genesisGameAccount.publicKey.toString());
+    assert.equal((await findGameAddress(program, arcadeAccount, 2)).toString(), gameAccount2.publicKey.toString());
   });
 
   it("Deletes the Most Recent Game in the Arcade", async () => {
@@ -118,6 +139,33 @@ describe("arcade", () => {
     assert.equal(updatedLaterGame.youngerGameKey.toString(), gameAccount3.publicKey.toString());
   });
 
+  it("does not delete games that still hold the fees of queued players", async () => {
+    // Create an arcade with 3 games, the middle one with a player queued
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+    const { gameAccount: gameAccount1 } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+    const { gameAccount: gameAccount2 } = await makeGame(program, provider, arcadeAccount, gameAccount1, 1, 0);
+    const { gameAccount: gameAccount3 } = await makeGame(program, provider, arcadeAccount, gameAccount2, 1, 0);
+
+    const { updatedGame: queuedGame } = await initOnePlayerQueue(program, provider, gameAccount2);
+    assert.isAbove(queuedGame.queuedFees.toNumber(), 0);
+
+    let rejected = false;
+    try {
+      await deleteGame(program, provider, gameAccount2, gameAccount3, gameAccount1);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "QueuedFeesOwed");
+    }
+    assert.isTrue(rejected);
+
+    // Once the queue is finished nobody is owed a refund and the game can be deleted
+    const { updatedGame: finishedGame } = await finishGameQueue(program, provider, gameAccount2);
+    assert.equal(finishedGame.queuedFees.toNumber(), 0);
+
+    const { updatedEarlierGame } = await deleteGame(program, provider, gameAccount2, gameAccount3, gameAccount1);
+    assert.equal(updatedEarlierGame.olderGameKey.toString(), gameAccount1.publicKey.toString());
+  });
+
   // 2 ->   ->   ->
   // 1 -> 2 ->   -> 3
   it("performs operations on a 1 player queue", async () => {
@@ -155,6 +203,16 @@ describe("arcade", () => {
     assert.equal(q2.lastPlayer.toString(), playerAccountThree.publicKey.toString());
     assert.equal(q2.numPlayersInQueue.toNumber(), 1);
     assert.equal(endGame.gameQueues[0].toString(), gameQueueAccountTwo.publicKey.toString());
+
+    // The recreated queue does not reuse the addresses of earlier players, whose session receipts are still unspent
+    assert.equal(endGame.firstPlayerSequence.toNumber(), 2);
+    assert.notEqual(playerAccountThree.publicKey.toString(), playerAccountOne.publicKey.toString());
+
+    const { playerAccount: playerAccountFour } = await joinOnePlayerQueue(program, provider, gameAccount, gameQueueAccountTwo, playerAccountThree);
+    const { updatedGameQueue: advancedGameQueue } = await advanceOnePlayerQueue(program, provider, playerAccountThree, gameQueueAccountTwo, gameAccount);
+
+    assert.equal(advancedGameQueue.currentPlayer.toString(), playerAccountFour.publicKey.toString());
+    assert.equal(advancedGameQueue.numPlayersInQueue.toNumber(), 1);
   });
 
   // 5 6 ->     -> 7   ->     ->     ->     ->    -> 
@@ -2675,6 +2733,71 @@ describe("arcade", () => {
     assert.equal(g26.gameQueues[3].toString(), gameAccount.publicKey.toString());
   });
 
+  it("performs operations on a 6 player party game queue", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Games can have at most 8 players and team games need an even number of them
+    for (const [numPlayers, gameType] of [[9, 0], [0, 0], [5, 2]]) {
+      let rejected = false;
+      try {
+        await makeGame(program, provider, arcadeAccount, genesisGameAccount, numPlayers, gameType);
+      } catch (err) {
+        rejected = true;
+        assert.equal(err.error.errorCode.code, "InvalidNumPlayers");
+      }
+      assert.isTrue(rejected);
+    }
+
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 6, 0);
+
+    const { playerAccount: playerAccountOne, gameQueueAccounts } = await initGameQueue(program, provider, gameAccount);
+    assert.equal(gameQueueAccounts.length, 6);
+
+    // The first five players to join fill the empty queues, the sixth lines up behind player one
+    const playerAccounts = [playerAccountOne];
+    for (let i = 0; i < 6; i++) {
+      const { playerAccount } = await joinGameQueue(program, provider, gameAccount);
+      playerAccounts.push(playerAccount);
+    }
+
+    const g0 = await program.account.game.fetch(gameAccount.publicKey);
+    const p10 = await program.account.player.fetch(playerAccountOne.publicKey);
+    const queues0 = await Promise.all(gameQueueAccounts.map((gameQueueAccount) => program.account.gameQueue.fetch(gameQueueAccount.publicKey)));
+
+    // Assert queues are correct
+    assert.deepEqual(g0.gameQueues.map((gameQueue) => gameQueue.toString()), gameQueueAccounts.map((gameQueueAccount) => gameQueueAccount.publicKey.toString()));
+    assert.equal(p10.nextPlayer.toString(), playerAccounts[6].publicKey.toString());
+    for (let i = 0; i < 6; i++) {
+      assert.equal(queues0[i].currentPlayer.toString(), playerAccounts[i].publicKey.toString());
+      assert.equal(queues0[i].numPlayersInQueue.toNumber(), i == 0 ? 2 : 1);
+    }
+    assert.equal(queues0[0].lastPlayer.toString(), playerAccounts[6].publicKey.toString());
+
+    // Everybody plays, only the seventh player is left
+    const { updatedGameQueues: queues1 } = await advanceGameQueue(program, provider, gameAccount);
+
+    assert.equal(queues1[0].currentPlayer.toString(), playerAccounts[6].publicKey.toString());
+    assert.equal(queues1[0].lastPlayer.toString(), playerAccounts[6].publicKey.toString());
+    assert.equal(queues1[0].numPlayersInQueue.toNumber(), 1);
+    for (let i = 1; i < 6; i++) {
+      assert.equal(queues1[i].currentPlayer.toString(), gameAccount.publicKey.toString());
+      assert.equal(queues1[i].lastPlayer.toString(), gameAccount.publicKey.toString());
+      assert.equal(queues1[i].numPlayersInQueue.toNumber(), 0);
+    }
+
+    // The finished players' accounts are closed
+    assert.isNull(await provider.connection.getAccountInfo(playerAccountOne.publicKey));
+
+    const { updatedGame: g2 } = await finishGameQueue(program, provider, gameAccount);
+
+    // Assert queues were torn down correctly
+    for (let i = 0; i < 6; i++) {
+      assert.equal(g2.gameQueues[i].toString(), gameAccount.publicKey.toString());
+      assert.isNull(await provider.connection.getAccountInfo(gameQueueAccounts[i].publicKey));
+    }
+  });
+
   it("pays back funds correctly", async () => {
     // Create an arcade
     const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
@@ -2704,23 +2827,23 @@ describe("arcade", () => {
     const score3 = new anchor.BN(500);
     const playerThreeWallet = new anchor.Wallet(playerThreeAccount);
 
-    const { updatedGame: g0 } = await updateLeaderboard(program, provider, gameAccount, playerName, score, playerOneWallet);
+    const { updatedLeaderboard: l0 } = await updateLeaderboard(program, provider, gameAccount, playerName, score, playerOneWallet);
 
-    assert.equal(g0.leaderboard.firstPlace.name, playerName);
-    assert.equal(g0.leaderboard.firstPlace.walletKey.toString(), playerOneWallet.publicKey.toString());
-    assert.equal(g0.leaderboard.firstPlace.score.toNumber(), score.toNumber());
+    assert.equal(l0.places[0].name, playerName);
+    assert.equal(l0.places[0].walletKey.toString(), playerOneWallet.publicKey.toString());
+    assert.equal(l0.places[0].score.toNumber(), score.toNumber());
 
-    const { updatedGame: g1 } = await updateLeaderboard(program, provider, gameAccount, playerName2, score2, playerTwoWallet);
+    const { updatedLeaderboard: l1 } = await updateLeaderboard(program, provider, gameAccount, playerName2, score2, playerTwoWallet);
 
-    assert.equal(g1.leaderboard.secondPlace.name, playerName2);
-    assert.equal(g1.leaderboard.secondPlace.walletKey.toString(), playerTwoWallet.publicKey.toString());
-    assert.equal(g1.leaderboard.secondPlace.score.toNumber(), score2.toNumber());
+    assert.equal(l1.places[1].name, playerName2);
+    assert.equal(l1.places[1].walletKey.toString(), playerTwoWallet.publicKey.toString());
+    assert.equal(l1.places[1].score.toNumber(), score2.toNumber());
 
-    const { updatedGame: g2 } = await updateLeaderboard(program, provider, gameAccount, playerName3, score3, playerThreeWallet);
+    const { updatedLeaderboard: l2 } = await updateLeaderboard(program, provider, gameAccount, playerName3, score3, playerThreeWallet);
 
-    assert.equal(g2.leaderboard.thirdPlace.name, playerName3);
-    assert.equal(g2.leaderboard.thirdPlace.walletKey.toString(), playerThreeWallet.publicKey.toString());
-    assert.equal(g2.leaderboard.thirdPlace.score.toNumber(), score3.toNumber());
+    assert.equal(l2.places[2].name, playerName3);
+    assert.equal(l2.places[2].walletKey.toString(), playerThreeWallet.publicKey.toString());
+    assert.equal(l2.places[2].score.toNumber(), score3.toNumber());
 
     const gameInfo0 = await program.account.game.getAccountInfo(gameAccount.publicKey);
     await refillGameFunds(program, provider, gameAccount, new anchor.BN(1000000000));
@@ -2736,6 +2859,8 @@ describe("arcade", () => {
     const pot2Info = await program.account.gamePot.getAccountInfo(playerTwoPotAccount.publicKey);
     const pot3Info = await program.account.gamePot.getAccountInfo(playerThreePotAccount.publicKey);
     const gameInfo1 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const potRent = await provider.connection.getMinimumBalanceForRentExemption(pot1Info.data.length);
+    const gameRent = await provider.connection.getMinimumBalanceForRentExemption(gameInfo1.data.length);
 
     assert.equal(pot1.game.toString(), gameAccount.publicKey.toString());
     assert.equal(pot2.game.toString(), gameAccount.publicKey.toString());
@@ -2743,26 +2868,1238 @@ describe("arcade", () => {
     assert.equal(pot1.winnerWallet.toString(), playerOneWallet.publicKey.toString());
     assert.equal(pot2.winnerWallet.toString(), playerTwoWallet.publicKey.toString());
     assert.equal(pot3.winnerWallet.toString(), playerThreeWallet.publicKey.toString());
-    assert.equal(pot1.nextGamePot.toString(), playerTwoPotAccount.publicKey.toString());
-    assert.equal(pot2.nextGamePot.toString(), playerThreePotAccount.publicKey.toString());
-    assert.equal(pot3.nextGamePot, null);
-    assert.equal(pot1Info.lamports, 287335965);
-    assert.equal(pot2Info.lamports, 144478822);
-    assert.equal(pot3Info.lamports, 73050251);
-    assert.equal(gameInfo1.lamports, 18896402);
+    assert.equal(pot1.index.toNumber(), 0);
+    assert.equal(pot2.index.toNumber(), 0);
+    assert.equal(pot3.index.toNumber(), 0);
+    // The default schedule pays 28.58%, 14.28% and 7.14% of the game's funds (the refill and the three paid plays) to the top three.
+    const funds = gameInfo.lamports - gameRent;
+    assert.equal(pot1Info.lamports - potRent, Math.floor(funds * 2858 / 10000));
+    assert.equal(pot2Info.lamports - potRent, Math.floor(funds * 1428 / 10000));
+    assert.equal(pot3Info.lamports - potRent, Math.floor(funds * 714 / 10000));
+    assert.equal(gameInfo1.lamports, gameRent);
+
+    // Pots are cashed out in any order without touching the other pots
+    await cashOutPot(program, playerTwoAccount, playerTwoPotAccount);
+    await cashOutPot(program, playerThreeAccount, playerThreePotAccount);
+    await cashOutPot(program, playerOneAccount, playerOnePotAccount);
+
+    const claimIndex1 = await program.account.claimIndex.fetch(await findClaimIndexAddress(program, playerOneWallet.publicKey));
+    assert.equal(claimIndex1.nextIndex.toNumber(), 1);
+    assert.equal(claimIndex1.unclaimedPots.toNumber(), 0);
+    assert.isNull(await provider.connection.getAccountInfo(playerOnePotAccount.publicKey));
+
+    // Cashing out counts as a win on the winner's profile
+    const profile1 = await program.account.playerProfile.fetch(await findPlayerProfileAddress(program, arcadeAccount.publicKey, playerOneWallet.publicKey));
+    assert.equal(profile1.wins.toNumber(), 1);
+    assert.equal(profile1.lifetimeWinnings.toNumber(), Math.floor(funds * 2858 / 10000));
+    // The wallet played before it had a profile, so that play is not counted
+    assert.equal(profile1.plays.toNumber(), 0);
+  });
+
+  it("pays out with a game's own payout schedule", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Create a 1 player game
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+
+    // Schedules that do not add up to 100% are rejected
+    let rejected = false;
+    try {
+      await setPayoutSchedule(program, provider, gameAccount, { ownerBps: 5000, placeBps: [4000], arcadeFeeBps: 0 });
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "InvalidPayoutSchedule");
+    }
+    assert.isTrue(rejected);
+
+    // Winner takes all of the prize money, the arcade takes a 10% fee
+    const schedule = { ownerBps: 6000, placeBps: [3000], arcadeFeeBps: 1000 };
+    const { updatedGame } = await setPayoutSchedule(program, provider, gameAccount, schedule);
+    assert.equal(updatedGame.payoutSchedule.ownerBps, 6000);
+    assert.deepEqual(updatedGame.payoutSchedule.placeBps, [3000]);
+    assert.equal(updatedGame.payoutSchedule.arcadeFeeBps, 1000);
+
+    const playerOneWallet = anchor.web3.Keypair.generate();
+    const playerTwoWallet = anchor.web3.Keypair.generate();
+    await updateLeaderboard(program, provider, gameAccount, "NBW", new anchor.BN(2048), playerOneWallet);
+    await updateLeaderboard(program, provider, gameAccount, "BSW", new anchor.BN(1000), playerTwoWallet);
+
+    await refillGameFunds(program, provider, gameAccount, new anchor.BN(1000000000));
+    const fundedGameInfo = await program.account.game.getAccountInfo(gameAccount.publicKey);
+
+    // Only first place is paid, so only one pot is created
+    const { potAccounts } = await paybackFunds(program, provider, gameAccount, arcadeAccount, 1);
+
+    const pot = await program.account.gamePot.fetch(potAccounts[0].publicKey);
+    const potInfo = await program.account.gamePot.getAccountInfo(potAccounts[0].publicKey);
+    const gameInfo = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const potRent = await provider.connection.getMinimumBalanceForRentExemption(potInfo.data.length);
+    const gameRent = await provider.connection.getMinimumBalanceForRentExemption(gameInfo.data.length);
+
+    assert.equal(pot.winnerWallet.toString(), playerOneWallet.publicKey.toString());
+    assert.equal(potInfo.lamports - potRent, Math.floor((fundedGameInfo.lamports - gameRent) * 3000 / 10000));
+    assert.equal(gameInfo.lamports, gameRent);
+
+    // Clearing the schedule goes back to the arcade's default
+    const { updatedGame: clearedGame } = await setPayoutSchedule(program, provider, gameAccount, null);
+    assert.equal(clearedGame.payoutSchedule, null);
+
+    const { updatedArcade } = await setDefaultPayoutSchedule(program, provider, arcadeAccount, { ownerBps: 2000, placeBps: [5000, 3000], arcadeFeeBps: 0 });
+    assert.deepEqual(updatedArcade.defaultPayoutSchedule.placeBps, [5000, 3000]);
+
+    // The winner's second pot gets the next index, so both are found from the wallet's claim index
+    await updateLeaderboard(program, provider, gameAccount, "NBW", new anchor.BN(4096), playerOneWallet);
+    await updateLeaderboard(program, provider, gameAccount, "BSW", new anchor.BN(1000), playerTwoWallet);
+    await refillGameFunds(program, provider, gameAccount, new anchor.BN(1000000000));
+    const { potAccounts: secondPotAccounts } = await paybackFunds(program, provider, gameAccount, arcadeAccount, 2);
+
+    const unclaimedPots = await fetchUnclaimedPots(program, playerOneWallet.publicKey);
+    assert.deepEqual(unclaimedPots.map((unclaimedPot) => unclaimedPot.account.index.toNumber()), [0, 1]);
+    assert.equal(unclaimedPots[1].publicKey.toString(), secondPotAccounts[0].publicKey.toString());
+
+    await cashOutPot(program, playerOneWallet, unclaimedPots[0]);
+    assert.equal((await fetchUnclaimedPots(program, playerOneWallet.publicKey)).length, 1);
+    const claimIndex = await program.account.claimIndex.fetch(await findClaimIndexAddress(program, playerOneWallet.publicKey));
+    assert.equal(claimIndex.unclaimedPots.toNumber(), 1);
+  });
+
+  it("only accepts scores attested by the game's score authority", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Create a game whose scores are signed by a separate server key
+    const serverKey = anchor.web3.Keypair.generate();
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0, serverKey.publicKey);
+
+    const playerWallet = anchor.web3.Keypair.generate();
+    const { updatedLeaderboard: l0, nonce } = await updateLeaderboard(program, provider, gameAccount, "NBW", new anchor.BN(2048), playerWallet, serverKey);
+    assert.equal(l0.places[0].walletKey.toString(), playerWallet.publicKey.toString());
+
+    // The same attestation cannot be replayed
+    let rejected = false;
+    try {
+      await updateLeaderboard(program, provider, gameAccount, "NBW", new anchor.BN(2048), playerWallet, serverKey, nonce);
+    } catch (err) {
+      rejected = true;
+      assert.include(err.logs.join("\n"), "already in use");
+    }
+    assert.isTrue(rejected);
+
+    // The provider wallet is not the score authority, so its attestations are rejected
+    rejected = false;
+    try {
+      await updateLeaderboard(program, provider, gameAccount, "BAD", new anchor.BN(4096), playerWallet);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "UnauthorizedScoreAuthority");
+    }
+    assert.isTrue(rejected);
+
+    // Rotate the key and make sure the old key no longer works
+    const rotatedKey = anchor.web3.Keypair.generate();
+    const { updatedGame: g1 } = await setScoreAuthority(program, provider, gameAccount, rotatedKey.publicKey);
+    assert.equal(g1.scoreAuthority.toString(), rotatedKey.publicKey.toString());
+
+    rejected = false;
+    try {
+      await updateLeaderboard(program, provider, gameAccount, "OLD", new anchor.BN(4096), playerWallet, serverKey);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "UnauthorizedScoreAuthority");
+    }
+    assert.isTrue(rejected);
+
+    const { updatedLeaderboard: l1 } = await updateLeaderboard(program, provider, gameAccount, "NEW", new anchor.BN(4096), playerWallet, rotatedKey);
+    assert.equal(l1.places[0].name, "NEW");
+  });
+
+  it("keeps a sorted leaderboard of the configured depth", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Create a game with a 5 place leaderboard
+    const leaderboardDepth = 5;
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0, provider.wallet.publicKey, leaderboardDepth);
+
+    const wallets = [];
+    for (let i = 0; i < 7; i++) {
+      wallets.push(anchor.web3.Keypair.generate());
+    }
+
+    // Post scores out of order so the leaderboard has to sort them
+    const scores = [300, 700, 100, 500, 600, 200, 400];
+    let leaderboard;
+    for (let i = 0; i < wallets.length; i++) {
+      ({ updatedLeaderboard: leaderboard } = await updateLeaderboard(program, provider, gameAccount, "P" + i, new anchor.BN(scores[i]), wallets[i]));
+    }
+
+    assert.equal(leaderboard.depth, leaderboardDepth);
+    assert.deepEqual(leaderboard.places.map((place) => place.score.toNumber()), [700, 600, 500, 400, 300]);
+
+    // A worse score from a wallet on the leaderboard does not replace its best score
+    ({ updatedLeaderboard: leaderboard } = await updateLeaderboard(program, provider, gameAccount, "P1", new anchor.BN(50), wallets[1]));
+    assert.deepEqual(leaderboard.places.map((place) => place.score.toNumber()), [700, 600, 500, 400, 300]);
+
+    // A better score moves the wallet's only place up the leaderboard
+    ({ updatedLeaderboard: leaderboard } = await updateLeaderboard(program, provider, gameAccount, "P0", new anchor.BN(800), wallets[0]));
+    assert.deepEqual(leaderboard.places.map((place) => place.score.toNumber()), [800, 700, 600, 500, 400]);
+    assert.equal(leaderboard.places[0].walletKey.toString(), wallets[0].publicKey.toString());
+  });
+
+  it("charges the play fee at the price feed's SOL price", async () => {
+    // Create an arcade (its price feed says a SOL is worth $20.00)
+    const { arcadeAccount, genesisGameAccount, priceFeedAccount } = await makeArcade(program, provider);
+
+    // Create a 1 player game
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+
+    // $0.25 at $20.00 a SOL is 0.0125 SOL
+    const gameInfo0 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const { playerAccount: playerAccountOne, gameQueueAccount } = await initOnePlayerQueue(program, provider, gameAccount);
+    const gameInfo1 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    assert.equal(gameInfo1.lamports - gameInfo0.lamports, 12500000);
+
+    // A $1.00 play fee at $25.00 a SOL is 0.04 SOL
+    const { updatedArcade } = await setDefaultPrice(program, provider, arcadeAccount, { usdCents: [new anchor.BN(100)] });
+    assert.equal(updatedArcade.defaultPrice.usdCents[0].toNumber(), 100);
+    await updateManualPriceFeed(program, provider, priceFeedAccount, new anchor.BN(2500000000));
+
+    const { playerAccount: playerAccountTwo } = await joinOnePlayerQueue(program, provider, gameAccount, gameQueueAccount, playerAccountOne);
+    const gameInfo2 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    assert.equal(gameInfo2.lamports - gameInfo1.lamports, 40000000);
+
+    // A price give or take 4% is too uncertain to charge with
+    await updateManualPriceFeed(program, provider, priceFeedAccount, new anchor.BN(2500000000), new anchor.BN(100000000));
+
+    let rejected = false;
+    try {
+      await joinOnePlayerQueue(program, provider, gameAccount, gameQueueAccount, playerAccountTwo);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "UncertainPrice");
+    }
+    assert.isTrue(rejected);
+  });
+
+  it("charges each game its own price", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // A free game never charges
+    const { gameAccount: freeGameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0, undefined, undefined, { free: {} });
+
+    const freeInfo0 = await program.account.game.getAccountInfo(freeGameAccount.publicKey);
+    await initOnePlayerQueue(program, provider, freeGameAccount);
+    const freeInfo1 = await program.account.game.getAccountInfo(freeGameAccount.publicKey);
+    assert.equal(freeInfo1.lamports, freeInfo0.lamports);
+
+    // A tournament cabinet charges a fixed 0.1 SOL
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, freeGameAccount, 1, 0, undefined, undefined, { lamports: [new anchor.BN(100000000)] });
+
+    const gameInfo0 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const { playerAccount, gameQueueAccount } = await initOnePlayerQueue(program, provider, gameAccount);
+    const gameInfo1 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    assert.equal(gameInfo1.lamports - gameInfo0.lamports, 100000000);
+
+    // Without its own price the game falls back to the arcade's default
+    const { updatedGame } = await setGamePrice(program, provider, gameAccount, null);
+    assert.equal(updatedGame.price, null);
+    await setDefaultPrice(program, provider, arcadeAccount, { lamports: [new anchor.BN(5000000)] });
+
+    await joinOnePlayerQueue(program, provider, gameAccount, gameQueueAccount, playerAccount);
+    const gameInfo2 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    assert.equal(gameInfo2.lamports - gameInfo1.lamports, 5000000);
+  });
+
+  it("takes plays paid in tokens and pays out from the escrow", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Create a 1 player game that costs 100 arcade tokens a play
+    const { mintAccount } = await createMint(provider);
+    const price = { token: { mint: mintAccount.publicKey, amount: new anchor.BN(100) } };
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0, undefined, undefined, price);
+
+    const { escrowAccount, updatedGame } = await initTokenEscrow(program, provider, gameAccount, mintAccount);
+    assert.equal(updatedGame.escrow.toString(), escrowAccount.publicKey.toString());
+
+    // Pay for two plays
+    const { tokenAccount: payerTokenAccount } = await createTokenAccount(provider, mintAccount, provider.wallet.publicKey, 1000);
+    const { playerAccount, gameQueueAccount } = await initOnePlayerQueueWithTokens(program, provider, gameAccount, payerTokenAccount);
+    const { playerAccount: playerAccountTwo } = await joinOnePlayerQueueWithTokens(program, provider, gameAccount, gameQueueAccount, playerAccount, payerTokenAccount);
+
+    assert.equal(await tokenBalance(provider, payerTokenAccount), 800);
+    assert.equal(await tokenBalance(provider, escrowAccount), 200);
+
+    // Paying in SOL is rejected
+    let rejected = false;
+    try {
+      await joinOnePlayerQueue(program, provider, gameAccount, gameQueueAccount, playerAccountTwo);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "PlayPricedInTokens");
+    }
+    assert.isTrue(rejected);
+
+    // The fees of players still in the queue are held back from payouts until both plays are played
+    const queuedGame = await program.account.game.fetch(gameAccount.publicKey);
+    assert.equal(queuedGame.queuedTokens.toNumber(), 200);
+
+    await advanceGameQueue(program, provider, gameAccount);
+    const { updatedGame: playedGame } = await finishGameQueue(program, provider, gameAccount);
+    assert.equal(playedGame.queuedTokens.toNumber(), 0);
+
+    // Only first place is held (by the wallet that paid both plays), so the owner gets 200 - 28.58% of 200 tokens
+    const winner = provider.wallet.payer;
+    await updateLeaderboard(program, provider, gameAccount, "NBW", new anchor.BN(2048), winner);
+
+    const { tokenAccount: ownerTokenAccount } = await createTokenAccount(provider, mintAccount, provider.wallet.publicKey);
+    const { tokenAccount: treasuryTokenAccount } = await createTokenAccount(provider, mintAccount, await findTreasuryAddress(program, arcadeAccount.publicKey));
+    const { potAccounts } = await paybackFunds(program, provider, gameAccount, arcadeAccount, 1, { escrowAccount, ownerTokenAccount, treasuryTokenAccount });
+
+    const pot = await program.account.gamePot.fetch(potAccounts[0].publicKey);
+    const paidGame = await program.account.game.fetch(gameAccount.publicKey);
+    assert.equal(pot.tokenAmount.toNumber(), 57);
+    assert.equal(paidGame.escrowReserved.toNumber(), 57);
+    assert.equal(await tokenBalance(provider, ownerTokenAccount), 143);
+    assert.equal(await tokenBalance(provider, escrowAccount), 57);
+
+    // The game can not be deleted while the pot has not been cashed out
+    rejected = false;
+    try {
+      await deleteRecentGame(program, provider, gameAccount, arcadeAccount, genesisGameAccount);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "UnclaimedTokenPots");
+    }
+    assert.isTrue(rejected);
+
+    // The winner cashes out their tokens
+    const { tokenAccount: winnerTokenAccount } = await createTokenAccount(provider, mintAccount, winner.publicKey);
+    await cashOutTokenPot(program, winner, potAccounts[0], gameAccount, escrowAccount, winnerTokenAccount);
+
+    const cashedOutGame = await program.account.game.fetch(gameAccount.publicKey);
+    assert.equal(cashedOutGame.escrowReserved.toNumber(), 0);
+    assert.equal(await tokenBalance(provider, winnerTokenAccount), 57);
+    assert.equal(await tokenBalance(provider, escrowAccount), 0);
+  });
+
+  it("lets waiting players leave a queue with a refund", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Create a 1 player game that charges a fixed 0.1 SOL and line up four players
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0, undefined, undefined, { lamports: [new anchor.BN(100000000)] });
+    const { playerAccount: playerAccountOne, gameQueueAccount } = await initOnePlayerQueue(program, provider, gameAccount);
+    const { playerAccount: playerAccountTwo } = await joinGameQueue(program, provider, gameAccount);
+    const { playerAccount: playerAccountThree } = await joinGameQueue(program, provider, gameAccount);
+    const { playerAccount: playerAccountFour } = await joinGameQueue(program, provider, gameAccount);
+
+    const g0 = await program.account.game.fetch(gameAccount.publicKey);
+    assert.equal(g0.queuedFees.toNumber(), 400000000);
+
+    // The current player is already playing
+    let rejected = false;
+    try {
+      await leaveQueue(program, provider, gameAccount, gameQueueAccount, playerAccountOne);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "CannotLeaveWhilePlaying");
+    }
+    assert.isTrue(rejected);
+
+    // Player three leaves from the middle of the line and gets their fee back
+    const gameInfo0 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const { updatedGameQueue: q1, updatedGame: g1 } = await leaveQueue(program, provider, gameAccount, gameQueueAccount, playerAccountThree);
+    const gameInfo1 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+
+    assert.equal(gameInfo0.lamports - gameInfo1.lamports, 100000000);
+    assert.equal(g1.queuedFees.toNumber(), 300000000);
+    assert.equal(q1.numPlayersInQueue.toNumber(), 3);
+    assert.equal(q1.lastPlayer.toString(), playerAccountFour.publicKey.toString());
+    assert.deepEqual((await fetchLine(program, gameQueueAccount)).map((playerKey) => playerKey.toString()),
+      [playerAccountOne, playerAccountTwo, playerAccountFour].map((playerAccount) => playerAccount.publicKey.toString()));
+    assert.isNull(await provider.connection.getAccountInfo(playerAccountThree.publicKey));
+
+    // Player four leaves from the back of the line
+    const { updatedGameQueue: q2 } = await leaveQueue(program, provider, gameAccount, gameQueueAccount, playerAccountFour);
+
+    assert.equal(q2.numPlayersInQueue.toNumber(), 2);
+    assert.equal(q2.lastPlayer.toString(), playerAccountTwo.publicKey.toString());
+    const p2 = await program.account.player.fetch(playerAccountTwo.publicKey);
+    assert.equal(p2.nextPlayer, null);
+
+    // The players who stayed still play in order
+    const { updatedGameQueues: queues3 } = await advanceGameQueue(program, provider, gameAccount);
+    assert.equal(queues3[0].currentPlayer.toString(), playerAccountTwo.publicKey.toString());
+    assert.equal(queues3[0].numPlayersInQueue.toNumber(), 1);
+
+    // Leaving works the same in king of the hill queues
+    const { gameAccount: kothGameAccount } = await makeGame(program, provider, arcadeAccount, gameAccount, 2, 1);
+    const { playerAccount: kingAccount, gameQueueAccountOne } = await initTwoPlayerQueue(program, provider, kothGameAccount);
+    const { playerAccount: challengerAccount } = await joinKingOfHillQueue(program, provider, kothGameAccount, gameQueueAccountOne, kingAccount);
+
+    const { updatedGameQueue: kq } = await leaveQueue(program, provider, kothGameAccount, gameQueueAccountOne, challengerAccount);
+    assert.equal(kq.numPlayersInQueue.toNumber(), 1);
+    assert.equal(kq.lastPlayer.toString(), kingAccount.publicKey.toString());
+    assert.isNull(await provider.connection.getAccountInfo(challengerAccount.publicKey));
+  });
+
+  it("lets anybody skip a player whose session ran out", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Create a 1 player game with two players in line
+    const { gameAccount, game } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+    assert.equal(game.maxSessionSecs, 600);
+
+    const { playerAccount: playerAccountOne, gameQueueAccount, gameQueue } = await initOnePlayerQueue(program, provider, gameAccount);
+    const { playerAccount: playerAccountTwo } = await joinGameQueue(program, provider, gameAccount);
+    assert.isAbove(gameQueue.currentStartedAt.toNumber(), 0);
+
+    // Player one still has time left
+    let rejected = false;
+    try {
+      await skipIdlePlayer(program, provider, gameAccount, gameQueueAccount);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "SessionNotExpired");
+    }
+    assert.isTrue(rejected);
+
+    // Only the owner sets the session length
+    const stranger = anchor.web3.Keypair.generate();
+    rejected = false;
+    try {
+      await program.rpc.setMaxSession(1, { accounts: { gameAccount: gameAccount.publicKey, owner: stranger.publicKey }, signers: [stranger] });
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotGameOwner");
+    }
+    assert.isTrue(rejected);
+
+    const { updatedGame } = await setMaxSession(program, provider, gameAccount, 1);
+    assert.equal(updatedGame.maxSessionSecs, 1);
+
+    // Once the session runs out player one is skipped and player two is up
+    await new Promise((resolve) => setTimeout(resolve, 2500));
+
+    const gameInfo0 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const { skippedPlayer, updatedGameQueue } = await skipIdlePlayer(program, provider, gameAccount, gameQueueAccount);
+    const gameInfo1 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+
+    assert.equal(skippedPlayer.toString(), playerAccountOne.publicKey.toString());
+    assert.equal(updatedGameQueue.currentPlayer.toString(), playerAccountTwo.publicKey.toString());
+    assert.equal(updatedGameQueue.numPlayersInQueue.toNumber(), 1);
+    assert.isAbove(updatedGameQueue.currentStartedAt.toNumber(), gameQueue.currentStartedAt.toNumber());
+    assert.isNull(await provider.connection.getAccountInfo(playerAccountOne.publicKey));
+
+    // The caller's reward comes out of the game and the skipped player's rent goes to their session receipt
+    assert.equal(gameInfo0.lamports - gameInfo1.lamports, 10000);
+    const sessionReceipt = await program.account.sessionReceipt.fetch(await findSessionReceiptAddress(program, playerAccountOne.publicKey));
+    assert.equal(sessionReceipt.wallet.toString(), provider.wallet.publicKey.toString());
+    assert.equal(sessionReceipt.startedAt.toNumber(), gameQueue.currentStartedAt.toNumber());
+  });
+
+  it("only lets the operator and the players whose turn ends move a queue on", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Create a 1 player game run by its own operator key with two players in line
+    const operator = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+    const { gameAccount, game } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0, undefined, undefined, null, operator.publicKey);
+    assert.equal(game.operator.toString(), operator.publicKey.toString());
+
+    await initOnePlayerQueue(program, provider, gameAccount);
+    await joinGameQueue(program, provider, gameAccount);
+
+    // A stranger can not advance somebody else's game
+    let rejected = false;
+    try {
+      await advanceGameQueue(program, provider, gameAccount, stranger);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotQueueAuthority");
+    }
+    assert.isTrue(rejected);
+
+    // The operator can
+    const { updatedGameQueues: [q1] } = await advanceGameQueue(program, provider, gameAccount, operator);
+    assert.equal(q1.numPlayersInQueue.toNumber(), 1);
+
+    // And so can the player whose turn is ending
+    await joinGameQueue(program, provider, gameAccount);
+    const { updatedGameQueues: [q2] } = await advanceGameQueue(program, provider, gameAccount);
+    assert.equal(q2.numPlayersInQueue.toNumber(), 1);
+
+    rejected = false;
+    try {
+      await finishGameQueue(program, provider, gameAccount, stranger);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotQueueAuthority");
+    }
+    assert.isTrue(rejected);
+
+    // The owner hands the game to a new operator
+    const { updatedGame } = await setOperator(program, provider, gameAccount, stranger.publicKey);
+    assert.equal(updatedGame.operator.toString(), stranger.publicKey.toString());
+
+    await finishGameQueue(program, provider, gameAccount, stranger);
+
+    // Strangers can not pick the winner of a king of the hill game either
+    const { gameAccount: kothGameAccount } = await makeGame(program, provider, arcadeAccount, gameAccount, 2, 1, undefined, undefined, null, operator.publicKey);
+    const { playerAccount: kingAccount, gameQueueAccountTwo } = await initTwoPlayerQueue(program, provider, kothGameAccount);
+    await joinKingOfHillQueue(program, provider, kothGameAccount, gameQueueAccountTwo, kingAccount);
+
+    rejected = false;
+    try {
+      await advanceKingOfHillQueue(program, provider, kothGameAccount, 0, stranger);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotQueueAuthority");
+    }
+    assert.isTrue(rejected);
+
+    const { updatedGameQueues: [kq1, kq2] } = await advanceKingOfHillQueue(program, provider, kothGameAccount, 0, operator);
+    assert.equal(kq1.currentPlayer.toString(), kingAccount.publicKey.toString());
+    assert.equal(kq2.currentPlayer.toString(), kothGameAccount.publicKey.toString());
+
+    // A challenger can not crown themselves by waiting for the session to run out
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(anchor.web3.SystemProgram.transfer({
+      fromPubkey: provider.wallet.publicKey,
+      toPubkey: stranger.publicKey,
+      lamports: 100000000,
+    })));
+    await joinKingOfHillQueue(program, provider, kothGameAccount, gameQueueAccountTwo, kingAccount, stranger);
+    await setMaxSession(program, provider, kothGameAccount, 1);
+    await new Promise((resolve) => setTimeout(resolve, 2500));
+
+    rejected = false;
+    try {
+      await advanceKingOfHillQueue(program, provider, kothGameAccount, 1, stranger);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotQueueAuthority");
+    }
+    assert.isTrue(rejected);
+  });
+
+  it("only takes scores for sessions the wallet paid for", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+
+    // Create a 1 player game
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+    const leaderboardAccount = await findLeaderboardAddress(program, gameAccount);
+
+    // A wallet that plays a session gets a receipt for it once its turn is over
+    const playerWallet = anchor.web3.Keypair.generate();
+    const { playerAccount } = await playSession(program, provider, gameAccount, playerWallet);
+
+    const sessionReceiptAddress = await findSessionReceiptAddress(program, playerAccount.publicKey);
+    const sessionReceipt = await program.account.sessionReceipt.fetch(sessionReceiptAddress);
+    assert.equal(sessionReceipt.game.toString(), gameAccount.publicKey.toString());
+    assert.equal(sessionReceipt.wallet.toString(), playerWallet.publicKey.toString());
+    assert.isAtMost(sessionReceipt.startedAt.toNumber(), sessionReceipt.endedAt.toNumber());
+    assert.isNull(await provider.connection.getAccountInfo(playerAccount.publicKey));
+
+    // Somebody else's receipt can not be used for a score
+    const stranger = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(anchor.web3.SystemProgram.transfer({
+      fromPubkey: provider.wallet.publicKey,
+      toPubkey: stranger.publicKey,
+      lamports: 100000000,
+    })));
+    const { playerProfileKey: strangerProfileKey } = await createPlayerProfile(program, provider, arcadeAccount.publicKey, stranger, "BAD");
+    const nonce = new anchor.BN(Math.floor(Math.random() * 1000000000));
+    const [scoreNonceAccount] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("score_nonce"), gameAccount.publicKey.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const score = new anchor.BN(4096);
+    const message = Buffer.concat([gameAccount.publicKey.toBuffer(), stranger.publicKey.toBuffer(), score.toArrayLike(Buffer, "le", 16), nonce.toArrayLike(Buffer, "le", 8)]);
+
+    let rejected = false;
+    try {
+      await program.rpc.updateLeaderboard(score, stranger.publicKey, nonce, {
+        accounts: {
+          gameAccount: gameAccount.publicKey,
+          leaderboardAccount,
+          scoreNonceAccount,
+          sessionReceipt: sessionReceiptAddress,
+          wallet: stranger.publicKey,
+          playerProfile: strangerProfileKey,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          payer: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        },
+        instructions: [anchor.web3.Ed25519Program.createInstructionWithPrivateKey({ privateKey: provider.wallet.payer.secretKey, message })],
+      });
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "WrongSessionReceipt");
+    }
+    assert.isTrue(rejected);
+
+    // The wallet's own score uses its receipt up and gets the wallet the receipt's rent back
+    await createPlayerProfile(program, provider, arcadeAccount.publicKey, playerWallet, "NBW");
+    const receiptInfo = await provider.connection.getAccountInfo(sessionReceiptAddress);
+    const walletBalance0 = await provider.connection.getBalance(playerWallet.publicKey);
+    const { updatedLeaderboard } = await updateLeaderboard(program, provider, gameAccount, "NBW", new anchor.BN(2048), playerWallet);
+    assert.equal(updatedLeaderboard.places[0].walletKey.toString(), playerWallet.publicKey.toString());
+    assert.isNull(await provider.connection.getAccountInfo(sessionReceiptAddress));
+    assert.equal(await provider.connection.getBalance(playerWallet.publicKey) - walletBalance0, receiptInfo.lamports);
+    assert.equal((await fetchSessionReceipts(program, gameAccount, playerWallet.publicKey)).length, 0);
+  });
+
+  it("lets a kiosk spend a wallet's prepaid credits on plays", async () => {
+    // Create an arcade (plays paid from credits are 5% off and credits are bought 1 SOL at a time)
+    const { arcade, arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+    assert.equal(arcade.creditDiscountBps, 500);
+    assert.equal(arcade.minCreditTopUp.toNumber(), 1000000000);
+
+    // Create a 1 player game ($0.25 at $20.00 a SOL is 0.0125 SOL, or 0.011875 SOL from credits)
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+
+    // A wallet opens its credits and buys them in bulk
+    const playerWallet = anchor.web3.Keypair.generate();
+    const kiosk = anchor.web3.Keypair.generate();
+    for (const wallet of [playerWallet, kiosk]) {
+      await provider.sendAndConfirm(new anchor.web3.Transaction().add(anchor.web3.SystemProgram.transfer({
+        fromPubkey: provider.wallet.publicKey,
+        toPubkey: wallet.publicKey,
+        lamports: 100000000,
+      })));
+    }
+    const { playerCreditsKey } = await openPlayerCredits(program, provider, arcadeAccount, playerWallet);
+
+    let rejected = false;
+    try {
+      await topUpCredits(program, provider, arcadeAccount, playerCreditsKey, 999999999);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "CreditTopUpTooSmall");
+    }
+    assert.isTrue(rejected);
+
+    const { balance: balance0 } = await topUpCredits(program, provider, arcadeAccount, playerCreditsKey, 1000000000);
+    assert.equal(balance0, 1000000000);
+
+    // Nobody but the wallet and its kiosk can spend the credits
+    rejected = false;
+    try {
+      await initGameQueueWithCredits(program, provider, gameAccount, playerCreditsKey);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotCreditsSpender");
+    }
+    assert.isTrue(rejected);
+
+    const { playerCredits } = await setCreditsKiosk(program, provider, playerCreditsKey, kiosk.publicKey, playerWallet);
+    assert.equal(playerCredits.kiosk.toString(), kiosk.publicKey.toString());
+
+    // The kiosk plays for the wallet without the wallet signing
+    const gameInfo0 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const { player } = await initGameQueueWithCredits(program, provider, gameAccount, playerCreditsKey, kiosk);
+    const { playerAccount: waitingPlayerAccount } = await joinGameQueueWithCredits(program, provider, gameAccount, playerCreditsKey, kiosk);
+    const gameInfo1 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+
+    assert.equal(player.walletKey.toString(), playerWallet.publicKey.toString());
+    assert.equal(player.feePaid.toNumber(), 11875000);
+    assert.equal(gameInfo1.lamports - gameInfo0.lamports, 2 * 11875000);
+    assert.equal(await creditsBalance(program, provider, playerCreditsKey), 1000000000 - 2 * 11875000);
+    assert.equal((await program.account.playerCredits.fetch(playerCreditsKey)).discountsReceived.toNumber(), 2 * 625000);
+
+    // Leaving the queue refunds a play paid from credits into the credits, which no longer owe its discount
+    const gameQueueAccount = { publicKey: (await program.account.game.fetch(gameAccount.publicKey)).gameQueues[0] };
+    const { updatedGame: leftGame } = await leaveQueue(program, provider, gameAccount, gameQueueAccount, waitingPlayerAccount, null, playerWallet);
+
+    assert.equal(leftGame.queuedFees.toNumber(), 11875000);
+    assert.equal(await creditsBalance(program, provider, playerCreditsKey), 1000000000 - 11875000);
+    assert.equal((await program.account.playerCredits.fetch(playerCreditsKey)).discountsReceived.toNumber(), 625000);
+
+    // Withdrawing pays the discounts back, so the credits can not be cashed out in full
+    rejected = false;
+    try {
+      await withdrawCredits(program, provider, arcadeAccount, playerCreditsKey, 1000000000 - 11875000, playerWallet);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "InsufficientCredits");
+    }
+    assert.isTrue(rejected);
+
+    const walletBalance0 = await provider.connection.getBalance(playerWallet.publicKey);
+    const treasuryBalance0 = await treasuryBalance(program, provider, arcadeAccount);
+    const { balance: balance1 } = await withdrawCredits(program, provider, arcadeAccount, playerCreditsKey, 500000000, playerWallet);
+    const walletBalance1 = await provider.connection.getBalance(playerWallet.publicKey);
+
+    assert.equal(walletBalance1 - walletBalance0, 500000000);
+    assert.equal(await treasuryBalance(program, provider, arcadeAccount) - treasuryBalance0, 625000);
+    assert.equal(balance1, 1000000000 - 11875000 - 500000000 - 625000);
+    assert.equal((await program.account.playerCredits.fetch(playerCreditsKey)).discountsReceived.toNumber(), 0);
+  });
+
+  it("shows scores with the initials registered to the wallet's profile", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
 
-    await cashOutPot(program, playerThreeAccount, playerThreePotAccount, playerTwoPotAccount);
+    // Create a 1 player game
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+
+    const wallets = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+    for (const wallet of wallets) {
+      await provider.sendAndConfirm(new anchor.web3.Transaction().add(anchor.web3.SystemProgram.transfer({
+        fromPubkey: provider.wallet.publicKey,
+        toPubkey: wallet.publicKey,
+        lamports: 100000000,
+      })));
+    }
+
+    // Initials are padded, upper cased and registered to one wallet per arcade
+    const { playerProfile } = await createPlayerProfile(program, provider, arcadeAccount.publicKey, wallets[0], "nb");
+    assert.equal(playerProfile.initials, "NB ");
+    assert.equal(playerProfile.wallet.toString(), wallets[0].publicKey.toString());
+    assert.equal(playerProfile.plays.toNumber(), 0);
+
+    let rejected = false;
+    try {
+      await createPlayerProfile(program, provider, arcadeAccount.publicKey, wallets[1], "NB");
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "InitialsTaken");
+    }
+    assert.isTrue(rejected);
+
+    // Changing initials frees up the old ones for other wallets
+    const { playerProfile: changedProfile } = await changeInitials(program, provider, arcadeAccount.publicKey, wallets[0], "NBW");
+    assert.equal(changedProfile.initials, "NBW");
+    const { playerProfile: otherProfile } = await createPlayerProfile(program, provider, arcadeAccount.publicKey, wallets[1], "NB");
+    assert.equal(otherProfile.initials, "NB ");
+
+    // Scores are posted under the profile's initials, the session played for the score is counted as a play
+    const { updatedLeaderboard } = await updateLeaderboard(program, provider, gameAccount, "NBW", new anchor.BN(2048), wallets[0]);
+    assert.equal(updatedLeaderboard.places[0].name, "NBW");
+
+    const profileKey = await findPlayerProfileAddress(program, arcadeAccount.publicKey, wallets[0].publicKey);
+    assert.equal((await program.account.playerProfile.fetch(profileKey)).plays.toNumber(), 1);
+
+    // Plays that never post a score are counted too
+    await playSession(program, provider, gameAccount, wallets[0]);
+    assert.equal((await program.account.playerProfile.fetch(profileKey)).plays.toNumber(), 2);
+  });
 
-    const pot21 = await program.account.gamePot.fetch(playerTwoPotAccount.publicKey);
+  it("claims all of a wallet's pots in one transaction", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
 
-    assert.equal(pot21.nextGamePot, null);
+    // Create a 1 player game and pay out to the same winner twice
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+    const winner = anchor.web3.Keypair.generate();
+
+    for (const score of [1000, 2000]) {
+      await updateLeaderboard(program, provider, gameAccount, "WIN", new anchor.BN(score), winner);
+      await refillGameFunds(program, provider, gameAccount, new anchor.BN(100000000));
+      await paybackFunds(program, provider, gameAccount, arcadeAccount, 1);
+    }
+
+    const unclaimedPots = await fetchUnclaimedPots(program, winner.publicKey);
+    assert.equal(unclaimedPots.length, 2);
+    const potInfos = await Promise.all(unclaimedPots.map((pot) => provider.connection.getAccountInfo(pot.publicKey)));
+
+    // Nobody else can claim the pots
+    const stranger = anchor.web3.Keypair.generate();
+    let rejected = false;
+    try {
+      await program.rpc.claimAll({
+        accounts: {
+          claimIndex: await findClaimIndexAddress(program, winner.publicKey),
+          playerProfile: await findPlayerProfileAddress(program, arcadeAccount.publicKey, winner.publicKey),
+          winner: stranger.publicKey,
+        },
+        remainingAccounts: unclaimedPots.map((pot) => ({ pubkey: pot.publicKey, isWritable: true, isSigner: false })),
+        signers: [stranger],
+      });
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "ConstraintSeeds");
+    }
+    assert.isTrue(rejected);
+
+    // One call pays out and closes every pot
+    const balance0 = await provider.connection.getBalance(winner.publicKey);
+    await claimAll(program, winner, arcadeAccount);
+    const balance1 = await provider.connection.getBalance(winner.publicKey);
+
+    assert.equal(balance1 - balance0, potInfos[0].lamports + potInfos[1].lamports);
+    assert.equal((await fetchUnclaimedPots(program, winner.publicKey)).length, 0);
+    const claimIndex = await program.account.claimIndex.fetch(await findClaimIndexAddress(program, winner.publicKey));
+    assert.equal(claimIndex.nextIndex.toNumber(), 2);
+    assert.equal(claimIndex.unclaimedPots.toNumber(), 0);
+
+    const profile = await program.account.playerProfile.fetch(await findPlayerProfileAddress(program, arcadeAccount.publicKey, winner.publicKey));
+    assert.equal(profile.wins.toNumber(), 2);
+  });
+
+  it("sends pots nobody claimed in time back to their game", async () => {
+    // Create an arcade (pots can be claimed for 90 days by default)
+    const { arcade, arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+    assert.equal(arcade.potClaimWindowSecs, 90 * 24 * 60 * 60);
+
+    // Create a 1 player game and pay out to a winner who has 2 seconds to claim
+    const { updatedArcade } = await setPotClaimWindow(program, provider, arcadeAccount, 2);
+    assert.equal(updatedArcade.potClaimWindowSecs, 2);
+
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+    const winner = anchor.web3.Keypair.generate();
+    await updateLeaderboard(program, provider, gameAccount, "WIN", new anchor.BN(1000), winner);
+    await refillGameFunds(program, provider, gameAccount, new anchor.BN(100000000));
+    const { potAccounts } = await paybackFunds(program, provider, gameAccount, arcadeAccount, 1);
+
+    const pot = await program.account.gamePot.fetch(potAccounts[0].publicKey);
+    assert.equal(pot.claimDeadline.toNumber(), pot.createdAt.toNumber() + 2);
+
+    // The pot can not be taken back while the winner still has time
+    let rejected = false;
+    try {
+      await reclaimExpiredPot(program, potAccounts[0]);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "PotNotExpired");
+    }
+    assert.isTrue(rejected);
+
+    // Once the deadline passes the winner can no longer claim it, but anybody can send it back to the game
+    await new Promise((resolve) => setTimeout(resolve, 3500));
+
+    rejected = false;
+    try {
+      await cashOutPot(program, winner, potAccounts[0]);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "PotExpired");
+    }
+    assert.isTrue(rejected);
+
+    const potInfo = await provider.connection.getAccountInfo(potAccounts[0].publicKey);
+    const gameInfo0 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    await reclaimExpiredPot(program, potAccounts[0]);
+    const gameInfo1 = await program.account.game.getAccountInfo(gameAccount.publicKey);
+
+    assert.equal(gameInfo1.lamports - gameInfo0.lamports, potInfo.lamports);
+    assert.isNull(await provider.connection.getAccountInfo(potAccounts[0].publicKey));
+    const claimIndex = await program.account.claimIndex.fetch(await findClaimIndexAddress(program, winner.publicKey));
+    assert.equal(claimIndex.unclaimedPots.toNumber(), 0);
+  });
+
+  it("collects the arcade fee in the treasury and lets only the authority withdraw it", async () => {
+    // Create an arcade (which takes no fee by default) and set a 10% fee
+    const { arcade, arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+    assert.equal(arcade.arcadeFeeBps, 0);
+
+    const stranger = anchor.web3.Keypair.generate();
+    let rejected = false;
+    try {
+      await setArcadeFee(program, provider, arcadeAccount, 1000, stranger);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotArcadeAdmin");
+    }
+    assert.isTrue(rejected);
+
+    const { updatedArcade } = await setArcadeFee(program, provider, arcadeAccount, 1000);
+    assert.equal(updatedArcade.arcadeFeeBps, 1000);
+
+    // Pay out a 1 player game, the fee is taken off the top before the owner and the winner split the rest
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+    const winner = anchor.web3.Keypair.generate();
+    await updateLeaderboard(program, provider, gameAccount, "FEE", new anchor.BN(1000), winner);
+    await refillGameFunds(program, provider, gameAccount, new anchor.BN(100000000));
+
+    const gameInfo = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const gameRent = await provider.connection.getMinimumBalanceForRentExemption(gameInfo.data.length);
+    const distribution = gameInfo.lamports - gameRent;
+    const arcadeFee = Math.floor(distribution / 10);
+
+    const treasuryBalance0 = await treasuryBalance(program, provider, arcadeAccount);
+    const { potAccounts } = await paybackFunds(program, provider, gameAccount, arcadeAccount, 1);
+    const treasuryBalance1 = await treasuryBalance(program, provider, arcadeAccount);
+
+    assert.equal(treasuryBalance1 - treasuryBalance0, arcadeFee);
+    const potInfo = await provider.connection.getAccountInfo(potAccounts[0].publicKey);
+    const potRent = await provider.connection.getMinimumBalanceForRentExemption(potInfo.data.length);
+    assert.equal(potInfo.lamports - potRent, Math.floor((distribution - arcadeFee) * 2858 / 10000));
+
+    // Only the arcade's authority can withdraw, and never more than the treasury holds
+    rejected = false;
+    try {
+      await withdrawTreasury(program, provider, arcadeAccount, arcadeFee, stranger.publicKey, stranger);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotArcadeAdmin");
+    }
+    assert.isTrue(rejected);
+
+    rejected = false;
+    try {
+      await withdrawTreasury(program, provider, arcadeAccount, treasuryBalance1 + 1, stranger.publicKey);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "InsufficientTreasuryFunds");
+    }
+    assert.isTrue(rejected);
+
+    const { balance } = await withdrawTreasury(program, provider, arcadeAccount, treasuryBalance1, stranger.publicKey);
+    assert.equal(balance, 0);
+    assert.equal(await provider.connection.getBalance(stranger.publicKey), treasuryBalance1);
+  });
+
+  it("splits the owner's share between collaborators who approved it", async () => {
+    // Create an arcade and a 1 player game built by its owner and a partner
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+
+    const partner = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(anchor.web3.SystemProgram.transfer({
+      fromPubkey: provider.wallet.publicKey,
+      toPubkey: partner.publicKey,
+      lamports: 100000000,
+    })));
+
+    // The owner proposes a 60/40 split, which needs the partner's approval before it applies
+    const { proposal } = await proposeCollaborators(program, provider, gameAccount, [[provider.wallet.publicKey, 6000], [partner.publicKey, 4000]]);
+    assert.equal(proposal.approvals.length, 1);
+
+    let rejected = false;
+    try {
+      await applyCollaborators(program, provider, gameAccount);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "MissingCollaboratorApprovals");
+    }
+    assert.isTrue(rejected);
+
+    await approveCollaborators(program, gameAccount, partner);
+    const { updatedGame } = await applyCollaborators(program, provider, gameAccount);
+    assert.deepEqual(updatedGame.collaborators.map((collaborator) => collaborator.shareBps), [6000, 4000]);
+
+    // A payout keeps the owner's share in the game for the collaborators to claim
+    const winner = anchor.web3.Keypair.generate();
+    await updateLeaderboard(program, provider, gameAccount, "TMS", new anchor.BN(1000), winner);
+    await refillGameFunds(program, provider, gameAccount, new anchor.BN(100000000));
+
+    const gameInfo = await program.account.game.getAccountInfo(gameAccount.publicKey);
+    const gameRent = await provider.connection.getMinimumBalanceForRentExemption(gameInfo.data.length);
+    const { potAccounts } = await paybackFunds(program, provider, gameAccount, arcadeAccount, 1);
+
+    const potInfo = await provider.connection.getAccountInfo(potAccounts[0].publicKey);
+    const potRent = await provider.connection.getMinimumBalanceForRentExemption(potInfo.data.length);
+    const ownerShare = gameInfo.lamports - gameRent - (potInfo.lamports - potRent);
+    const partnerShare = Math.floor(ownerShare * 4000 / 10000);
+
+    const paidGame = await program.account.game.fetch(gameAccount.publicKey);
+    assert.equal(paidGame.collaborators[0].unclaimedLamports.toNumber(), ownerShare - partnerShare);
+    assert.equal(paidGame.collaborators[1].unclaimedLamports.toNumber(), partnerShare);
+
+    // Each collaborator claims their own share
+    const balance0 = await provider.connection.getBalance(partner.publicKey);
+    await claimCollaboratorShare(program, gameAccount, partner);
+    const balance1 = await provider.connection.getBalance(partner.publicKey);
+    assert.equal(balance1 - balance0, partnerShare);
+
+    const claimedGame = await program.account.game.fetch(gameAccount.publicKey);
+    assert.equal(claimedGame.collaborators[1].unclaimedLamports.toNumber(), 0);
+    assert.equal(claimedGame.collaborators[0].unclaimedLamports.toNumber(), ownerShare - partnerShare);
+  });
+
+  it("hands a game over to a new owner once they accept", async () => {
+    // Create an arcade and a 1 player game
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+
+    const newOwner = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+
+    // Only the owner can propose, and only the proposed wallet can accept
+    let rejected = false;
+    try {
+      await proposeOwner(program, provider, gameAccount, stranger.publicKey, stranger);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotGameOwner");
+    }
+    assert.isTrue(rejected);
+
+    const { updatedGame: proposedGame } = await proposeOwner(program, provider, gameAccount, newOwner.publicKey);
+    assert.equal(proposedGame.pendingOwner.toString(), newOwner.publicKey.toString());
+    assert.equal(proposedGame.ownerWallet.toString(), provider.wallet.publicKey.toString());
+
+    rejected = false;
+    try {
+      await acceptOwner(program, gameAccount, stranger);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotPendingOwner");
+    }
+    assert.isTrue(rejected);
+
+    const { updatedGame } = await acceptOwner(program, gameAccount, newOwner);
+    assert.equal(updatedGame.ownerWallet.toString(), newOwner.publicKey.toString());
+    assert.isNull(updatedGame.pendingOwner);
+
+    // The old owner lost control of the game's settings, which the new owner now has
+    rejected = false;
+    try {
+      await setMaxSession(program, provider, gameAccount, 60);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotGameOwner");
+    }
+    assert.isTrue(rejected);
+
+    await program.rpc.setMaxSession(60, {
+      accounts: {
+        gameAccount: gameAccount.publicKey,
+        owner: newOwner.publicKey,
+      },
+      signers: [newOwner],
+    });
+    assert.equal((await program.account.game.fetch(gameAccount.publicKey)).maxSessionSecs, 60);
+  });
+
+  it("lets role holders run only their role's admin instructions and hands the arcade over in two steps", async () => {
+    // Create an arcade and give a hot wallet the treasurer role
+    const { arcadeAccount } = await makeArcade(program, provider);
+    const treasurer = anchor.web3.Keypair.generate();
+    await grantRole(program, provider, arcadeAccount, treasurer.publicKey, "treasurer");
+    const treasurerGrant = await roleGrantAccounts(program, arcadeAccount, treasurer.publicKey, "treasurer");
+
+    // The treasurer can set the arcade's fee, but only with its grant
+    let rejected = false;
+    try {
+      await setArcadeFee(program, provider, arcadeAccount, 500, treasurer);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotArcadeAdmin");
+    }
+    assert.isTrue(rejected);
+
+    await program.rpc.setArcadeFee(500, {
+      accounts: { arcadeAccount: arcadeAccount.publicKey, authority: treasurer.publicKey },
+      remainingAccounts: treasurerGrant,
+      signers: [treasurer],
+    });
+    assert.equal((await program.account.arcadeState.fetch(arcadeAccount.publicKey)).arcadeFeeBps, 500);
+
+    // The treasurer's grant does not let it do a curator's job
+    rejected = false;
+    try {
+      await program.rpc.setDefaultPrice({ free: {} }, {
+        accounts: { arcadeAccount: arcadeAccount.publicKey, authority: treasurer.publicKey },
+        remainingAccounts: treasurerGrant,
+        signers: [treasurer],
+      });
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotArcadeAdmin");
+    }
+    assert.isTrue(rejected);
+
+    // Once revoked the grant is gone
+    await revokeRole(program, provider, arcadeAccount, treasurer.publicKey, "treasurer");
+    rejected = false;
+    try {
+      await program.rpc.setArcadeFee(0, {
+        accounts: { arcadeAccount: arcadeAccount.publicKey, authority: treasurer.publicKey },
+        remainingAccounts: treasurerGrant,
+        signers: [treasurer],
+      });
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotArcadeAdmin");
+    }
+    assert.isTrue(rejected);
+
+    // The authority hands the arcade over, and loses control once the new authority accepts
+    const newAuthority = anchor.web3.Keypair.generate();
+    const { updatedArcade: proposedArcade } = await proposeAuthority(program, provider, arcadeAccount, newAuthority.publicKey);
+    assert.equal(proposedArcade.pendingAuthority.toString(), newAuthority.publicKey.toString());
+
+    rejected = false;
+    try {
+      await acceptAuthority(program, arcadeAccount, treasurer);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotPendingAuthority");
+    }
+    assert.isTrue(rejected);
+
+    const { updatedArcade } = await acceptAuthority(program, arcadeAccount, newAuthority);
+    assert.equal(updatedArcade.authority.toString(), newAuthority.publicKey.toString());
+    assert.isNull(updatedArcade.pendingAuthority);
+
+    rejected = false;
+    try {
+      await setArcadeFee(program, provider, arcadeAccount, 0);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotArcadeAdmin");
+    }
+    assert.isTrue(rejected);
+
+    const { updatedArcade: feeArcade } = await setArcadeFee(program, provider, arcadeAccount, 0, newAuthority);
+    assert.equal(feeArcade.arcadeFeeBps, 0);
+  });
+
+  it("hides games that reach the strike threshold until a moderator clears them", async () => {
+    // Create an arcade that hides games at 2 strikes, a moderator and a 1 player game
+    const { arcade, arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+    assert.equal(arcade.strikeThreshold, 3);
+    const { updatedArcade } = await setStrikeThreshold(program, provider, arcadeAccount, 2);
+    assert.equal(updatedArcade.strikeThreshold, 2);
+
+    const moderator = anchor.web3.Keypair.generate();
+    await grantRole(program, provider, arcadeAccount, moderator.publicKey, "moderator");
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 0);
+
+    // Wallets without the moderator role can not give strikes
+    const stranger = anchor.web3.Keypair.generate();
+    let rejected = false;
+    try {
+      await program.rpc.reportGame(1, {
+        accounts: { gameAccount: gameAccount.publicKey, arcadeAccount: arcadeAccount.publicKey, moderator: stranger.publicKey },
+        signers: [stranger],
+      });
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotArcadeAdmin");
+    }
+    assert.isTrue(rejected);
+
+    // The second strike hides the game, which then takes no plays
+    const { updatedGame: struckGame } = await reportGame(program, provider, gameAccount, 1, moderator);
+    assert.equal(struckGame.strikes, 1);
+    assert.isFalse(struckGame.hidden);
+
+    const { updatedGame: hiddenGame } = await reportGame(program, provider, gameAccount, 4, moderator);
+    assert.equal(hiddenGame.strikes, 2);
+    assert.isTrue(hiddenGame.hidden);
+
+    rejected = false;
+    try {
+      await initOnePlayerQueue(program, provider, gameAccount);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "GameHidden");
+    }
+    assert.isTrue(rejected);
+
+    // The owner appeals, a dismissed appeal leaves the game hidden
+    const { updatedGame: appealedGame } = await appealGame(program, provider, gameAccount);
+    assert.isTrue(appealedGame.appealPending);
+    const { updatedGame: dismissedGame } = await dismissAppeal(program, provider, gameAccount, moderator);
+    assert.isFalse(dismissedGame.appealPending);
+    assert.isTrue(dismissedGame.hidden);
+
+    // Clearing the strikes lists the game again
+    await appealGame(program, provider, gameAccount);
+    const { updatedGame: clearedGame } = await clearStrikes(program, provider, gameAccount, moderator);
+    assert.equal(clearedGame.strikes, 0);
+    assert.isFalse(clearedGame.hidden);
+    assert.isFalse(clearedGame.appealPending);
+
+    const { updatedGame } = await initOnePlayerQueue(program, provider, gameAccount);
+    assert.equal(updatedGame.gameQueues.length, 1);
+  });
+
+  it("only lets wallets with an unexpired age attestation play 18+ games", async () => {
+    // Create an arcade with an age attester and an 18+ 1 player game
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
+    const attester = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(anchor.web3.SystemProgram.transfer({
+      fromPubkey: provider.wallet.publicKey,
+      toPubkey: attester.publicKey,
+      lamports: 100000000,
+    })));
+    const { updatedArcade } = await setAgeAttester(program, provider, arcadeAccount, attester.publicKey);
+    assert.equal(updatedArcade.ageAttester.toString(), attester.publicKey.toString());
+
+    const { gameAccount } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 1, 3);
+
+    // Wallets without an attestation can not play
+    let rejected = false;
+    try {
+      await initOnePlayerQueue(program, provider, gameAccount);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "MissingAgeAttestation");
+    }
+    assert.isTrue(rejected);
+
+    // Only the attester can attest ages, and attestations have to expire in the future
+    const now = Math.floor(Date.now() / 1000);
+    rejected = false;
+    try {
+      await attestAge(program, arcadeAccount, provider.wallet.publicKey, now + 3600, anchor.web3.Keypair.generate());
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "NotAgeAttester");
+    }
+    assert.isTrue(rejected);
+
+    rejected = false;
+    try {
+      await attestAge(program, arcadeAccount, provider.wallet.publicKey, now - 60, attester);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "AgeAttestationExpired");
+    }
+    assert.isTrue(rejected);
+
+    // Once attested the wallet can play
+    const { ageAttestation } = await attestAge(program, arcadeAccount, provider.wallet.publicKey, now + 3600, attester);
+    assert.equal(ageAttestation.wallet.toString(), provider.wallet.publicKey.toString());
+    assert.equal(ageAttestation.expiresAt.toNumber(), now + 3600);
+
+    const { updatedGame } = await initOnePlayerQueue(program, provider, gameAccount);
+    assert.equal(updatedGame.gameQueues.length, 1);
+
+    // A revoked attestation no longer lets the wallet play
+    await revokeAgeAttestation(program, arcadeAccount, provider.wallet.publicKey, attester);
+
+    rejected = false;
+    try {
+      await joinGameQueue(program, provider, gameAccount);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "MissingAgeAttestation");
+    }
+    assert.isTrue(rejected);
+  });
+
+  it("only lets players join a king of the hill game through one of its own queues", async () => {
+    // Create an arcade
+    const { arcadeAccount, genesisGameAccount } = await makeArcade(program, provider);
 
-    await cashOutPot(program, playerTwoAccount, playerTwoPotAccount, playerOnePotAccount);
+    // Create two king of the hill games, only the first of which has its queues initialized
+    const { gameAccount: gameAccountOne } = await makeGame(program, provider, arcadeAccount, genesisGameAccount, 2, 1);
+    const { gameAccount: gameAccountTwo } = await makeGame(program, provider, arcadeAccount, gameAccountOne, 2, 1);
 
-    const pot12 = await program.account.gamePot.fetch(playerOnePotAccount.publicKey);
+    const { playerAccount, gameQueueAccountOne } = await initTwoPlayerQueue(program, provider, gameAccountOne);
 
-    assert.equal(pot12.nextGamePot, null);
+    let rejected = false;
+    try {
+      await joinKingOfHillQueue(program, provider, gameAccountTwo, gameQueueAccountOne, playerAccount);
+    } catch (err) {
+      rejected = true;
+      assert.equal(err.error.errorCode.code, "CannotAdvanceGameQueueWrongGameQueue");
+    }
+    assert.isTrue(rejected);
 
-    await cashOutMostRecentPot(program, playerOneAccount, arcadeAccount, playerOnePotAccount);
+    // The game's own queue takes the player
+    const { player } = await joinKingOfHillQueue(program, provider, gameAccountOne, gameQueueAccountOne, playerAccount);
+    assert.equal(player.walletKey.toString(), provider.wallet.publicKey.toString());
   });
 });
@@ -1,181 +1,124 @@
 const anchor = require("@project-serum/anchor");
 
 const { SystemProgram } = anchor.web3;
+const { fetchGameQueues, queueAndPlayerAccounts } = require("./gameQueues.js");
 
-async function advanceOnePlayerQueue(program, provider, currentPlayerAccount, gameQueueAccount, gameAccount) {
-	await program.rpc.advanceOnePlayerGameQueue({
+async function fetchUpdatedGameQueues(program, gameAccount) {
+	const gameQueues = await fetchGameQueues(program, gameAccount);
+
+	return gameQueues.map((gameQueue) => gameQueue.account);
+}
+
+async function advanceGameQueue(program, provider, gameAccount, authority = null) {
+	await program.rpc.advanceGameQueue({
 		accounts: {
-			currentPlayer: currentPlayerAccount.publicKey,
-			gameQueueAccount: gameQueueAccount.publicKey,
 			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
+			authority: authority ? authority.publicKey : provider.wallet.publicKey,
 			systemProgram: SystemProgram.programId,
 		},
+		remainingAccounts: await queueAndPlayerAccounts(program, gameAccount),
+		signers: authority ? [authority] : [],
 	});
 
-	const updatedGameQueue = await program.account.gameQueue.fetch(gameQueueAccount.publicKey);
-
-	return { updatedGameQueue };
+	return { updatedGameQueues: await fetchUpdatedGameQueues(program, gameAccount) };
 }
 
-async function advanceTwoPlayerQueue(program, provider, playerOneAccount, playerTwoAccount, gameQueueAccountOne, gameQueueAccountTwo, gameAccount) {
-	await program.rpc.advanceTwoPlayerGameQueue({
+// Advances a king of the hill game, the winner is given by (the index of) the queue they play from.
+async function advanceKingOfHillQueue(program, provider, gameAccount, winningQueue, authority = null) {
+	const game = await program.account.game.fetch(gameAccount.publicKey);
+	// 18+ games (types 3 to 5) are played like their base type.
+	const teamSize = game.gameType % 3 == 2 ? 2 : 1;
+	const isLoser = (queueIndex) => Math.floor(queueIndex / teamSize) != Math.floor(winningQueue / teamSize);
+
+	await program.rpc.advanceKingOfHillQueue(winningQueue, {
 		accounts: {
-			playerOne: playerOneAccount.publicKey,
-			playerTwo: playerTwoAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
 			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
+			authority: authority ? authority.publicKey : provider.wallet.publicKey,
 			systemProgram: SystemProgram.programId,
 		},
+		remainingAccounts: await queueAndPlayerAccounts(program, gameAccount, isLoser),
+		signers: authority ? [authority] : [],
 	});
 
-	const updatedGameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const updatedGameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-
-	return { updatedGameQueueOne, updatedGameQueueTwo };
+	return { updatedGameQueues: await fetchUpdatedGameQueues(program, gameAccount) };
 }
 
-async function advanceTwoPlayerKingOfHillQueue(program, provider, winningPlayerAccount, losingPlayerAccount, gameQueueAccountOne, gameQueueAccountTwo, gameAccount) {
-	await program.rpc.advanceTwoPlayerKingOfHillQueue({
+async function setOperator(program, provider, gameAccount, operator) {
+	await program.rpc.setOperator(operator, {
 		accounts: {
-			winningPlayer: winningPlayerAccount.publicKey,
-			losingPlayer: losingPlayerAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
 			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
+			owner: provider.wallet.publicKey,
 		},
 	});
 
-	const updatedGameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const updatedGameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+// The index of the queue the winning player is currently playing from.
+async function winningQueueOf(program, gameAccount, winningPlayerAccount) {
+	const gameQueues = await fetchGameQueues(program, gameAccount);
+
+	return gameQueues.findIndex((gameQueue) => gameQueue.account.currentPlayer.equals(winningPlayerAccount.publicKey));
+}
+
+async function advanceOnePlayerQueue(program, provider, currentPlayerAccount, gameQueueAccount, gameAccount) {
+	const { updatedGameQueues: [updatedGameQueue] } = await advanceGameQueue(program, provider, gameAccount);
+
+	return { updatedGameQueue };
+}
+
+async function advanceTwoPlayerQueue(program, provider, playerOneAccount, playerTwoAccount, gameQueueAccountOne, gameQueueAccountTwo, gameAccount) {
+	const { updatedGameQueues: [updatedGameQueueOne, updatedGameQueueTwo] } = await advanceGameQueue(program, provider, gameAccount);
 
 	return { updatedGameQueueOne, updatedGameQueueTwo };
 }
 
-async function advanceThreePlayerQueue(program, provider, playerOneAccount, playerTwoAccount, playerThreeAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameAccount) {
-	await program.rpc.advanceThreePlayerGameQueue({
-		accounts: {
-			playerOne: playerOneAccount.publicKey,
-			playerTwo: playerTwoAccount.publicKey,
-			playerThree: playerThreeAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
+async function advanceTwoPlayerKingOfHillQueue(program, provider, winningPlayerAccount, losingPlayerAccount, gameQueueAccountOne, gameQueueAccountTwo, gameAccount) {
+	const winningQueue = await winningQueueOf(program, gameAccount, winningPlayerAccount);
+	const { updatedGameQueues: [updatedGameQueueOne, updatedGameQueueTwo] } = await advanceKingOfHillQueue(program, provider, gameAccount, winningQueue);
+
+	return { updatedGameQueueOne, updatedGameQueueTwo };
+}
 
-	const updatedGameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const updatedGameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-	const updatedGameQueueThree = await program.account.gameQueue.fetch(gameQueueAccountThree.publicKey);
+async function advanceThreePlayerQueue(program, provider, playerOneAccount, playerTwoAccount, playerThreeAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameAccount) {
+	const { updatedGameQueues: [updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree] } = await advanceGameQueue(program, provider, gameAccount);
 
 	return { updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree };
 }
 
 async function advanceThreePlayerKingOfHillQueue(program, provider, winningPlayerAccount, losingPlayerAccountOne, losingPlayerAccountTwo, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameAccount) {
-	await program.rpc.advanceThreePlayerKingOfHillQueue({
-		accounts: {
-			winningPlayer: winningPlayerAccount.publicKey,
-			losingPlayerOne: losingPlayerAccountOne.publicKey,
-			losingPlayerTwo: losingPlayerAccountTwo.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const updatedGameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-	const updatedGameQueueThree = await program.account.gameQueue.fetch(gameQueueAccountThree.publicKey);
+	const winningQueue = await winningQueueOf(program, gameAccount, winningPlayerAccount);
+	const { updatedGameQueues: [updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree] } = await advanceKingOfHillQueue(program, provider, gameAccount, winningQueue);
 
 	return { updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree };
 }
 
 async function advanceFourPlayerQueue(program, provider, playerOneAccount, playerTwoAccount, playerThreeAccount, playerFourAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour, gameAccount) {
-	await program.rpc.advanceFourPlayerGameQueue({
-		accounts: {
-			playerOne: playerOneAccount.publicKey,
-			playerTwo: playerTwoAccount.publicKey,
-			playerThree: playerThreeAccount.publicKey,
-			playerFour: playerFourAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameQueueAccountFour: gameQueueAccountFour.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const updatedGameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-	const updatedGameQueueThree = await program.account.gameQueue.fetch(gameQueueAccountThree.publicKey);
-	const updatedGameQueueFour = await program.account.gameQueue.fetch(gameQueueAccountFour.publicKey);
+	const { updatedGameQueues: [updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree, updatedGameQueueFour] } = await advanceGameQueue(program, provider, gameAccount);
 
 	return { updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree, updatedGameQueueFour };
 }
 
 async function advanceFourPlayerKingOfHillQueue(program, provider, winningPlayerAccount, losingPlayerAccountOne, losingPlayerAccountTwo, losingPlayerAccountThree, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour, gameAccount) {
-	await program.rpc.advanceFourPlayerKingOfHillQueue({
-		accounts: {
-			winningPlayer: winningPlayerAccount.publicKey,
-			losingPlayerOne: losingPlayerAccountOne.publicKey,
-			losingPlayerTwo: losingPlayerAccountTwo.publicKey,
-			losingPlayerThree: losingPlayerAccountThree.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameQueueAccountFour: gameQueueAccountFour.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const updatedGameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-	const updatedGameQueueThree = await program.account.gameQueue.fetch(gameQueueAccountThree.publicKey);
-	const updatedGameQueueFour = await program.account.gameQueue.fetch(gameQueueAccountFour.publicKey);
+	const winningQueue = await winningQueueOf(program, gameAccount, winningPlayerAccount);
+	const { updatedGameQueues: [updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree, updatedGameQueueFour] } = await advanceKingOfHillQueue(program, provider, gameAccount, winningQueue);
 
 	return { updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree, updatedGameQueueFour };
 }
 
 async function advanceTeamKingOfHillQueue(program, provider, winningPlayerAccountOne, winningPlayerAccountTwo, losingPlayerAccountOne, losingPlayerAccountTwo, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour, gameAccount) {
-	await program.rpc.advanceTeamKingOfHillQueue({
-		accounts: {
-			winningPlayerOne: winningPlayerAccountOne.publicKey,
-			winningPlayerTwo: winningPlayerAccountTwo.publicKey,
-			losingPlayerOne: losingPlayerAccountOne.publicKey,
-			losingPlayerTwo: losingPlayerAccountTwo.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameQueueAccountFour: gameQueueAccountFour.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const updatedGameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-	const updatedGameQueueThree = await program.account.gameQueue.fetch(gameQueueAccountThree.publicKey);
-	const updatedGameQueueFour = await program.account.gameQueue.fetch(gameQueueAccountFour.publicKey);
+	const winningQueue = await winningQueueOf(program, gameAccount, winningPlayerAccountOne);
+	const { updatedGameQueues: [updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree, updatedGameQueueFour] } = await advanceKingOfHillQueue(program, provider, gameAccount, winningQueue);
 
 	return { updatedGameQueueOne, updatedGameQueueTwo, updatedGameQueueThree, updatedGameQueueFour };
 }
 
 module.exports = {
+	setOperator,
+	advanceGameQueue,
+	advanceKingOfHillQueue,
 	advanceOnePlayerQueue,
 	advanceTwoPlayerQueue,
 	advanceTwoPlayerKingOfHillQueue,
@@ -184,4 +127,4 @@ module.exports = {
 	advanceFourPlayerQueue,
 	advanceFourPlayerKingOfHillQueue,
 	advanceTeamKingOfHillQueue,
-};
\ No newline at end of file
+};
@@ -0,0 +1,67 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram, PublicKey } = anchor.web3;
+
+async function findAgeAttestationAddress(program, arcadeKey, walletKey) {
+	const [ageAttestationAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("age"), arcadeKey.toBuffer(), walletKey.toBuffer()],
+		program.programId
+	);
+	return ageAttestationAddress;
+}
+
+// The age attestation every instruction that puts a wallet in a game's queues needs, which is only read for 18+ games.
+async function ageAttestationAccounts(program, gameAccount, walletKey) {
+	const game = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { ageAttestation: await findAgeAttestationAddress(program, game.arcade, walletKey) };
+}
+
+async function setAgeAttester(program, provider, arcadeAccount, ageAttesterKey) {
+	await program.rpc.setAgeAttester(ageAttesterKey, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			authority: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+// Attests (or renews the attestation of) a wallet's age until `expiresAt` (a unix timestamp), signed by the arcade's attester.
+async function attestAge(program, arcadeAccount, walletKey, expiresAt, attester) {
+	const ageAttestation = await findAgeAttestationAddress(program, arcadeAccount.publicKey, walletKey);
+
+	await program.rpc.attestAge(walletKey, new anchor.BN(expiresAt), {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			ageAttestation,
+			attester: attester.publicKey,
+			systemProgram: SystemProgram.programId,
+		},
+		signers: [attester],
+	});
+
+	return { ageAttestation: await program.account.ageAttestation.fetch(ageAttestation) };
+}
+
+async function revokeAgeAttestation(program, arcadeAccount, walletKey, attester) {
+	await program.rpc.revokeAgeAttestation({
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			ageAttestation: await findAgeAttestationAddress(program, arcadeAccount.publicKey, walletKey),
+			attester: attester.publicKey,
+		},
+		signers: [attester],
+	});
+}
+
+module.exports = {
+	findAgeAttestationAddress,
+	ageAttestationAccounts,
+	setAgeAttester,
+	attestAge,
+	revokeAgeAttestation,
+};
@@ -0,0 +1,93 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram, PublicKey } = anchor.web3;
+const { TOKEN_PROGRAM_ID } = anchor.utils.token;
+
+// A game has at most one proposed list of collaborators, at an address derived from the game.
+async function findCollaboratorsProposalAddress(program, gameAccount) {
+	const [proposalAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("collaborators"), gameAccount.publicKey.toBuffer()],
+		program.programId
+	);
+	return proposalAddress;
+}
+
+// Shares are given as [[walletKey, shareBps], ...].
+async function proposeCollaborators(program, provider, gameAccount, shares) {
+	const proposal = await findCollaboratorsProposalAddress(program, gameAccount);
+
+	await program.rpc.proposeCollaborators(shares.map(([wallet, shareBps]) => ({ wallet, shareBps })), {
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			proposal,
+			owner: provider.wallet.publicKey,
+			systemProgram: SystemProgram.programId,
+		},
+	});
+
+	return { proposal: await program.account.collaboratorsProposal.fetch(proposal) };
+}
+
+async function approveCollaborators(program, gameAccount, wallet) {
+	const proposal = await findCollaboratorsProposalAddress(program, gameAccount);
+
+	await program.rpc.approveCollaborators({
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			proposal,
+			wallet: wallet.publicKey,
+		},
+		signers: [wallet],
+	});
+
+	return { proposal: await program.account.collaboratorsProposal.fetch(proposal) };
+}
+
+async function applyCollaborators(program, provider, gameAccount) {
+	await program.rpc.applyCollaborators({
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			proposal: await findCollaboratorsProposalAddress(program, gameAccount),
+			owner: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+async function cancelCollaborators(program, provider, gameAccount) {
+	await program.rpc.cancelCollaborators({
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			proposal: await findCollaboratorsProposalAddress(program, gameAccount),
+			owner: provider.wallet.publicKey,
+		},
+	});
+}
+
+// Games paid in tokens also need { escrowAccount, tokenAccount } when the collaborator is owed tokens.
+async function claimCollaboratorShare(program, gameAccount, wallet, tokenAccounts = null) {
+	await program.rpc.claimCollaboratorShare({
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			wallet: wallet.publicKey,
+		},
+		remainingAccounts: tokenAccounts == null ? [] : [
+			{ pubkey: tokenAccounts.escrowAccount.publicKey, isWritable: true, isSigner: false },
+			{ pubkey: tokenAccounts.tokenAccount.publicKey, isWritable: true, isSigner: false },
+			{ pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+		],
+		signers: [wallet],
+	});
+}
+
+module.exports = {
+	findCollaboratorsProposalAddress,
+	proposeCollaborators,
+	approveCollaborators,
+	applyCollaborators,
+	cancelCollaborators,
+	claimCollaboratorShare,
+};
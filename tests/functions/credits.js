@@ -0,0 +1,101 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram } = anchor.web3;
+const { findTreasuryAddress } = require("./treasury.js");
+
+async function findPlayerCreditsAddress(program, arcadeAccount, walletKey) {
+	const [address] = await anchor.web3.PublicKey.findProgramAddress(
+		[Buffer.from("credits"), arcadeAccount.publicKey.toBuffer(), walletKey.toBuffer()],
+		program.programId
+	);
+
+	return address;
+}
+
+// The lamports of a credits account above its rent.
+async function creditsBalance(program, provider, playerCreditsKey) {
+	const info = await provider.connection.getAccountInfo(playerCreditsKey);
+	const rent = await provider.connection.getMinimumBalanceForRentExemption(info.data.length);
+
+	return info.lamports - rent;
+}
+
+async function openPlayerCredits(program, provider, arcadeAccount, wallet = null) {
+	const walletKey = wallet ? wallet.publicKey : provider.wallet.publicKey;
+	const playerCreditsKey = await findPlayerCreditsAddress(program, arcadeAccount, walletKey);
+
+	await program.rpc.openPlayerCredits({
+		accounts: {
+			playerCredits: playerCreditsKey,
+			arcadeAccount: arcadeAccount.publicKey,
+			wallet: walletKey,
+			systemProgram: SystemProgram.programId,
+		},
+		signers: wallet ? [wallet] : [],
+	});
+
+	return { playerCreditsKey };
+}
+
+async function topUpCredits(program, provider, arcadeAccount, playerCreditsKey, lamports) {
+	await program.rpc.topUpCredits(new anchor.BN(lamports), {
+		accounts: {
+			playerCredits: playerCreditsKey,
+			arcadeAccount: arcadeAccount.publicKey,
+			payer: provider.wallet.publicKey,
+			systemProgram: SystemProgram.programId,
+		},
+	});
+
+	return { balance: await creditsBalance(program, provider, playerCreditsKey) };
+}
+
+async function setCreditsKiosk(program, provider, playerCreditsKey, kiosk, wallet = null) {
+	await program.rpc.setCreditsKiosk(kiosk, {
+		accounts: {
+			playerCredits: playerCreditsKey,
+			wallet: wallet ? wallet.publicKey : provider.wallet.publicKey,
+		},
+		signers: wallet ? [wallet] : [],
+	});
+
+	const playerCredits = await program.account.playerCredits.fetch(playerCreditsKey);
+
+	return { playerCredits };
+}
+
+async function withdrawCredits(program, provider, arcadeAccount, playerCreditsKey, lamports, wallet = null) {
+	await program.rpc.withdrawCredits(new anchor.BN(lamports), {
+		accounts: {
+			playerCredits: playerCreditsKey,
+			treasury: await findTreasuryAddress(program, arcadeAccount.publicKey),
+			wallet: wallet ? wallet.publicKey : provider.wallet.publicKey,
+		},
+		signers: wallet ? [wallet] : [],
+	});
+
+	return { balance: await creditsBalance(program, provider, playerCreditsKey) };
+}
+
+async function setCreditTerms(program, provider, arcadeAccount, creditDiscountBps, minCreditTopUp) {
+	await program.rpc.setCreditTerms(creditDiscountBps, new anchor.BN(minCreditTopUp), {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			authority: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+module.exports = {
+	findPlayerCreditsAddress,
+	creditsBalance,
+	openPlayerCredits,
+	topUpCredits,
+	setCreditsKiosk,
+	withdrawCredits,
+	setCreditTerms,
+};
@@ -1,16 +1,17 @@
 const anchor = require("@project-serum/anchor");
 
 const { SystemProgram } = anchor.web3;
+const { queueAndPlayerAccounts } = require("./gameQueues.js");
 
-async function finishOnePlayerGameQueue(program, provider, currentPlayerAccount, gameQueueAccount, gameAccount) {
-	await program.rpc.finishOnePlayerGameQueue({
+async function finishGameQueue(program, provider, gameAccount, authority = null) {
+	await program.rpc.finishGameQueue({
 		accounts: {
-			currentPlayer: currentPlayerAccount.publicKey,
-			gameQueueAccount: gameQueueAccount.publicKey,
 			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
+			authority: authority ? authority.publicKey : provider.wallet.publicKey,
 			systemProgram: SystemProgram.programId,
 		},
+		remainingAccounts: await queueAndPlayerAccounts(program, gameAccount),
+		signers: authority ? [authority] : [],
 	});
 
 	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
@@ -18,144 +19,40 @@ async function finishOnePlayerGameQueue(program, provider, currentPlayerAccount,
 	return { updatedGame };
 }
 
-async function finishTwoPlayerGameQueue(program, provider, playerOneAccount, playerTwoAccount, gameQueueAccountOne, gameQueueAccountTwo, gameAccount) {
-	await program.rpc.finishTwoPlayerGameQueue({
-		accounts: {
-			playerOne: playerOneAccount.publicKey,
-			playerTwo: playerTwoAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+async function finishOnePlayerGameQueue(program, provider, currentPlayerAccount, gameQueueAccount, gameAccount) {
+	return finishGameQueue(program, provider, gameAccount);
+}
 
-	return { updatedGame };
+async function finishTwoPlayerGameQueue(program, provider, playerOneAccount, playerTwoAccount, gameQueueAccountOne, gameQueueAccountTwo, gameAccount) {
+	return finishGameQueue(program, provider, gameAccount);
 }
 
 async function finishTwoPlayerKingOfHillQueue(program, provider, losingPlayerAccount, gameQueueAccountOne, gameQueueAccountTwo, gameAccount) {
-	await program.rpc.finishTwoPlayerKingOfHillQueue({
-		accounts: {
-			losingPlayer: losingPlayerAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
-
-	return { updatedGame };
+	return finishGameQueue(program, provider, gameAccount);
 }
 
 async function finishThreePlayerGameQueue(program, provider, playerOneAccount, playerTwoAccount, playerThreeAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameAccount) {
-	await program.rpc.finishThreePlayerGameQueue({
-		accounts: {
-			playerOne: playerOneAccount.publicKey,
-			playerTwo: playerTwoAccount.publicKey,
-			playerThree: playerThreeAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
-
-	return { updatedGame };
+	return finishGameQueue(program, provider, gameAccount);
 }
 
 async function finishThreePlayerKingOfHillQueue(program, provider, losingPlayerAccountOne, losingPlayerAccountTwo, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameAccount) {
-	await program.rpc.finishThreePlayerKingOfHillQueue({
-		accounts: {
-			losingPlayerOne: losingPlayerAccountOne.publicKey,
-			losingPlayerTwo: losingPlayerAccountTwo.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
-
-	return { updatedGame };
+	return finishGameQueue(program, provider, gameAccount);
 }
 
 async function finishFourPlayerGameQueue(program, provider, playerOneAccount, playerTwoAccount, playerThreeAccount, playerFourAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour, gameAccount) {
-	await program.rpc.finishFourPlayerGameQueue({
-		accounts: {
-			playerOne: playerOneAccount.publicKey,
-			playerTwo: playerTwoAccount.publicKey,
-			playerThree: playerThreeAccount.publicKey,
-			playerFour: playerFourAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameQueueAccountFour: gameQueueAccountFour.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
-
-	return { updatedGame };
+	return finishGameQueue(program, provider, gameAccount);
 }
 
 async function finishFourPlayerKingOfHillQueue(program, provider, losingPlayerAccountOne, losingPlayerAccountTwo, losingPlayerAccountThree, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour, gameAccount) {
-	await program.rpc.finishFourPlayerKingOfHillQueue({
-		accounts: {
-			losingPlayerOne: losingPlayerAccountOne.publicKey,
-			losingPlayerTwo: losingPlayerAccountTwo.publicKey,
-			losingPlayerThree: losingPlayerAccountThree.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameQueueAccountFour: gameQueueAccountFour.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
-
-	return { updatedGame };
+	return finishGameQueue(program, provider, gameAccount);
 }
 
 async function finishTeamKingOfHillQueue(program, provider, losingPlayerAccountOne, losingPlayerAccountTwo, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour, gameAccount) {
-	await program.rpc.finishTeamKingOfHillQueue({
-		accounts: {
-			losingPlayerOne: losingPlayerAccountOne.publicKey,
-			losingPlayerTwo: losingPlayerAccountTwo.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameQueueAccountFour: gameQueueAccountFour.publicKey,
-			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-	});
-
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
-
-	return { updatedGame };
+	return finishGameQueue(program, provider, gameAccount);
 }
 
 module.exports = {
+	finishGameQueue,
 	finishOnePlayerGameQueue,
 	finishTwoPlayerGameQueue,
 	finishTwoPlayerKingOfHillQueue,
@@ -164,4 +61,4 @@ module.exports = {
 	finishFourPlayerGameQueue,
 	finishFourPlayerKingOfHillQueue,
 	finishTeamKingOfHillQueue,
-};
\ No newline at end of file
+};
@@ -0,0 +1,33 @@
+// Proposes a new owner for a game (null withdraws the proposal), signed by its current owner.
+async function proposeOwner(program, provider, gameAccount, newOwnerKey, owner = null) {
+	await program.rpc.proposeOwner(newOwnerKey, {
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			owner: owner ? owner.publicKey : provider.wallet.publicKey,
+		},
+		signers: owner ? [owner] : [],
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+async function acceptOwner(program, gameAccount, newOwner) {
+	await program.rpc.acceptOwner({
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			newOwner: newOwner.publicKey,
+		},
+		signers: [newOwner],
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+module.exports = {
+	proposeOwner,
+	acceptOwner,
+};
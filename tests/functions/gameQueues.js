@@ -0,0 +1,65 @@
+const anchor = require("@project-serum/anchor");
+
+const { PublicKey } = anchor.web3;
+const { u64Seed } = require("./makeGame.js");
+
+// Queues live at an address derived from their game and queue number (1 for the first queue).
+async function findGameQueueAddress(program, gameAccount, queueNum) {
+	const [gameQueueAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("game_queue"), gameAccount.publicKey.toBuffer(), Buffer.from([queueNum])],
+		program.programId
+	);
+	return gameQueueAddress;
+}
+
+// Players live at an address derived from their queue and their sequence number in it.
+async function findPlayerAddress(program, gameQueueAddress, sequence) {
+	const [playerAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("player"), gameQueueAddress.toBuffer(), u64Seed(sequence)],
+		program.programId
+	);
+	return playerAddress;
+}
+
+// Session receipts live at an address derived from the player account they replace.
+async function findSessionReceiptAddress(program, playerAddress) {
+	const [sessionReceiptAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("session_receipt"), playerAddress.toBuffer()],
+		program.programId
+	);
+	return sessionReceiptAddress;
+}
+
+// Fetches the game's queues in queue order.
+async function fetchGameQueues(program, gameAccount) {
+	const game = await program.account.game.fetch(gameAccount.publicKey);
+
+	return Promise.all(game.gameQueues.map(async (publicKey) => ({ publicKey, account: await program.account.gameQueue.fetch(publicKey) })));
+}
+
+function writable(publicKey) {
+	return { pubkey: publicKey, isSigner: false, isWritable: true };
+}
+
+// The remaining accounts of the queue instructions that work on everyone playing: the game's queues followed by the current
+// player of every queue that is not empty, and then the session receipts of the players (by queue index) whose session ends.
+async function queueAndPlayerAccounts(program, gameAccount, endsSession = (queueIndex) => true) {
+	const gameQueues = await fetchGameQueues(program, gameAccount);
+	const playing = gameQueues
+		.map((gameQueue, queueIndex) => ({ queueIndex, currentPlayer: gameQueue.account.currentPlayer }))
+		.filter(({ currentPlayer }) => !currentPlayer.equals(gameAccount.publicKey));
+	const sessionReceipts = await Promise.all(playing
+		.filter(({ queueIndex }) => endsSession(queueIndex))
+		.map(async ({ currentPlayer }) => writable(await findSessionReceiptAddress(program, currentPlayer))));
+
+	return [...gameQueues.map((gameQueue) => writable(gameQueue.publicKey)), ...playing.map(({ currentPlayer }) => writable(currentPlayer)), ...sessionReceipts];
+}
+
+module.exports = {
+	findGameQueueAddress,
+	findPlayerAddress,
+	findSessionReceiptAddress,
+	fetchGameQueues,
+	writable,
+	queueAndPlayerAccounts,
+};
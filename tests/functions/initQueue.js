@@ -1,116 +1,128 @@
 const anchor = require("@project-serum/anchor");
 
 const { SystemProgram } = anchor.web3;
+const { playFeeAccounts } = require("./priceFeed.js");
+const { ageAttestationAccounts } = require("./ageAttestation.js");
+const { playerProfileAccounts } = require("./profiles.js");
+const { tokenPaymentAccounts } = require("./tokens.js");
+const { findGameQueueAddress, findPlayerAddress } = require("./gameQueues.js");
+
+// The accounts of a queue initialization, where the attestation and the profile are the playing wallet's (the payer's unless given).
+async function initQueueAccounts(program, provider, gameAccount, payer = null, walletKey = null) {
+	const game = await program.account.game.fetch(gameAccount.publicKey);
+	const gameQueueAccounts = await Promise.all(Array.from({ length: game.maxPlayers }, async (_, i) => ({ publicKey: await findGameQueueAddress(program, gameAccount, i + 1) })));
+	const playerAccount = { publicKey: await findPlayerAddress(program, gameQueueAccounts[0].publicKey, game.firstPlayerSequence) };
+	const playingWallet = walletKey ?? (payer ? payer.publicKey : provider.wallet.publicKey);
+
+	const accounts = {
+		playerAccount: playerAccount.publicKey,
+		gameAccount: gameAccount.publicKey,
+		...(await playFeeAccounts(program, gameAccount)),
+		...(await ageAttestationAccounts(program, gameAccount, playingWallet)),
+		...(await playerProfileAccounts(program, gameAccount, playingWallet)),
+		payer: payer ? payer.publicKey : provider.wallet.publicKey,
+		systemProgram: SystemProgram.programId,
+	};
+	const remainingAccounts = gameQueueAccounts.map((gameQueueAccount) => ({ pubkey: gameQueueAccount.publicKey, isSigner: false, isWritable: true }));
+
+	return { playerAccount, gameQueueAccounts, accounts, remainingAccounts };
+}
 
-async function initOnePlayerQueue(program, provider, gameAccount) {
-	const playerAccount = anchor.web3.Keypair.generate();
-	const gameQueueAccount = anchor.web3.Keypair.generate();
-
-	await program.rpc.initOnePlayerGameQueue({
-		accounts: {
-			playerAccount: playerAccount.publicKey,
-			gameQueueAccount: gameQueueAccount.publicKey,
-			gameAccount: gameAccount.publicKey,
-			payer: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-		signers: [playerAccount, gameQueueAccount],
-	});
-
-	const gameQueue = await program.account.gameQueue.fetch(gameQueueAccount.publicKey);
+async function fetchInitializedQueues(program, gameAccount, playerAccount, gameQueueAccounts) {
+	const gameQueues = await Promise.all(gameQueueAccounts.map((gameQueueAccount) => program.account.gameQueue.fetch(gameQueueAccount.publicKey)));
 	const player = await program.account.player.fetch(playerAccount.publicKey);
 	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
 
-	return { player, playerAccount, gameQueue, gameQueueAccount, updatedGame };
+	return { player, playerAccount, gameQueues, gameQueueAccounts, updatedGame };
 }
 
-async function initTwoPlayerQueue(program, provider, gameAccount) {
-	const playerAccount = anchor.web3.Keypair.generate();
-	const gameQueueAccountOne = anchor.web3.Keypair.generate();
-	const gameQueueAccountTwo = anchor.web3.Keypair.generate();
+async function initGameQueue(program, provider, gameAccount, payer = null) {
+	const { playerAccount, gameQueueAccounts, accounts, remainingAccounts } = await initQueueAccounts(program, provider, gameAccount, payer);
 
-	await program.rpc.initTwoPlayerGameQueue({
-		accounts: {
-			playerAccount: playerAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameAccount: gameAccount.publicKey,
-			payer: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
-		},
-		signers: [playerAccount, gameQueueAccountOne, gameQueueAccountTwo],
+	await program.rpc.initGameQueue({
+		accounts,
+		remainingAccounts,
+		signers: payer ? [payer] : [],
 	});
 
-	const gameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const gameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-	const player = await program.account.player.fetch(playerAccount.publicKey);
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
-
-	return { player, playerAccount, gameQueueOne, gameQueueAccountOne, gameQueueTwo, gameQueueAccountTwo, updatedGame };
+	return fetchInitializedQueues(program, gameAccount, playerAccount, gameQueueAccounts);
 }
 
-async function initThreePlayerQueue(program, provider, gameAccount) {
-	const playerAccount = anchor.web3.Keypair.generate();
-	const gameQueueAccountOne = anchor.web3.Keypair.generate();
-	const gameQueueAccountTwo = anchor.web3.Keypair.generate();
-	const gameQueueAccountThree = anchor.web3.Keypair.generate();
+async function initGameQueueWithTokens(program, provider, gameAccount, payerTokenAccount) {
+	const { playerAccount, gameQueueAccounts, accounts, remainingAccounts } = await initQueueAccounts(program, provider, gameAccount);
 
-	await program.rpc.initThreePlayerGameQueue({
+	await program.rpc.initGameQueueWithTokens({
 		accounts: {
-			playerAccount: playerAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameAccount: gameAccount.publicKey,
-			payer: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
+			queue: accounts,
+			payment: await tokenPaymentAccounts(program, gameAccount, payerTokenAccount),
 		},
-		signers: [playerAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree],
+		remainingAccounts,
 	});
 
-	const gameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const gameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-	const gameQueueThree = await program.account.gameQueue.fetch(gameQueueAccountThree.publicKey);
-	const player = await program.account.player.fetch(playerAccount.publicKey);
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
-
-	return { player, playerAccount, gameQueueOne, gameQueueAccountOne, gameQueueTwo, gameQueueAccountTwo, gameQueueThree, gameQueueAccountThree, updatedGame};
+	return fetchInitializedQueues(program, gameAccount, playerAccount, gameQueueAccounts);
 }
 
-async function initFourPlayerQueue(program, provider, gameAccount) {
-	const playerAccount = anchor.web3.Keypair.generate();
-	const gameQueueAccountOne = anchor.web3.Keypair.generate();
-	const gameQueueAccountTwo = anchor.web3.Keypair.generate();
-	const gameQueueAccountThree = anchor.web3.Keypair.generate();
-	const gameQueueAccountFour = anchor.web3.Keypair.generate();
+// Initializes the queues with a play paid from prepaid credits, spent by their wallet or, when given, its kiosk (which then also
+// pays for the new accounts).
+async function initGameQueueWithCredits(program, provider, gameAccount, playerCreditsKey, kiosk = null) {
+	const playerCredits = await program.account.playerCredits.fetch(playerCreditsKey);
+	const { playerAccount, gameQueueAccounts, accounts, remainingAccounts } = await initQueueAccounts(program, provider, gameAccount, kiosk, playerCredits.wallet);
 
-	await program.rpc.initFourPlayerGameQueue({
+	await program.rpc.initGameQueueWithCredits({
 		accounts: {
-			playerAccount: playerAccount.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameQueueAccountFour: gameQueueAccountFour.publicKey,
-			gameAccount: gameAccount.publicKey,
-			payer: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
+			queue: accounts,
+			credits: { playerCredits: playerCreditsKey },
 		},
-		signers: [playerAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour],
+		remainingAccounts,
+		signers: kiosk ? [kiosk] : [],
 	});
 
-	const gameQueueOne = await program.account.gameQueue.fetch(gameQueueAccountOne.publicKey);
-	const gameQueueTwo = await program.account.gameQueue.fetch(gameQueueAccountTwo.publicKey);
-	const gameQueueThree = await program.account.gameQueue.fetch(gameQueueAccountThree.publicKey);
-	const gameQueueFour = await program.account.gameQueue.fetch(gameQueueAccountFour.publicKey);
-	const player = await program.account.player.fetch(playerAccount.publicKey);
-	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+	return fetchInitializedQueues(program, gameAccount, playerAccount, gameQueueAccounts);
+}
+
+async function initOnePlayerQueue(program, provider, gameAccount) {
+	const { player, playerAccount, gameQueues, gameQueueAccounts, updatedGame } = await initGameQueue(program, provider, gameAccount);
+
+	return { player, playerAccount, gameQueue: gameQueues[0], gameQueueAccount: gameQueueAccounts[0], updatedGame };
+}
+
+async function initOnePlayerQueueWithTokens(program, provider, gameAccount, payerTokenAccount) {
+	const { player, playerAccount, gameQueues, gameQueueAccounts, updatedGame } = await initGameQueueWithTokens(program, provider, gameAccount, payerTokenAccount);
+
+	return { player, playerAccount, gameQueue: gameQueues[0], gameQueueAccount: gameQueueAccounts[0], updatedGame };
+}
+
+async function initTwoPlayerQueue(program, provider, gameAccount) {
+	const { player, playerAccount, gameQueues, gameQueueAccounts, updatedGame } = await initGameQueue(program, provider, gameAccount);
+	const [gameQueueOne, gameQueueTwo] = gameQueues;
+	const [gameQueueAccountOne, gameQueueAccountTwo] = gameQueueAccounts;
+
+	return { player, playerAccount, gameQueueOne, gameQueueAccountOne, gameQueueTwo, gameQueueAccountTwo, updatedGame };
+}
+
+async function initThreePlayerQueue(program, provider, gameAccount) {
+	const { player, playerAccount, gameQueues, gameQueueAccounts, updatedGame } = await initGameQueue(program, provider, gameAccount);
+	const [gameQueueOne, gameQueueTwo, gameQueueThree] = gameQueues;
+	const [gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree] = gameQueueAccounts;
+
+	return { player, playerAccount, gameQueueOne, gameQueueAccountOne, gameQueueTwo, gameQueueAccountTwo, gameQueueThree, gameQueueAccountThree, updatedGame};
+}
+
+async function initFourPlayerQueue(program, provider, gameAccount) {
+	const { player, playerAccount, gameQueues, gameQueueAccounts, updatedGame } = await initGameQueue(program, provider, gameAccount);
+	const [gameQueueOne, gameQueueTwo, gameQueueThree, gameQueueFour] = gameQueues;
+	const [gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour] = gameQueueAccounts;
 
 	return { player, playerAccount, gameQueueOne, gameQueueAccountOne, gameQueueTwo, gameQueueAccountTwo, gameQueueThree, gameQueueAccountThree, gameQueueFour, gameQueueAccountFour, updatedGame };
 }
 
 module.exports = {
+	initGameQueue,
+	initGameQueueWithTokens,
+	initGameQueueWithCredits,
 	initOnePlayerQueue,
+	initOnePlayerQueueWithTokens,
 	initTwoPlayerQueue,
 	initThreePlayerQueue,
 	initFourPlayerQueue,
-};
\ No newline at end of file
+};
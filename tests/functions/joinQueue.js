@@ -1,20 +1,42 @@
 const anchor = require("@project-serum/anchor");
 
 const { SystemProgram } = anchor.web3;
+const { playFeeAccounts } = require("./priceFeed.js");
+const { ageAttestationAccounts } = require("./ageAttestation.js");
+const { playerProfileAccounts } = require("./profiles.js");
+const { tokenPaymentAccounts } = require("./tokens.js");
+const { fetchGameQueues, writable, findPlayerAddress } = require("./gameQueues.js");
+
+// The accounts of a normal join: the new player's account, which is derived from the queue the program puts the new player in
+// (the first of the shortest queues), and the game's queues followed by the last player of that queue.
+async function joinQueueAccounts(program, gameAccount) {
+	const gameQueues = await fetchGameQueues(program, gameAccount);
+	const shortestQueue = gameQueues.reduce((shortest, gameQueue) => gameQueue.account.numPlayersInQueue.lt(shortest.account.numPlayersInQueue) ? gameQueue : shortest);
+	const remainingAccounts = gameQueues.map((gameQueue) => writable(gameQueue.publicKey));
+
+	if (!shortestQueue.account.currentPlayer.equals(gameAccount.publicKey)) {
+		remainingAccounts.push(writable(shortestQueue.account.lastPlayer));
+	}
+
+	const playerAccount = { publicKey: await findPlayerAddress(program, shortestQueue.publicKey, shortestQueue.account.nextSequence) };
+
+	return { playerAccount, remainingAccounts };
+}
 
-async function joinOnePlayerQueue(program, provider, gameAccount, gameQueueAccount, lastPlayer) {
-	const playerAccount = anchor.web3.Keypair.generate();
+async function joinGameQueue(program, provider, gameAccount) {
+	const { playerAccount, remainingAccounts } = await joinQueueAccounts(program, gameAccount);
 
-	await program.rpc.joinOnePlayerGameQueue({
+	await program.rpc.joinGameQueue({
 		accounts: {
 			playerAccount: playerAccount.publicKey,
-			lastPlayer: lastPlayer.publicKey,
-			gameQueueAccount: gameQueueAccount.publicKey,
 			gameAccount: gameAccount.publicKey,
+			...(await playFeeAccounts(program, gameAccount)),
+			...(await ageAttestationAccounts(program, gameAccount, provider.wallet.publicKey)),
+			...(await playerProfileAccounts(program, gameAccount, provider.wallet.publicKey)),
 			payer: provider.wallet.publicKey,
 			systemProgram: SystemProgram.programId,
 		},
-		signers: [playerAccount],
+		remainingAccounts,
 	});
 
 	const player = await program.account.player.fetch(playerAccount.publicKey);
@@ -22,21 +44,23 @@ async function joinOnePlayerQueue(program, provider, gameAccount, gameQueueAccou
 	return { player, playerAccount };
 }
 
-async function joinTwoPlayerQueue(program, provider, gameAccount, gameQueueAccountOne, gameQueueAccountTwo, queueOneLastPlayer, queueTwoLastPlayer) {
-	const playerAccount = anchor.web3.Keypair.generate();
+async function joinGameQueueWithTokens(program, provider, gameAccount, payerTokenAccount) {
+	const { playerAccount, remainingAccounts } = await joinQueueAccounts(program, gameAccount);
 
-	await program.rpc.joinTwoPlayerGameQueue({
+	await program.rpc.joinGameQueueWithTokens({
 		accounts: {
-			playerAccount: playerAccount.publicKey,
-			q1LastPlayer: queueOneLastPlayer.publicKey,
-			q2LastPlayer: queueTwoLastPlayer.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameAccount: gameAccount.publicKey,
-			payer: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
+			queue: {
+				playerAccount: playerAccount.publicKey,
+				gameAccount: gameAccount.publicKey,
+				...(await playFeeAccounts(program, gameAccount)),
+				...(await ageAttestationAccounts(program, gameAccount, provider.wallet.publicKey)),
+				...(await playerProfileAccounts(program, gameAccount, provider.wallet.publicKey)),
+				payer: provider.wallet.publicKey,
+				systemProgram: SystemProgram.programId,
+			},
+			payment: await tokenPaymentAccounts(program, gameAccount, payerTokenAccount),
 		},
-		signers: [playerAccount],
+		remainingAccounts,
 	});
 
 	const player = await program.account.player.fetch(playerAccount.publicKey);
@@ -44,23 +68,27 @@ async function joinTwoPlayerQueue(program, provider, gameAccount, gameQueueAccou
 	return { player, playerAccount };
 }
 
-async function joinThreePlayerQueue(program, provider, gameAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, queueOneLastPlayer, queueTwoLastPlayer, queueThreeLastPlayer) {
-	const playerAccount = anchor.web3.Keypair.generate();
+// Joins with a play paid from prepaid credits, spent by their wallet or, when given, its kiosk (which then also pays for the new
+// player account).
+async function joinGameQueueWithCredits(program, provider, gameAccount, playerCreditsKey, kiosk = null) {
+	const { playerAccount, remainingAccounts } = await joinQueueAccounts(program, gameAccount);
+	const playerCredits = await program.account.playerCredits.fetch(playerCreditsKey);
 
-	await program.rpc.joinThreePlayerGameQueue({
+	await program.rpc.joinGameQueueWithCredits({
 		accounts: {
-			playerAccount: playerAccount.publicKey,
-			q1LastPlayer: queueOneLastPlayer.publicKey,
-			q2LastPlayer: queueTwoLastPlayer.publicKey,
-			q3LastPlayer: queueThreeLastPlayer.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameAccount: gameAccount.publicKey,
-			payer: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
+			queue: {
+				playerAccount: playerAccount.publicKey,
+				gameAccount: gameAccount.publicKey,
+				...(await playFeeAccounts(program, gameAccount)),
+				...(await ageAttestationAccounts(program, gameAccount, playerCredits.wallet)),
+				...(await playerProfileAccounts(program, gameAccount, playerCredits.wallet)),
+				payer: kiosk ? kiosk.publicKey : provider.wallet.publicKey,
+				systemProgram: SystemProgram.programId,
+			},
+			credits: { playerCredits: playerCreditsKey },
 		},
-		signers: [playerAccount],
+		remainingAccounts,
+		signers: kiosk ? [kiosk] : [],
 	});
 
 	const player = await program.account.player.fetch(playerAccount.publicKey);
@@ -68,25 +96,45 @@ async function joinThreePlayerQueue(program, provider, gameAccount, gameQueueAcc
 	return { player, playerAccount };
 }
 
+async function joinOnePlayerQueue(program, provider, gameAccount, gameQueueAccount, lastPlayer) {
+	return joinGameQueue(program, provider, gameAccount);
+}
+
+async function joinOnePlayerQueueWithTokens(program, provider, gameAccount, gameQueueAccount, lastPlayer, payerTokenAccount) {
+	return joinGameQueueWithTokens(program, provider, gameAccount, payerTokenAccount);
+}
+
+async function joinTwoPlayerQueue(program, provider, gameAccount, gameQueueAccountOne, gameQueueAccountTwo, queueOneLastPlayer, queueTwoLastPlayer) {
+	return joinGameQueue(program, provider, gameAccount);
+}
+
+async function joinThreePlayerQueue(program, provider, gameAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, queueOneLastPlayer, queueTwoLastPlayer, queueThreeLastPlayer) {
+	return joinGameQueue(program, provider, gameAccount);
+}
+
 async function joinFourPlayerQueue(program, provider, gameAccount, gameQueueAccountOne, gameQueueAccountTwo, gameQueueAccountThree, gameQueueAccountFour, queueOneLastPlayer, queueTwoLastPlayer, queueThreeLastPlayer, queueFourLastPlayer) {
-	const playerAccount = anchor.web3.Keypair.generate();
+	return joinGameQueue(program, provider, gameAccount);
+}
+
+// Joins a king of the hill queue, paid by (and played by) the provider's wallet unless a payer is given.
+async function joinKingOfHillQueue(program, provider, gameAccount, gameQueueAccount, lastPlayerAccount, payer = null) {
+	const gameQueue = await program.account.gameQueue.fetch(gameQueueAccount.publicKey);
+	const playerAccount = { publicKey: await findPlayerAddress(program, gameQueueAccount.publicKey, gameQueue.nextSequence) };
+	const payerKey = payer ? payer.publicKey : provider.wallet.publicKey;
 
-	await program.rpc.joinFourPlayerGameQueue({
+	await program.rpc.joinKingOfHillGameQueue({
 		accounts: {
 			playerAccount: playerAccount.publicKey,
-			q1LastPlayer: queueOneLastPlayer.publicKey,
-			q2LastPlayer: queueTwoLastPlayer.publicKey,
-			q3LastPlayer: queueThreeLastPlayer.publicKey,
-			q4LastPlayer: queueFourLastPlayer.publicKey,
-			gameQueueAccountOne: gameQueueAccountOne.publicKey,
-			gameQueueAccountTwo: gameQueueAccountTwo.publicKey,
-			gameQueueAccountThree: gameQueueAccountThree.publicKey,
-			gameQueueAccountFour: gameQueueAccountFour.publicKey,
+			lastPlayer: lastPlayerAccount.publicKey,
+			gameQueueAccount: gameQueueAccount.publicKey,
 			gameAccount: gameAccount.publicKey,
-			payer: provider.wallet.publicKey,
+			...(await playFeeAccounts(program, gameAccount)),
+			...(await ageAttestationAccounts(program, gameAccount, payerKey)),
+			...(await playerProfileAccounts(program, gameAccount, payerKey)),
+			payer: payerKey,
 			systemProgram: SystemProgram.programId,
 		},
-		signers: [playerAccount],
+		signers: payer ? [payer] : [],
 	});
 
 	const player = await program.account.player.fetch(playerAccount.publicKey);
@@ -94,19 +142,27 @@ async function joinFourPlayerQueue(program, provider, gameAccount, gameQueueAcco
 	return { player, playerAccount };
 }
 
-async function joinKingOfHillQueue(program, provider, gameAccount, gameQueueAccount, lastPlayerAccount) {
-	const playerAccount = anchor.web3.Keypair.generate();
+async function joinKingOfHillQueueWithCredits(program, provider, gameAccount, gameQueueAccount, lastPlayerAccount, playerCreditsKey, kiosk = null) {
+	const gameQueue = await program.account.gameQueue.fetch(gameQueueAccount.publicKey);
+	const playerAccount = { publicKey: await findPlayerAddress(program, gameQueueAccount.publicKey, gameQueue.nextSequence) };
+	const playerCredits = await program.account.playerCredits.fetch(playerCreditsKey);
 
-	await program.rpc.joinKingOfHillGameQueue({
+	await program.rpc.joinKingOfHillGameQueueWithCredits({
 		accounts: {
-			playerAccount: playerAccount.publicKey,
-			lastPlayer: lastPlayerAccount.publicKey,
-			gameQueueAccount: gameQueueAccount.publicKey,
-			gameAccount: gameAccount.publicKey,
-			payer: provider.wallet.publicKey,
-			systemProgram: SystemProgram.programId,
+			queue: {
+				playerAccount: playerAccount.publicKey,
+				lastPlayer: lastPlayerAccount.publicKey,
+				gameQueueAccount: gameQueueAccount.publicKey,
+				gameAccount: gameAccount.publicKey,
+				...(await playFeeAccounts(program, gameAccount)),
+				...(await ageAttestationAccounts(program, gameAccount, playerCredits.wallet)),
+				...(await playerProfileAccounts(program, gameAccount, playerCredits.wallet)),
+				payer: kiosk ? kiosk.publicKey : provider.wallet.publicKey,
+				systemProgram: SystemProgram.programId,
+			},
+			credits: { playerCredits: playerCreditsKey },
 		},
-		signers: [playerAccount],
+		signers: kiosk ? [kiosk] : [],
 	});
 
 	const player = await program.account.player.fetch(playerAccount.publicKey);
@@ -115,9 +171,14 @@ async function joinKingOfHillQueue(program, provider, gameAccount, gameQueueAcco
 }
 
 module.exports = {
+	joinGameQueue,
+	joinGameQueueWithTokens,
+	joinGameQueueWithCredits,
 	joinOnePlayerQueue,
+	joinOnePlayerQueueWithTokens,
 	joinTwoPlayerQueue,
 	joinThreePlayerQueue,
 	joinFourPlayerQueue,
 	joinKingOfHillQueue,
+	joinKingOfHillQueueWithCredits,
 };
\ No newline at end of file
@@ -0,0 +1,57 @@
+const anchor = require("@project-serum/anchor");
+
+const { TOKEN_PROGRAM_ID } = anchor.utils.token;
+const { writable } = require("./gameQueues.js");
+
+// Walks a queue's line of players from its current player to the back of the queue.
+async function fetchLine(program, gameQueueAccount) {
+	const gameQueue = await program.account.gameQueue.fetch(gameQueueAccount.publicKey);
+	const line = [];
+
+	let playerKey = gameQueue.currentPlayer.equals(gameQueue.game) ? null : gameQueue.currentPlayer;
+	while (playerKey) {
+		line.push(playerKey);
+		playerKey = (await program.account.player.fetch(playerKey)).nextPlayer;
+	}
+
+	return line;
+}
+
+// Takes a waiting player out of their queue.  The player in front of them is passed as the previous player and the players behind
+// them as remaining accounts, followed by the refund token accounts when the play was paid in tokens or by the credits it was
+// paid from.
+async function leaveQueue(program, provider, gameAccount, gameQueueAccount, playerAccount, refundTokenAccount, wallet = null) {
+	const line = await fetchLine(program, gameQueueAccount);
+	const position = line.findIndex((playerKey) => playerKey.equals(playerAccount.publicKey));
+	const remainingAccounts = line.slice(position + 1).map(writable);
+
+	const player = await program.account.player.fetch(playerAccount.publicKey);
+	if (player.feeMint) {
+		const game = await program.account.game.fetch(gameAccount.publicKey);
+		remainingAccounts.push(writable(game.escrow), writable(refundTokenAccount.publicKey), { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false });
+	} else if (player.feeCredits) {
+		remainingAccounts.push(writable(player.feeCredits));
+	}
+
+	await program.rpc.leaveQueue({
+		accounts: {
+			playerAccount: playerAccount.publicKey,
+			previousPlayer: line[position - 1] ?? playerAccount.publicKey,
+			gameQueueAccount: gameQueueAccount.publicKey,
+			gameAccount: gameAccount.publicKey,
+			wallet: wallet ? wallet.publicKey : provider.wallet.publicKey,
+		},
+		remainingAccounts,
+		signers: wallet ? [wallet] : [],
+	});
+
+	const updatedGameQueue = await program.account.gameQueue.fetch(gameQueueAccount.publicKey);
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGameQueue, updatedGame };
+}
+
+module.exports = {
+	fetchLine,
+	leaveQueue,
+};
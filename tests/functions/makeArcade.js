@@ -1,24 +1,31 @@
 const anchor = require("@project-serum/anchor");
 
 const { SystemProgram } = anchor.web3;
+const { makeManualPriceFeed, setPriceFeed } = require("./priceFeed.js");
+const { findGameAddress } = require("./makeGame.js");
+const { findTreasuryAddress } = require("./treasury.js");
 
 async function makeArcade(program, provider) {
 	const arcadeAccount = anchor.web3.Keypair.generate();
-	const genesisGameAccount = anchor.web3.Keypair.generate();
+	const genesisGameAccount = { publicKey: await findGameAddress(program, arcadeAccount, 0) };
 
 	await program.rpc.initializeArcade({
 		accounts: {
 			arcadeAccount: arcadeAccount.publicKey,
 			genesisGameAccount: genesisGameAccount.publicKey,
+			treasury: await findTreasuryAddress(program, arcadeAccount.publicKey),
 			authority: provider.wallet.publicKey,
 			systemProgram: SystemProgram.programId,
 		},
-		signers: [arcadeAccount, genesisGameAccount],
+		signers: [arcadeAccount],
 	});
 
+	const { priceFeedAccount } = await makeManualPriceFeed(program, provider);
+	await setPriceFeed(program, provider, arcadeAccount, priceFeedAccount);
+
 	const arcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
 
-	return { arcade, arcadeAccount, genesisGameAccount };
+	return { arcade, arcadeAccount, genesisGameAccount, priceFeedAccount };
 }
 
 module.exports = {
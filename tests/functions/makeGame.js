@@ -1,23 +1,46 @@
 const anchor = require("@project-serum/anchor");
 
-const { SystemProgram } = anchor.web3;
+const { SystemProgram, PublicKey } = anchor.web3;
 
-async function makeGame(program, provider, arcadeAccount, mostRecentGameAccount, numPlayers, gameType) {
-	const gameAccount = anchor.web3.Keypair.generate();
+function u64Seed(n) {
+	return new anchor.BN(n).toArrayLike(Buffer, "le", 8);
+}
+
+// Games live at an address derived from their arcade and their index on it (the genesis game has index 0).
+async function findGameAddress(program, arcadeAccount, index) {
+	const [gameAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("game"), arcadeAccount.publicKey.toBuffer(), u64Seed(index)],
+		program.programId
+	);
+	return gameAddress;
+}
+
+async function findLeaderboardAddress(program, gameAccount) {
+	const [leaderboardAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("leaderboard"), gameAccount.publicKey.toBuffer()],
+		program.programId
+	);
+	return leaderboardAddress;
+}
+
+async function makeGame(program, provider, arcadeAccount, mostRecentGameAccount, numPlayers, gameType, scoreAuthority = provider.wallet.publicKey, leaderboardDepth = 10, price = null, operator = provider.wallet.publicKey) {
+	const arcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+	const gameAccount = { publicKey: await findGameAddress(program, arcadeAccount, arcade.gameCount) };
 	const title = "game title";
 	const webGLHash = "this is the webgl hash";
 	const gameArtHash = "this is the game art hash";
 	const gameWallet = anchor.web3.Keypair.generate();
+	const leaderboardAccount = await findLeaderboardAddress(program, gameAccount);
 
-	await program.rpc.createGame(title, webGLHash, gameArtHash, numPlayers, gameType, {
+	await program.rpc.createGame(title, webGLHash, gameArtHash, numPlayers, gameType, scoreAuthority, operator, leaderboardDepth, price, {
 		accounts: {
 			arcadeAccount: arcadeAccount.publicKey,
 			owner: provider.wallet.publicKey,
 			gameAccount: gameAccount.publicKey,
+			leaderboardAccount,
 			systemProgram: SystemProgram.programId,
 			mostRecentGameAccount: mostRecentGameAccount.publicKey,
 		},
-		signers: [gameAccount],
 	});
 
 	const game = await program.account.game.fetch(gameAccount.publicKey);
@@ -26,4 +49,7 @@ async function makeGame(program, provider, arcadeAccount, mostRecentGameAccount,
 
 module.exports = {
 	makeGame,
+	u64Seed,
+	findGameAddress,
+	findLeaderboardAddress,
 };
\ No newline at end of file
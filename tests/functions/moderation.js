@@ -0,0 +1,66 @@
+const { roleGrantAccounts } = require("./roles.js");
+
+// Runs one of the moderator instructions on a game, signed by the arcade's authority or by a wallet holding the moderator role.
+async function moderate(program, provider, instruction, args, gameAccount, moderator) {
+	const game = await program.account.game.fetch(gameAccount.publicKey);
+
+	await program.rpc[instruction](...args, {
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			arcadeAccount: game.arcade,
+			moderator: moderator ? moderator.publicKey : provider.wallet.publicKey,
+		},
+		remainingAccounts: moderator ? await roleGrantAccounts(program, { publicKey: game.arcade }, moderator.publicKey, "moderator") : [],
+		signers: moderator ? [moderator] : [],
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+async function reportGame(program, provider, gameAccount, reason, moderator = null) {
+	return moderate(program, provider, "reportGame", [reason], gameAccount, moderator);
+}
+
+async function dismissAppeal(program, provider, gameAccount, moderator = null) {
+	return moderate(program, provider, "dismissAppeal", [], gameAccount, moderator);
+}
+
+async function clearStrikes(program, provider, gameAccount, moderator = null) {
+	return moderate(program, provider, "clearStrikes", [], gameAccount, moderator);
+}
+
+async function appealGame(program, provider, gameAccount) {
+	await program.rpc.appealGame({
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			owner: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+async function setStrikeThreshold(program, provider, arcadeAccount, strikeThreshold) {
+	await program.rpc.setStrikeThreshold(strikeThreshold, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			authority: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+module.exports = {
+	reportGame,
+	appealGame,
+	dismissAppeal,
+	clearStrikes,
+	setStrikeThreshold,
+};
@@ -1,50 +1,188 @@
 const anchor = require("@project-serum/anchor");
 
-const { SystemProgram } = anchor.web3;
+const { SystemProgram, PublicKey } = anchor.web3;
+const { TOKEN_PROGRAM_ID } = anchor.utils.token;
+const { findLeaderboardAddress, u64Seed } = require("./makeGame.js");
+const { findPlayerProfileAddress } = require("./profiles.js");
+const { findTreasuryAddress } = require("./treasury.js");
 
-async function paybackFunds(program, provider, gameAccount, arcadeAccount) {
-	const potAccountOne = anchor.web3.Keypair.generate();
-	const potAccountTwo = anchor.web3.Keypair.generate();
-	const potAccountThree = anchor.web3.Keypair.generate();
+// Pots live at an address derived from their winner's wallet and their index among the wallet's pots.
+async function findGamePotAddress(program, walletKey, index) {
+	const [gamePotAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("game_pot"), walletKey.toBuffer(), u64Seed(index)],
+		program.programId
+	);
+	return gamePotAddress;
+}
+
+async function findClaimIndexAddress(program, walletKey) {
+	const [claimIndexAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("claim_index"), walletKey.toBuffer()],
+		program.programId
+	);
+	return claimIndexAddress;
+}
+
+// Fetches the pots of a wallet that have not been cashed out yet.
+async function fetchUnclaimedPots(program, walletKey) {
+	const claimIndex = await program.account.claimIndex.fetchNullable(await findClaimIndexAddress(program, walletKey));
+	const nextIndex = claimIndex == null ? 0 : claimIndex.nextIndex.toNumber();
+	const potKeys = await Promise.all([...Array(nextIndex)].map((_, index) => findGamePotAddress(program, walletKey, index)));
+	const pots = await program.account.gamePot.fetchMultiple(potKeys);
+
+	return potKeys.map((publicKey, index) => ({ publicKey, account: pots[index] })).filter((pot) => pot.account != null);
+}
+
+// Games with a token escrow also need { escrowAccount, ownerTokenAccount, treasuryTokenAccount } to pay out their tokens, where the
+// treasury's token account is owned by the arcade's treasury.
+async function paybackFunds(program, provider, gameAccount, arcadeAccount, numPots = 3, tokenAccounts = null) {
+	const leaderboardAccount = await findLeaderboardAddress(program, gameAccount);
+	const leaderboard = await program.account.leaderboard.fetch(leaderboardAccount);
+
+	// Each winner's pot gets the next index of the winner's claim index (0 for a wallet that never won before).
+	const winnerKeys = leaderboard.places.slice(0, numPots).map((place) => place.walletKey);
+	const claimIndexKeys = await Promise.all(winnerKeys.map((walletKey) => findClaimIndexAddress(program, walletKey)));
+	const claimIndexes = await program.account.claimIndex.fetchMultiple(claimIndexKeys);
+	const potAccounts = await Promise.all(winnerKeys.map(async (walletKey, rank) => ({
+		publicKey: await findGamePotAddress(program, walletKey, claimIndexes[rank] == null ? 0 : claimIndexes[rank].nextIndex),
+	})));
 
 	await program.rpc.paybackFunds({
 		accounts: {
-			playerOnePot: potAccountOne.publicKey,
-			playerTwoPot: potAccountTwo.publicKey,
-			playerThreePot: potAccountThree.publicKey,
 			gameAccount: gameAccount.publicKey,
+			leaderboardAccount,
 			arcadeAccount: arcadeAccount.publicKey,
+			treasury: await findTreasuryAddress(program, arcadeAccount.publicKey),
 			owner: provider.wallet.publicKey,
 			systemProgram: SystemProgram.programId,
 		},
-		signers: [potAccountOne, potAccountTwo, potAccountThree],
+		remainingAccounts: [
+			...potAccounts.map((pot) => ({ pubkey: pot.publicKey, isWritable: true, isSigner: false })),
+			...claimIndexKeys.map((claimIndexKey) => ({ pubkey: claimIndexKey, isWritable: true, isSigner: false })),
+			...(tokenAccounts == null ? [] : [
+				{ pubkey: tokenAccounts.escrowAccount.publicKey, isWritable: true, isSigner: false },
+				{ pubkey: tokenAccounts.ownerTokenAccount.publicKey, isWritable: true, isSigner: false },
+				{ pubkey: tokenAccounts.treasuryTokenAccount.publicKey, isWritable: true, isSigner: false },
+				{ pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+			]),
+		],
+	});
+
+	return {
+		potAccounts,
+		playerOnePotAccount: potAccounts[0],
+		playerTwoPotAccount: potAccounts[1],
+		playerThreePotAccount: potAccounts[2],
+	};
+}
+
+async function setPayoutSchedule(program, provider, gameAccount, payoutSchedule) {
+	await program.rpc.setPayoutSchedule(payoutSchedule, {
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			owner: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+async function setDefaultPayoutSchedule(program, provider, arcadeAccount, payoutSchedule) {
+	await program.rpc.setDefaultPayoutSchedule(payoutSchedule, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			authority: provider.wallet.publicKey,
+		},
 	});
 
-	return { playerOnePotAccount: potAccountOne, playerTwoPotAccount: potAccountTwo, playerThreePotAccount: potAccountThree };
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+// The profile of the winner on the pot's arcade records the win.
+async function winnerProfileAddress(program, winningWallet, potAccount) {
+	const pot = await program.account.gamePot.fetch(potAccount.publicKey);
+
+	return findPlayerProfileAddress(program, pot.arcade, winningWallet.publicKey);
 }
 
-async function cashOutPot(program, winningWallet, potAccount, previousPotAccount) {
+async function cashOutPotAccounts(program, winningWallet, potAccount) {
+	return {
+		gamePotAccount: potAccount.publicKey,
+		claimIndex: await findClaimIndexAddress(program, winningWallet.publicKey),
+		playerProfile: await winnerProfileAddress(program, winningWallet, potAccount),
+		winner: winningWallet.publicKey,
+		systemProgram: SystemProgram.programId,
+	};
+}
+
+async function cashOutPot(program, winningWallet, potAccount) {
 	await program.rpc.cashOutPot({
+		accounts: await cashOutPotAccounts(program, winningWallet, potAccount),
+		signers: [winningWallet],
+	});
+}
+
+async function cashOutTokenPot(program, winningWallet, potAccount, gameAccount, escrowAccount, winnerTokenAccount) {
+	await program.rpc.cashOutTokenPot({
 		accounts: {
-			gamePotAccount: potAccount.publicKey,
-			previousPotAccount: previousPotAccount.publicKey,
-			winner: winningWallet.publicKey,
-			systemProgram: SystemProgram.programId,
+			cashOut: await cashOutPotAccounts(program, winningWallet, potAccount),
+			payout: {
+				gameAccount: gameAccount.publicKey,
+				escrowAccount: escrowAccount.publicKey,
+				winnerTokenAccount: winnerTokenAccount.publicKey,
+				tokenProgram: TOKEN_PROGRAM_ID,
+			},
 		},
 		signers: [winningWallet],
 	});
 }
 
-async function cashOutMostRecentPot(program, winningWallet, arcadeAccount, potAccount) {
-	await program.rpc.cashOutMostRecentPot({
+// Cashes out all of the wallet's unclaimed pots on the arcade in one transaction.
+async function claimAll(program, winningWallet, arcadeAccount) {
+	const unclaimedPots = (await fetchUnclaimedPots(program, winningWallet.publicKey)).filter((pot) => pot.account.arcade.equals(arcadeAccount.publicKey));
+
+	await program.rpc.claimAll({
 		accounts: {
-			gamePotAccount: potAccount.publicKey,
-			arcadeState: arcadeAccount.publicKey,
+			claimIndex: await findClaimIndexAddress(program, winningWallet.publicKey),
+			playerProfile: await findPlayerProfileAddress(program, arcadeAccount.publicKey, winningWallet.publicKey),
 			winner: winningWallet.publicKey,
-			systemProgram: SystemProgram.programId,
 		},
+		remainingAccounts: unclaimedPots.map((pot) => ({ pubkey: pot.publicKey, isWritable: true, isSigner: false })),
 		signers: [winningWallet],
 	});
+
+	return { claimedPots: unclaimedPots };
+}
+
+// Sends a pot whose claim deadline passed back to its game (or into the arcade's treasury if the game was deleted).
+async function reclaimExpiredPot(program, potAccount) {
+	const pot = await program.account.gamePot.fetch(potAccount.publicKey);
+
+	await program.rpc.reclaimExpiredPot({
+		accounts: {
+			gamePotAccount: potAccount.publicKey,
+			gameAccount: pot.game,
+			treasury: await findTreasuryAddress(program, pot.arcade),
+			claimIndex: await findClaimIndexAddress(program, pot.winnerWallet),
+		},
+	});
+}
+
+async function setPotClaimWindow(program, provider, arcadeAccount, potClaimWindowSecs) {
+	await program.rpc.setPotClaimWindow(potClaimWindowSecs, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			authority: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
 }
 
 async function refillGameFunds(program, provider, gameAccount, lamports) {
@@ -58,8 +196,16 @@ async function refillGameFunds(program, provider, gameAccount, lamports) {
 }
 
 module.exports = {
+	findGamePotAddress,
+	findClaimIndexAddress,
+	fetchUnclaimedPots,
 	paybackFunds,
+	setPayoutSchedule,
+	setDefaultPayoutSchedule,
 	cashOutPot,
-	cashOutMostRecentPot,
+	cashOutTokenPot,
+	claimAll,
+	reclaimExpiredPot,
+	setPotClaimWindow,
 	refillGameFunds,
 };
\ No newline at end of file
@@ -0,0 +1,89 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram } = anchor.web3;
+
+// $20.00 a SOL, give or take a cent.
+const DEFAULT_PRICE = new anchor.BN(2000000000);
+const DEFAULT_CONF = new anchor.BN(1000000);
+const DEFAULT_EXPO = -8;
+
+async function makeManualPriceFeed(program, provider, price = DEFAULT_PRICE, conf = DEFAULT_CONF, expo = DEFAULT_EXPO) {
+	const priceFeedAccount = anchor.web3.Keypair.generate();
+
+	await program.rpc.initManualPriceFeed(price, conf, expo, {
+		accounts: {
+			priceFeed: priceFeedAccount.publicKey,
+			authority: provider.wallet.publicKey,
+			systemProgram: SystemProgram.programId,
+		},
+		signers: [priceFeedAccount],
+	});
+
+	return { priceFeedAccount };
+}
+
+async function updateManualPriceFeed(program, provider, priceFeedAccount, price, conf = DEFAULT_CONF, expo = DEFAULT_EXPO) {
+	await program.rpc.updateManualPriceFeed(price, conf, expo, {
+		accounts: {
+			priceFeed: priceFeedAccount.publicKey,
+			authority: provider.wallet.publicKey,
+		},
+	});
+}
+
+async function setPriceFeed(program, provider, arcadeAccount, priceFeedAccount, priceFeedKind = { manual: {} }, maxPriceAgeSecs = 600, maxPriceConfidenceBps = 100) {
+	await program.rpc.setPriceFeed(priceFeedKind, maxPriceAgeSecs, maxPriceConfidenceBps, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			priceFeed: priceFeedAccount.publicKey,
+			authority: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+async function setDefaultPrice(program, provider, arcadeAccount, price) {
+	await program.rpc.setDefaultPrice(price, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			authority: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+async function setGamePrice(program, provider, gameAccount, price) {
+	await program.rpc.setGamePrice(price, {
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			owner: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+// The accounts every instruction that charges the play fee needs.
+async function playFeeAccounts(program, gameAccount) {
+	const game = await program.account.game.fetch(gameAccount.publicKey);
+	const arcade = await program.account.arcadeState.fetch(game.arcade);
+
+	return { arcadeAccount: game.arcade, priceFeed: arcade.priceFeed };
+}
+
+module.exports = {
+	makeManualPriceFeed,
+	updateManualPriceFeed,
+	setPriceFeed,
+	setDefaultPrice,
+	setGamePrice,
+	playFeeAccounts,
+};
@@ -0,0 +1,98 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram, PublicKey } = anchor.web3;
+
+// Initials are stored padded to 3 characters with their letters upper cased.
+function padInitials(initials) {
+	return initials.replace(/[a-z]/g, (letter) => letter.toUpperCase()).padEnd(3, " ");
+}
+
+async function findPlayerProfileAddress(program, arcadeKey, walletKey) {
+	const [address] = await PublicKey.findProgramAddress(
+		[Buffer.from("profile"), arcadeKey.toBuffer(), walletKey.toBuffer()],
+		program.programId
+	);
+
+	return address;
+}
+
+// The profile every instruction that puts a wallet in a game's queues counts the play on, if the wallet has one.
+async function playerProfileAccounts(program, gameAccount, walletKey) {
+	const game = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { playerProfile: await findPlayerProfileAddress(program, game.arcade, walletKey) };
+}
+
+async function findInitialsClaimAddress(program, arcadeKey, initials) {
+	const [address] = await PublicKey.findProgramAddress(
+		[Buffer.from("initials"), arcadeKey.toBuffer(), Buffer.from(padInitials(initials))],
+		program.programId
+	);
+
+	return address;
+}
+
+// `wallet` is a keypair that pays for its own profile.
+async function createPlayerProfile(program, provider, arcadeKey, wallet, initials) {
+	const playerProfileKey = await findPlayerProfileAddress(program, arcadeKey, wallet.publicKey);
+
+	await program.rpc.createPlayerProfile(initials, {
+		accounts: {
+			playerProfile: playerProfileKey,
+			initialsClaim: await findInitialsClaimAddress(program, arcadeKey, initials),
+			arcadeAccount: arcadeKey,
+			wallet: wallet.publicKey,
+			systemProgram: SystemProgram.programId,
+		},
+		signers: [wallet],
+	});
+
+	const playerProfile = await program.account.playerProfile.fetch(playerProfileKey);
+
+	return { playerProfile, playerProfileKey };
+}
+
+async function changeInitials(program, provider, arcadeKey, wallet, initials) {
+	const playerProfileKey = await findPlayerProfileAddress(program, arcadeKey, wallet.publicKey);
+	const { initials: oldInitials } = await program.account.playerProfile.fetch(playerProfileKey);
+
+	await program.rpc.changeInitials(initials, {
+		accounts: {
+			playerProfile: playerProfileKey,
+			oldInitialsClaim: await findInitialsClaimAddress(program, arcadeKey, oldInitials),
+			newInitialsClaim: await findInitialsClaimAddress(program, arcadeKey, initials),
+			wallet: wallet.publicKey,
+			systemProgram: SystemProgram.programId,
+		},
+		signers: [wallet],
+	});
+
+	const playerProfile = await program.account.playerProfile.fetch(playerProfileKey);
+
+	return { playerProfile, playerProfileKey };
+}
+
+// Makes sure the wallet has a profile on the arcade with these initials, creating it or changing its initials when needed.
+async function ensurePlayerProfile(program, provider, arcadeKey, wallet, initials) {
+	const playerProfileKey = await findPlayerProfileAddress(program, arcadeKey, wallet.publicKey);
+	const playerProfile = await program.account.playerProfile.fetchNullable(playerProfileKey);
+
+	if (playerProfile == null) {
+		return createPlayerProfile(program, provider, arcadeKey, wallet, initials);
+	}
+	if (playerProfile.initials != padInitials(initials)) {
+		return changeInitials(program, provider, arcadeKey, wallet, initials);
+	}
+
+	return { playerProfile, playerProfileKey };
+}
+
+module.exports = {
+	padInitials,
+	findPlayerProfileAddress,
+	playerProfileAccounts,
+	findInitialsClaimAddress,
+	createPlayerProfile,
+	changeInitials,
+	ensurePlayerProfile,
+};
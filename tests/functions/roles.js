@@ -0,0 +1,83 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram, PublicKey } = anchor.web3;
+
+// The byte each role adds to the address of its grants, in the order the roles are declared in the program.
+const ROLE_SEEDS = { moderator: 0, curator: 1, treasurer: 2 };
+
+// Roles are passed by name ("moderator", "curator" or "treasurer").
+async function findRoleGrantAddress(program, arcadeKey, walletKey, role) {
+	const [roleGrantAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("role"), arcadeKey.toBuffer(), walletKey.toBuffer(), Buffer.from([ROLE_SEEDS[role]])],
+		program.programId
+	);
+	return roleGrantAddress;
+}
+
+// The remaining accounts a role holder passes to the admin instructions of their role.
+async function roleGrantAccounts(program, arcadeAccount, walletKey, role) {
+	return [{ pubkey: await findRoleGrantAddress(program, arcadeAccount.publicKey, walletKey, role), isWritable: false, isSigner: false }];
+}
+
+async function grantRole(program, provider, arcadeAccount, walletKey, role) {
+	const roleGrant = await findRoleGrantAddress(program, arcadeAccount.publicKey, walletKey, role);
+
+	await program.rpc.grantRole(walletKey, { [role]: {} }, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			roleGrant,
+			authority: provider.wallet.publicKey,
+			systemProgram: SystemProgram.programId,
+		},
+	});
+
+	return { roleGrant };
+}
+
+async function revokeRole(program, provider, arcadeAccount, walletKey, role) {
+	await program.rpc.revokeRole({
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			roleGrant: await findRoleGrantAddress(program, arcadeAccount.publicKey, walletKey, role),
+			authority: provider.wallet.publicKey,
+		},
+	});
+}
+
+// Proposes a new authority for an arcade (null withdraws the proposal), signed by its current authority.
+async function proposeAuthority(program, provider, arcadeAccount, newAuthorityKey, authority = null) {
+	await program.rpc.proposeAuthority(newAuthorityKey, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			authority: authority ? authority.publicKey : provider.wallet.publicKey,
+		},
+		signers: authority ? [authority] : [],
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+async function acceptAuthority(program, arcadeAccount, newAuthority) {
+	await program.rpc.acceptAuthority({
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			newAuthority: newAuthority.publicKey,
+		},
+		signers: [newAuthority],
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+module.exports = {
+	findRoleGrantAddress,
+	roleGrantAccounts,
+	grantRole,
+	revokeRole,
+	proposeAuthority,
+	acceptAuthority,
+};
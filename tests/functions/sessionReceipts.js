@@ -0,0 +1,33 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram, Transaction } = anchor.web3;
+const { initGameQueue } = require("./initQueue.js");
+const { finishGameQueue } = require("./finishQueue.js");
+
+// Fetches the session receipts of a wallet's sessions of a game that have not been used up by a score yet.
+async function fetchSessionReceipts(program, gameAccount, walletKey) {
+	return program.account.sessionReceipt.all([
+		{ memcmp: { offset: 8, bytes: gameAccount.publicKey.toBase58() } },
+		{ memcmp: { offset: 40, bytes: walletKey.toBase58() } },
+	]);
+}
+
+// Plays a quick session of a game whose queues are not in use for `wallet` (a keypair funded by the provider), which leaves the
+// wallet with a session receipt.
+async function playSession(program, provider, gameAccount, wallet) {
+	await provider.sendAndConfirm(new Transaction().add(SystemProgram.transfer({
+		fromPubkey: provider.wallet.publicKey,
+		toPubkey: wallet.publicKey,
+		lamports: 100000000,
+	})));
+
+	const { playerAccount } = await initGameQueue(program, provider, gameAccount, wallet);
+	await finishGameQueue(program, provider, gameAccount);
+
+	return { playerAccount };
+}
+
+module.exports = {
+	fetchSessionReceipts,
+	playSession,
+};
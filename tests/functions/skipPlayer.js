@@ -0,0 +1,42 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram } = anchor.web3;
+const { findSessionReceiptAddress } = require("./gameQueues.js");
+
+async function setMaxSession(program, provider, gameAccount, maxSessionSecs) {
+	await program.rpc.setMaxSession(maxSessionSecs, {
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			owner: provider.wallet.publicKey,
+		},
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { updatedGame };
+}
+
+// Skips the current player of a queue whose session ran out, paying the crank reward to the provider's wallet.
+async function skipIdlePlayer(program, provider, gameAccount, gameQueueAccount) {
+	const gameQueue = await program.account.gameQueue.fetch(gameQueueAccount.publicKey);
+
+	await program.rpc.skipIdlePlayer({
+		accounts: {
+			playerAccount: gameQueue.currentPlayer,
+			sessionReceipt: await findSessionReceiptAddress(program, gameQueue.currentPlayer),
+			gameQueueAccount: gameQueueAccount.publicKey,
+			gameAccount: gameAccount.publicKey,
+			caller: provider.wallet.publicKey,
+			systemProgram: SystemProgram.programId,
+		},
+	});
+
+	const updatedGameQueue = await program.account.gameQueue.fetch(gameQueueAccount.publicKey);
+
+	return { skippedPlayer: gameQueue.currentPlayer, updatedGameQueue };
+}
+
+module.exports = {
+	setMaxSession,
+	skipIdlePlayer,
+};
@@ -0,0 +1,89 @@
+const anchor = require("@project-serum/anchor");
+
+const { SystemProgram, PublicKey, SYSVAR_RENT_PUBKEY } = anchor.web3;
+const { TOKEN_PROGRAM_ID } = anchor.utils.token;
+
+async function createMint(provider, decimals = 0) {
+	const tokenProgram = anchor.Spl.token(provider);
+	const mintAccount = anchor.web3.Keypair.generate();
+
+	await tokenProgram.methods
+		.initializeMint(decimals, provider.wallet.publicKey, null)
+		.accounts({ mint: mintAccount.publicKey, rent: SYSVAR_RENT_PUBKEY })
+		.preInstructions([await tokenProgram.account.mint.createInstruction(mintAccount)])
+		.signers([mintAccount])
+		.rpc();
+
+	return { mintAccount };
+}
+
+async function createTokenAccount(provider, mintAccount, owner, amount = 0) {
+	const tokenProgram = anchor.Spl.token(provider);
+	const tokenAccount = anchor.web3.Keypair.generate();
+
+	await tokenProgram.methods
+		.initializeAccount()
+		.accounts({ account: tokenAccount.publicKey, mint: mintAccount.publicKey, authority: owner, rent: SYSVAR_RENT_PUBKEY })
+		.preInstructions([await tokenProgram.account.token.createInstruction(tokenAccount)])
+		.signers([tokenAccount])
+		.rpc();
+
+	if (amount > 0) {
+		await tokenProgram.methods
+			.mintTo(new anchor.BN(amount))
+			.accounts({ mint: mintAccount.publicKey, to: tokenAccount.publicKey, authority: provider.wallet.publicKey })
+			.rpc();
+	}
+
+	return { tokenAccount };
+}
+
+async function tokenBalance(provider, tokenAccount) {
+	const balance = await provider.connection.getTokenAccountBalance(tokenAccount.publicKey);
+	return Number(balance.value.amount);
+}
+
+async function findEscrowAddress(program, gameAccount) {
+	const [escrowAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("escrow"), gameAccount.publicKey.toBuffer()],
+		program.programId
+	);
+	return escrowAddress;
+}
+
+async function initTokenEscrow(program, provider, gameAccount, mintAccount) {
+	const escrowAccount = await findEscrowAddress(program, gameAccount);
+
+	await program.rpc.initTokenEscrow({
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			escrowAccount,
+			mint: mintAccount.publicKey,
+			owner: provider.wallet.publicKey,
+			tokenProgram: TOKEN_PROGRAM_ID,
+			systemProgram: SystemProgram.programId,
+		},
+	});
+
+	const updatedGame = await program.account.game.fetch(gameAccount.publicKey);
+
+	return { escrowAccount: { publicKey: escrowAccount }, updatedGame };
+}
+
+// The accounts every instruction that takes a play fee in tokens needs.
+async function tokenPaymentAccounts(program, gameAccount, payerTokenAccount) {
+	return {
+		payerTokenAccount: payerTokenAccount.publicKey,
+		escrowAccount: await findEscrowAddress(program, gameAccount),
+		tokenProgram: TOKEN_PROGRAM_ID,
+	};
+}
+
+module.exports = {
+	createMint,
+	createTokenAccount,
+	tokenBalance,
+	findEscrowAddress,
+	initTokenEscrow,
+	tokenPaymentAccounts,
+};
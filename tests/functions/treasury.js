@@ -0,0 +1,70 @@
+const anchor = require("@project-serum/anchor");
+
+const { PublicKey } = anchor.web3;
+const { TOKEN_PROGRAM_ID } = anchor.utils.token;
+
+// An arcade's treasury lives at an address derived from the arcade.
+async function findTreasuryAddress(program, arcadeKey) {
+	const [treasuryAddress] = await PublicKey.findProgramAddress(
+		[Buffer.from("treasury"), arcadeKey.toBuffer()],
+		program.programId
+	);
+	return treasuryAddress;
+}
+
+// The lamports in a treasury are the ones above its rent.
+async function treasuryBalance(program, provider, arcadeAccount) {
+	const info = await provider.connection.getAccountInfo(await findTreasuryAddress(program, arcadeAccount.publicKey));
+	const rent = await provider.connection.getMinimumBalanceForRentExemption(info.data.length);
+
+	return info.lamports - rent;
+}
+
+async function setArcadeFee(program, provider, arcadeAccount, arcadeFeeBps, authority = null) {
+	await program.rpc.setArcadeFee(arcadeFeeBps, {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			authority: authority ? authority.publicKey : provider.wallet.publicKey,
+		},
+		signers: authority ? [authority] : [],
+	});
+
+	const updatedArcade = await program.account.arcadeState.fetch(arcadeAccount.publicKey);
+
+	return { updatedArcade };
+}
+
+async function withdrawTreasury(program, provider, arcadeAccount, lamports, destination, authority = null) {
+	await program.rpc.withdrawTreasury(new anchor.BN(lamports), {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			treasury: await findTreasuryAddress(program, arcadeAccount.publicKey),
+			destination,
+			authority: authority ? authority.publicKey : provider.wallet.publicKey,
+		},
+		signers: authority ? [authority] : [],
+	});
+
+	return { balance: await treasuryBalance(program, provider, arcadeAccount) };
+}
+
+async function withdrawTreasuryTokens(program, provider, arcadeAccount, amount, treasuryTokenAccount, destinationTokenAccount) {
+	await program.rpc.withdrawTreasuryTokens(new anchor.BN(amount), {
+		accounts: {
+			arcadeAccount: arcadeAccount.publicKey,
+			treasury: await findTreasuryAddress(program, arcadeAccount.publicKey),
+			treasuryTokenAccount: treasuryTokenAccount.publicKey,
+			destinationTokenAccount: destinationTokenAccount.publicKey,
+			tokenProgram: TOKEN_PROGRAM_ID,
+			authority: provider.wallet.publicKey,
+		},
+	});
+}
+
+module.exports = {
+	findTreasuryAddress,
+	treasuryBalance,
+	setArcadeFee,
+	withdrawTreasury,
+	withdrawTreasuryTokens,
+};
@@ -1,13 +1,73 @@
 const anchor = require("@project-serum/anchor");
 
-const { SystemProgram } = anchor.web3;
+const { SystemProgram, Ed25519Program, SYSVAR_INSTRUCTIONS_PUBKEY, PublicKey } = anchor.web3;
+const { findLeaderboardAddress } = require("./makeGame.js");
+const { fetchSessionReceipts, playSession } = require("./sessionReceipts.js");
+const { ensurePlayerProfile } = require("./profiles.js");
 
-async function updateLeaderboard(program, provider, gameAccount, playerName, score, walletKey) {
-	await program.rpc.updateLeaderboard(playerName, score, walletKey.publicKey, {
+let nextNonce = Math.floor(Math.random() * 1000000000);
+
+function scoreAttestationMessage(gameAccount, walletKey, score, nonce) {
+	return Buffer.concat([
+		gameAccount.publicKey.toBuffer(),
+		walletKey.publicKey.toBuffer(),
+		score.toArrayLike(Buffer, "le", 16),
+		nonce.toArrayLike(Buffer, "le", 8),
+	]);
+}
+
+// Every score uses up one of the wallet's session receipts, a wallet without one plays a quick session first.  The score is
+// shown with the initials of the wallet's profile, which is created (or has its initials changed) to `playerName` first.
+async function updateLeaderboard(program, provider, gameAccount, playerName, score, walletKey, scoreAuthority = provider.wallet.payer, nonce = null) {
+	if (nonce == null) {
+		nonce = new anchor.BN(nextNonce++);
+	}
+
+	let sessionReceipts = await fetchSessionReceipts(program, gameAccount, walletKey.publicKey);
+	if (sessionReceipts.length == 0) {
+		await playSession(program, provider, gameAccount, walletKey.payer ?? walletKey);
+		sessionReceipts = await fetchSessionReceipts(program, gameAccount, walletKey.publicKey);
+	}
+
+	const game = await program.account.game.fetch(gameAccount.publicKey);
+	const { playerProfileKey } = await ensurePlayerProfile(program, provider, game.arcade, walletKey.payer ?? walletKey, playerName);
+
+	const [scoreNonceAccount] = await PublicKey.findProgramAddress(
+		[Buffer.from("score_nonce"), gameAccount.publicKey.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
+		program.programId
+	);
+
+	const leaderboardAccount = await findLeaderboardAddress(program, gameAccount);
+
+	const attestation = Ed25519Program.createInstructionWithPrivateKey({
+		privateKey: scoreAuthority.secretKey,
+		message: scoreAttestationMessage(gameAccount, walletKey, score, nonce),
+	});
+
+	await program.rpc.updateLeaderboard(score, walletKey.publicKey, nonce, {
 		accounts: {
 			gameAccount: gameAccount.publicKey,
-			authority: provider.wallet.publicKey,
+			leaderboardAccount,
+			scoreNonceAccount,
+			sessionReceipt: sessionReceipts[0].publicKey,
+			wallet: walletKey.publicKey,
+			playerProfile: playerProfileKey,
+			instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+			payer: provider.wallet.publicKey,
 			systemProgram: SystemProgram.programId,
+		},
+		instructions: [attestation],
+	});
+
+	const updatedLeaderboard = await program.account.leaderboard.fetch(leaderboardAccount);
+	return { updatedLeaderboard, nonce };
+}
+
+async function setScoreAuthority(program, provider, gameAccount, scoreAuthority) {
+	await program.rpc.setScoreAuthority(scoreAuthority, {
+		accounts: {
+			gameAccount: gameAccount.publicKey,
+			owner: provider.wallet.publicKey,
 		}
 	});
 
@@ -17,4 +77,5 @@ async function updateLeaderboard(program, provider, gameAccount, playerName, sco
 
 module.exports = {
 	updateLeaderboard,
-}
\ No newline at end of file
+	setScoreAuthority,
+}